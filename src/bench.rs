@@ -0,0 +1,388 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{self, Write},
+    path::Path,
+    str::FromStr,
+    time::Duration,
+};
+
+use crate::{
+    error::{parse_token, Error, Result},
+    report::format_duration,
+    runner,
+    solution::{Answer, Part, Solution},
+};
+
+/// Summary of the timed runs of one part
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    /// Sample standard deviation, zero for a single run
+    pub stddev: Duration,
+}
+
+impl Stats {
+    /// None when there are no samples
+    pub fn new(samples: &[Duration]) -> Option<Stats> {
+        let runs = samples.len();
+        let mut sorted = samples.iter().map(|d| d.as_secs_f64()).collect::<Vec<_>>();
+        sorted.sort_by(f64::total_cmp);
+        let min = *sorted.first()?;
+        let median = if runs % 2 == 1 {
+            sorted[runs / 2]
+        } else {
+            (sorted[runs / 2 - 1] + sorted[runs / 2]) / 2.0
+        };
+        let mean = sorted.iter().sum::<f64>() / runs as f64;
+        let variance = if runs > 1 {
+            sorted.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (runs - 1) as f64
+        } else {
+            0.0
+        };
+        Some(Stats {
+            runs,
+            min: Duration::from_secs_f64(min),
+            median: Duration::from_secs_f64(median),
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        })
+    }
+}
+
+/// Timings of one part of one day
+#[derive(Debug, Clone)]
+pub struct BenchResult {
+    pub day: u8,
+    pub part: Part,
+    pub answer: Answer,
+    pub stats: Stats,
+    pub input_hash: u64,
+}
+
+/// Solve a part `warmup` times untimed and then `runs` times timed
+///
+/// Every run must give the same answer, a solver that doesn't is reported as
+/// an error since its timings can't be compared.
+pub fn bench_part(
+    day: u8,
+    solution: &dyn Solution,
+    part: Part,
+    input: &str,
+    warmup: usize,
+    runs: usize,
+) -> Result<BenchResult> {
+    if runs == 0 {
+        return Err(Error::new("need at least one timed run").for_day(day));
+    }
+    for _ in 0..warmup {
+        runner::run_part(day, solution, part, input)?;
+    }
+    let results = (0..runs)
+        .map(|_| runner::run_part(day, solution, part, input))
+        .collect::<Result<Vec<_>>>()?;
+    let answer = results[0].answer;
+    if let Some(other) = results.iter().find(|r| r.answer != answer) {
+        return Err(Error::new(format!(
+            "part {} answered both {} and {}",
+            part, answer, other.answer
+        ))
+        .for_day(day));
+    }
+    let samples = results.iter().map(|r| r.duration).collect::<Vec<_>>();
+    Ok(BenchResult {
+        day,
+        part,
+        answer,
+        stats: Stats::new(&samples).unwrap(),
+        input_hash: results[0].input_hash,
+    })
+}
+
+/// Timings saved by an earlier bench run
+///
+/// The file is CSV with a header row and one row per day and part, in
+/// nanoseconds:
+///
+/// ```text
+/// day,part,input_hash,runs,min_ns,median_ns,mean_ns,stddev_ns
+/// 14,b,00000000000000ff,10,2400000,2500000,2510000,30000
+/// ```
+#[derive(Debug, Default)]
+pub struct Baseline {
+    entries: BTreeMap<(u8, Part), (u64, Stats)>,
+}
+
+const BASELINE_HEADER: &str = "day,part,input_hash,runs,min_ns,median_ns,mean_ns,stddev_ns";
+
+impl Baseline {
+    /// A missing file is an empty baseline
+    pub fn load(path: &Path) -> std::result::Result<Baseline, String> {
+        if !path.exists() {
+            return Ok(Baseline::default());
+        }
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("could not read baseline {}: {}", path.display(), e))?;
+        text.parse::<Baseline>()
+            .map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut out = Vec::new();
+        self.write(&mut out)?;
+        fs::write(path, out)
+    }
+
+    pub fn write(&self, out: &mut impl Write) -> io::Result<()> {
+        writeln!(out, "{}", BASELINE_HEADER)?;
+        for ((day, part), (input_hash, stats)) in &self.entries {
+            writeln!(
+                out,
+                "{},{},{:016x},{},{},{},{},{}",
+                day,
+                part,
+                input_hash,
+                stats.runs,
+                stats.min.as_nanos(),
+                stats.median.as_nanos(),
+                stats.mean.as_nanos(),
+                stats.stddev.as_nanos()
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Input hash and timings of a part, if it has been benchmarked
+    pub fn get(&self, day: u8, part: Part) -> Option<&(u64, Stats)> {
+        self.entries.get(&(day, part))
+    }
+
+    /// Replace the timings of a part with those of a newer run
+    pub fn update(&mut self, result: &BenchResult) {
+        self.entries
+            .insert((result.day, result.part), (result.input_hash, result.stats));
+    }
+}
+
+impl FromStr for Baseline {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
+        let mut entries = BTreeMap::new();
+        for (iline, line) in s.lines().enumerate() {
+            if line.trim().is_empty() || line == BASELINE_HEADER {
+                continue;
+            }
+            let parse_line = || {
+                let fields = line.split(',').collect::<Vec<_>>();
+                if fields.len() != 8 {
+                    return Err(Error::new(format!(
+                        "expected 8 fields, found {}",
+                        fields.len()
+                    )));
+                }
+                let day = parse_token::<u8>(line, fields[0])?;
+                let part = parse_token::<Part>(line, fields[1])?;
+                let input_hash = u64::from_str_radix(fields[2], 16).map_err(|_| {
+                    Error::new(format!("could not parse hash `{}`", fields[2]))
+                        .at_token(line, fields[2])
+                })?;
+                let nanos = |field: &str| parse_token::<u64>(line, field).map(Duration::from_nanos);
+                let stats = Stats {
+                    runs: parse_token::<usize>(line, fields[3])?,
+                    min: nanos(fields[4])?,
+                    median: nanos(fields[5])?,
+                    mean: nanos(fields[6])?,
+                    stddev: nanos(fields[7])?,
+                };
+                Ok(((day, part), (input_hash, stats)))
+            };
+            let (key, entry) = parse_line().map_err(|e: Error| e.at_line(iline + 1))?;
+            entries.insert(key, entry);
+        }
+        Ok(Baseline { entries })
+    }
+}
+
+/// How a result compares with the baseline
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Comparison {
+    /// The part has not been benchmarked before
+    New,
+    /// The baseline was taken on different input, so the timings can't be
+    /// compared
+    OtherInput,
+    /// Median time relative to the baseline's, above 1 is slower
+    Ratio(f64),
+}
+
+/// Compare a result with the baseline's median
+pub fn compare(result: &BenchResult, baseline: &Baseline) -> Comparison {
+    match baseline.get(result.day, result.part) {
+        None => Comparison::New,
+        Some((input_hash, _)) if *input_hash != result.input_hash => Comparison::OtherInput,
+        Some((_, stats)) => Comparison::Ratio(
+            result.stats.median.as_secs_f64() / stats.median.as_secs_f64().max(f64::MIN_POSITIVE),
+        ),
+    }
+}
+
+/// Whether a comparison is more than `threshold` slower, e.g. 0.1 for 10%
+pub fn is_regression(comparison: Comparison, threshold: f64) -> bool {
+    matches!(comparison, Comparison::Ratio(ratio) if ratio > 1.0 + threshold)
+}
+
+/// Write a table of results, each compared with the baseline
+pub fn write_report(
+    out: &mut impl Write,
+    results: &[BenchResult],
+    baseline: &Baseline,
+    threshold: f64,
+) -> io::Result<()> {
+    writeln!(
+        out,
+        "day part runs {:>12} {:>12} {:>12} {:>12}  baseline",
+        "min", "median", "mean", "stddev"
+    )?;
+    for result in results {
+        let stats = &result.stats;
+        let comparison = compare(result, baseline);
+        let change = match comparison {
+            Comparison::New => "new".to_string(),
+            Comparison::OtherInput => "other input".to_string(),
+            Comparison::Ratio(ratio) => {
+                let percent = (ratio - 1.0) * 100.0;
+                if is_regression(comparison, threshold) {
+                    format!("{:+.1}% SLOWER", percent)
+                } else {
+                    format!("{:+.1}%", percent)
+                }
+            }
+        };
+        writeln!(
+            out,
+            "{:>3} {:>4} {:>4} {:>12} {:>12} {:>12} {:>12}  {}",
+            result.day,
+            result.part,
+            stats.runs,
+            format_duration(&stats.min),
+            format_duration(&stats.median),
+            format_duration(&stats.mean),
+            format_duration(&stats.stddev),
+            change
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{compare, is_regression, write_report, Baseline, BenchResult, Comparison, Stats};
+    use crate::solution::{self, Answer, Part};
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    fn result(part: Part, median: Duration, input_hash: u64) -> BenchResult {
+        BenchResult {
+            day: 14,
+            part,
+            answer: Answer::Unsigned(1),
+            stats: Stats {
+                runs: 3,
+                min: median,
+                median,
+                mean: median,
+                stddev: Duration::ZERO,
+            },
+            input_hash,
+        }
+    }
+
+    #[test]
+    fn test_stats() {
+        let stats = Stats::new(&[ms(4), ms(2), ms(6), ms(8)]).unwrap();
+        assert_eq!(stats.runs, 4);
+        assert_eq!(stats.min, ms(2));
+        assert_eq!(stats.median, ms(5));
+        assert_eq!(stats.mean, ms(5));
+        // sample variance of 2, 4, 6, 8 is 20 / 3
+        let stddev = stats.stddev.as_secs_f64() * 1000.0;
+        assert!((stddev - (20.0_f64 / 3.0).sqrt()).abs() < 1e-6);
+
+        let stats = Stats::new(&[ms(3)]).unwrap();
+        assert_eq!(stats.median, ms(3));
+        assert_eq!(stats.stddev, Duration::ZERO);
+        assert!(Stats::new(&[]).is_none());
+    }
+
+    #[test]
+    fn test_baseline() {
+        let mut baseline = Baseline::default();
+        baseline.update(&result(Part::B, ms(10), 0xff));
+        let mut out = Vec::new();
+        baseline.write(&mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(
+            text,
+            "day,part,input_hash,runs,min_ns,median_ns,mean_ns,stddev_ns
+14,b,00000000000000ff,3,10000000,10000000,10000000,0
+"
+        );
+        let parsed = text.parse::<Baseline>().unwrap();
+        assert_eq!(parsed.get(14, Part::B), baseline.get(14, Part::B));
+        assert_eq!(parsed.get(14, Part::A), None);
+
+        let error = "14,b,ff,3,1,2,3,x".parse::<Baseline>().unwrap_err();
+        assert_eq!(error.to_string(), "line 1, column 17: could not parse `x`");
+        assert!("14,b,ff,3".parse::<Baseline>().is_err());
+    }
+
+    #[test]
+    fn test_compare() {
+        let mut baseline = Baseline::default();
+        baseline.update(&result(Part::A, ms(10), 0xff));
+
+        let slower = compare(&result(Part::A, ms(12), 0xff), &baseline);
+        assert!(matches!(slower, Comparison::Ratio(r) if (r - 1.2).abs() < 1e-9));
+        assert!(is_regression(slower, 0.1));
+        assert!(!is_regression(slower, 0.25));
+
+        let faster = compare(&result(Part::A, ms(5), 0xff), &baseline);
+        assert!(!is_regression(faster, 0.1));
+
+        let other = compare(&result(Part::A, ms(50), 0xaa), &baseline);
+        assert_eq!(other, Comparison::OtherInput);
+        assert!(!is_regression(other, 0.1));
+        let new = compare(&result(Part::B, ms(50), 0xff), &baseline);
+        assert_eq!(new, Comparison::New);
+
+        let mut out = Vec::new();
+        write_report(
+            &mut out,
+            &[result(Part::A, ms(12), 0xff), result(Part::B, ms(1), 0xff)],
+            &baseline,
+            0.1,
+        )
+        .unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let lines = out.lines().collect::<Vec<_>>();
+        assert!(lines[1].ends_with("  +20.0% SLOWER"));
+        assert!(lines[2].ends_with("  new"));
+    }
+
+    #[test]
+    fn test_bench_part() {
+        let day_9 = solution::get(9).unwrap();
+        let result = super::bench_part(9, day_9, Part::A, "0 3 6 9 12 15", 1, 3).unwrap();
+        assert_eq!(result.answer, Answer::Signed(18));
+        assert_eq!(result.stats.runs, 3);
+        assert!(result.stats.min <= result.stats.median);
+        assert!(super::bench_part(9, day_9, Part::A, "0 3 6", 0, 0).is_err());
+    }
+}
@@ -1,7 +1,17 @@
-pub fn day_1() {
-    let day_1_data = include_str!("day_1_data.txt");
-    println!("day 1a {}", day_1a_faster_fc(day_1_data));
-    println!("day 1b {}", day_1b(day_1_data));
+use crate::{
+    error::Result,
+    solution::{Answer, Solution},
+};
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    fn part_a(&self, input: &str) -> Result<Answer> {
+        Ok(day_1a_faster_fc(input).into())
+    }
+    fn part_b(&self, input: &str) -> Result<Answer> {
+        Ok(day_1b(input).into())
+    }
 }
 
 fn day_1a_proc_line(line: &str) -> u32 {
@@ -146,11 +156,9 @@ fn day_1b(input: &str) -> u32 {
                     sum += 90;
                     break;
                 }
-                'o' => {
-                    if line.len() - pos >= 3 && &line[pos + 1..pos + 3] == "ne" {
-                        sum += 10;
-                        break;
-                    }
+                'o' if line.len() - pos >= 3 && &line[pos + 1..pos + 3] == "ne" => {
+                    sum += 10;
+                    break;
                 }
                 't' => {
                     if line.len() - pos >= 3 && &line[pos + 1..pos + 3] == "wo" {
@@ -182,17 +190,13 @@ fn day_1b(input: &str) -> u32 {
                         break;
                     }
                 }
-                'e' => {
-                    if line.len() - pos >= 5 && &line[pos + 1..pos + 5] == "ight" {
-                        sum += 80;
-                        break;
-                    }
+                'e' if line.len() - pos >= 5 && &line[pos + 1..pos + 5] == "ight" => {
+                    sum += 80;
+                    break;
                 }
-                'n' => {
-                    if line.len() - pos >= 4 && &line[pos + 1..pos + 4] == "ine" {
-                        sum += 90;
-                        break;
-                    }
+                'n' if line.len() - pos >= 4 && &line[pos + 1..pos + 4] == "ine" => {
+                    sum += 90;
+                    break;
                 }
                 _ => (),
             }
@@ -254,35 +258,25 @@ fn day_1b(input: &str) -> u32 {
                         break;
                     }
                 }
-                'o' => {
-                    if line.len() - pos >= 3 && &line[pos + 1..pos + 3] == "wt" {
-                        sum += 2;
-                        break;
-                    }
+                'o' if line.len() - pos >= 3 && &line[pos + 1..pos + 3] == "wt" => {
+                    sum += 2;
+                    break;
                 }
-                'r' => {
-                    if line.len() - pos >= 4 && &line[pos + 1..pos + 4] == "uof" {
-                        sum += 4;
-                        break;
-                    }
+                'r' if line.len() - pos >= 4 && &line[pos + 1..pos + 4] == "uof" => {
+                    sum += 4;
+                    break;
                 }
-                'x' => {
-                    if line.len() - pos >= 3 && &line[pos + 1..pos + 3] == "is" {
-                        sum += 6;
-                        break;
-                    }
+                'x' if line.len() - pos >= 3 && &line[pos + 1..pos + 3] == "is" => {
+                    sum += 6;
+                    break;
                 }
-                'n' => {
-                    if line.len() - pos >= 5 && &line[pos + 1..pos + 5] == "eves" {
-                        sum += 7;
-                        break;
-                    }
+                'n' if line.len() - pos >= 5 && &line[pos + 1..pos + 5] == "eves" => {
+                    sum += 7;
+                    break;
                 }
-                't' => {
-                    if line.len() - pos >= 5 && &line[pos + 1..pos + 5] == "hgie" {
-                        sum += 8;
-                        break;
-                    }
+                't' if line.len() - pos >= 5 && &line[pos + 1..pos + 5] == "hgie" => {
+                    sum += 8;
+                    break;
                 }
                 _ => (),
             }
@@ -1,47 +1,31 @@
 use std::{collections::BTreeSet, str::FromStr};
-use strum::IntoEnumIterator;
-use strum_macros::EnumIter;
 
-pub fn run(input: &str) -> u64 {
-    input.parse::<Maze>().unwrap().count_inside()
+use crate::{
+    error::{Error, Result},
+    geometry::{Direction, Point2},
+    grid::Grid,
+};
+
+pub fn run(input: &str) -> Result<u64> {
+    input.parse::<Maze>()?.count_inside()
 }
 
 struct Maze {
-    nrows: usize,
-    ncols: usize,
-    map: Vec<Vec<char>>,
+    map: Grid<char>,
 }
 
 impl FromStr for Maze {
-    type Err = &'static str;
-    fn from_str(input: &str) -> Result<Self, <Self as FromStr>::Err> {
-        let map = input
-            .lines()
-            .map(|line| line.chars().collect::<Vec<_>>())
-            .collect::<Vec<_>>();
-        assert!(map.iter().all(|line| line.len() == map[0].len()));
-        Ok(Maze {
-            nrows: map.len(),
-            ncols: map[0].len(),
-            map,
-        })
-    }
-}
-
-#[derive(Ord, PartialOrd, Eq, Clone, Copy, Debug, EnumIter, PartialEq)]
-enum Direction {
-    N,
-    E,
-    S,
-    W,
-}
-
-fn opposite_direction(direction: &Direction) -> Direction {
-    match direction {
-        Direction::N => Direction::S,
-        Direction::E => Direction::W,
-        Direction::S => Direction::N,
-        Direction::W => Direction::E,
+    type Err = Error;
+    fn from_str(input: &str) -> Result<Self> {
+        let map = Grid::parse_with(input, |c| match c {
+            'S' | '|' | '-' | 'L' | 'J' | '7' | 'F' | '.' => Ok(c),
+            _ => Err(Error::new(format!("bad tile `{}`", c))),
+        })?;
+        let starts = map.positions(|c| *c == 'S').count();
+        if starts != 1 {
+            return Err(Error::new(format!("expected one start, found {}", starts)));
+        }
+        Ok(Maze { map })
     }
 }
 
@@ -53,11 +37,17 @@ fn get_directions(c: &char) -> Option<(Direction, Direction)> {
         'J' => Some((Direction::N, Direction::W)),
         '7' => Some((Direction::S, Direction::W)),
         'F' => Some((Direction::S, Direction::E)),
-        '.' => None,
-        _ => panic!("got unexpect val {}", c),
+        _ => None,
     }
 }
 
+/// The pipe connecting `a` and `b`, if they aren't the same way
+fn tile_joining(a: Direction, b: Direction) -> Option<char> {
+    ['|', '-', 'L', 'J', '7', 'F']
+        .into_iter()
+        .find(|tile| tile_supports_dir(tile, &a) && tile_supports_dir(tile, &b) && a != b)
+}
+
 fn tile_supports_dir(tile: &char, direction: &Direction) -> bool {
     let Some((d1, d2)) = get_directions(tile) else {
         return false;
@@ -72,43 +62,19 @@ fn can_go_to_tile(tile: &char, direction: &Direction) -> bool {
     if *tile == 'S' {
         return true;
     }
-    tile_supports_dir(tile, &opposite_direction(direction))
+    tile_supports_dir(tile, &direction.opposite())
 }
 
 struct MazeWalker<'a> {
     maze: &'a Maze,
-    pos: (usize, usize),
+    pos: Point2<usize>,
     last_direction: Option<Direction>,
 }
 
 impl<'a> MazeWalker<'a> {
     fn get_tile(&self, direction: &Direction) -> Option<char> {
-        match direction {
-            Direction::N => {
-                if self.pos.0 == 0 {
-                    return None;
-                }
-                return Some(self.maze.map[self.pos.0 - 1][self.pos.1]);
-            }
-            Direction::E => {
-                if self.pos.1 == self.maze.ncols - 1 {
-                    return None;
-                }
-                return Some(self.maze.map[self.pos.0][self.pos.1 + 1]);
-            }
-            Direction::S => {
-                if self.pos.0 == self.maze.nrows - 1 {
-                    return None;
-                }
-                return Some(self.maze.map[self.pos.0 + 1][self.pos.1]);
-            }
-            Direction::W => {
-                if self.pos.1 == 0 {
-                    return None;
-                }
-                return Some(self.maze.map[self.pos.0][self.pos.1 - 1]);
-            }
-        }
+        let position = self.pos.step(*direction)?;
+        self.maze.map.get(position.index()).copied()
     }
 
     fn can_move(&self, direction: &Direction) -> bool {
@@ -118,127 +84,82 @@ impl<'a> MazeWalker<'a> {
         can_go_to_tile(&tile, direction)
     }
 
-    fn new(maze: &Maze) -> MazeWalker {
-        MazeWalker {
+    fn new(maze: &Maze) -> Result<MazeWalker<'_>> {
+        Ok(MazeWalker {
             maze,
-            pos: maze.find_start().expect("No start!"),
+            pos: maze.find_start()?,
             last_direction: None,
-        }
+        })
+    }
+
+    /// Error located at the walker's tile
+    fn error(&self, message: &str) -> Error {
+        Error::new(message)
+            .at_line(self.pos.row + 1)
+            .at_column(self.pos.col + 1)
     }
 
     fn advance_position(&mut self, direction: &Direction) {
-        self.pos = match direction {
-            Direction::N => (self.pos.0 - 1, self.pos.1),
-            Direction::E => (self.pos.0, self.pos.1 + 1),
-            Direction::S => (self.pos.0 + 1, self.pos.1),
-            Direction::W => (self.pos.0, self.pos.1 - 1),
-        };
+        self.pos = self.pos.step(*direction).unwrap();
         self.last_direction = Some(*direction);
     }
 
-    fn sub_start(&self) -> char {
-        assert_eq!(self.last_direction, None);
-        let dirs = Direction::iter()
-            .filter(|d| self.can_move(d))
-            .collect::<BTreeSet<Direction>>();
-        assert_eq!(2, dirs.len());
-        if dirs.contains(&Direction::N) {
-            if dirs.contains(&Direction::E) {
-                return 'L';
-            } else if dirs.contains(&Direction::W) {
-                return 'J';
-            } else if dirs.contains(&Direction::S) {
-                return '|';
-            } else {
-                panic!();
-            }
-        } else if dirs.contains(&Direction::S) {
-            if dirs.contains(&Direction::E) {
-                return 'F';
-            } else if dirs.contains(&Direction::W) {
-                return '7';
-            } else {
-                panic!();
-            }
-        } else if dirs.contains(&Direction::E) && dirs.contains(&Direction::W) {
-            return '-';
-        }
-        panic!();
-    }
-    fn make_move(&mut self) -> Option<()> {
-        match self.last_direction {
+    /// Take a step along the loop, true once it's back at the start
+    fn make_move(&mut self) -> Result<bool> {
+        let direction = match self.last_direction {
             Some(ld) => {
-                let tile = &self.maze.map[self.pos.0][self.pos.1];
-                let directions = get_directions(tile).expect("Not on a valid tile!");
-                let back = opposite_direction(&ld);
-                let direction = if directions.0 == back {
-                    directions.1
-                } else if directions.1 == back {
-                    directions.0
-                } else {
-                    panic!(
-                        "directions {:?} from tile {:?} did not match back {:?}",
-                        directions, tile, back
-                    )
-                };
-                assert!(self.can_move(&direction));
-                self.advance_position(&direction);
-                if self.maze.map[self.pos.0][self.pos.1] == 'S' {
-                    return None;
-                }
-            }
-            None => {
-                for dir in Direction::iter() {
-                    if self.can_move(&dir) {
-                        self.advance_position(&dir);
-                        break;
-                    }
+                let back = ld.opposite();
+                match get_directions(&self.maze.map[self.pos.index()]) {
+                    Some((d1, d2)) if d1 == back => d2,
+                    Some((d1, d2)) if d2 == back => d1,
+                    _ => return Err(self.error("the loop is broken")),
                 }
             }
+            None => Direction::ALL
+                .into_iter()
+                .find(|d| self.can_move(d))
+                .ok_or_else(|| self.error("no pipe connects to the start"))?,
+        };
+        if !self.can_move(&direction) {
+            return Err(self.error(&format!("the pipe leads {} to a dead end", direction)));
         }
-        Some(())
+        self.advance_position(&direction);
+        Ok(self.maze.map[self.pos.index()] == 'S')
     }
 }
 
 impl Maze {
-    fn find_start(&self) -> Result<(usize, usize), &'static str> {
-        for (irow, row) in self.map.iter().enumerate() {
-            for (icol, col) in row.iter().enumerate() {
-                if *col == 'S' {
-                    return Ok((irow, icol));
-                }
-            }
-        }
-        Err("No start")
-    }
-
-    fn sub_start(&self) -> char {
-        MazeWalker::new(self).sub_start()
+    fn find_start(&self) -> Result<Point2<usize>> {
+        let start = self.map.positions(|c| *c == 'S').next().ok_or("No start")?;
+        Ok(Point2::from(start))
     }
 
-    fn count_inside(&self) -> u64 {
-        let mut walker = MazeWalker::new(self);
+    fn count_inside(&self) -> Result<u64> {
+        let mut walker = MazeWalker::new(self)?;
         let mut path = BTreeSet::new();
         path.insert(walker.pos);
-        loop {
-            match walker.make_move() {
-                Some(_) => (),
-                None => {
-                    break;
-                }
-            }
+        walker.make_move()?;
+        path.insert(walker.pos);
+        let first = walker.last_direction;
+        while !walker.make_move()? {
             path.insert(walker.pos);
         }
         path.insert(walker.pos);
+        // the pipe under the start joins where the loop leaves and returns
+        let start = match (first, walker.last_direction) {
+            (Some(first), Some(last)) => tile_joining(first, last.opposite()),
+            _ => None,
+        }
+        .ok_or_else(|| walker.error("expected a loop through the start"))?;
         let mut count = 0;
-        for irow in 0..self.nrows {
+        for irow in 0..self.map.nrows() {
             let mut crossings = 0;
             let mut on_ridge = false;
             let mut ridge_start = None;
-            for icol in 0..self.ncols {
-                let pos = (irow, icol);
-                let tile = self.map[pos.0][pos.1];
-                let tile = if tile == 'S' { self.sub_start() } else { tile };
+            for icol in 0..self.map.ncols() {
+                let pos = Point2::new(irow, icol);
+                let tile = self.map[pos.index()];
+                let tile = if tile == 'S' { start } else { tile };
                 if path.contains(&pos) {
                     match tile {
                         '|' => {
@@ -305,7 +226,7 @@ impl Maze {
             }
             assert!(!on_ridge);
         }
-        count
+        Ok(count)
     }
 }
 
@@ -314,16 +235,21 @@ mod tests {
     #[test]
     fn test2() {
         let input = include_str!("example_data_2.txt");
-        assert_eq!(super::run(input), 4);
+        assert_eq!(super::run(input).unwrap(), 4);
+        let error = super::run("S-7\n|.|\nL-L").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 3, column 3: the pipe leads E to a dead end"
+        );
     }
     #[test]
     fn test3() {
         let input = include_str!("example_data_3.txt");
-        assert_eq!(super::run(input), 8);
+        assert_eq!(super::run(input).unwrap(), 8);
     }
     #[test]
     fn test4() {
         let input = include_str!("example_data_4.txt");
-        assert_eq!(super::run(input), 10);
+        assert_eq!(super::run(input).unwrap(), 10);
     }
 }
@@ -1,8 +1,18 @@
+use crate::{
+    error::Result,
+    solution::{Answer, Solution},
+};
+
 mod a;
 mod b;
 
-pub fn run() {
-    let input = include_str!("data.txt");
-    println!("day 10 a {}", a::run(input));
-    println!("day 10 b {}", b::run(input));
+pub struct Day10;
+
+impl Solution for Day10 {
+    fn part_a(&self, input: &str) -> Result<Answer> {
+        Ok(a::run(input)?.into())
+    }
+    fn part_b(&self, input: &str) -> Result<Answer> {
+        Ok(b::run(input)?.into())
+    }
 }
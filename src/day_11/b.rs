@@ -1,5 +1,7 @@
 use std::{collections::BTreeSet, str::FromStr};
 
+use crate::error::{at_char, Error, Result};
+
 #[derive(Debug)]
 struct Point {
     row: usize,
@@ -24,16 +26,21 @@ impl Universe {
 }
 
 impl FromStr for Universe {
-    type Err = &'static str;
-    fn from_str(input: &str) -> Result<Self, <Self as FromStr>::Err> {
+    type Err = Error;
+    fn from_str(input: &str) -> Result<Self> {
         let mut points = Vec::new();
         for (row, line) in input.lines().enumerate() {
             for (col, c) in line.chars().enumerate() {
-                if c == '#' {
-                    points.push(Point::new(row, col));
+                match c {
+                    '#' => points.push(Point::new(row, col)),
+                    '.' => (),
+                    _ => return Err(at_char(col, format!("bad char `{}`", c)).at_line(row + 1)),
                 }
             }
         }
+        if points.is_empty() {
+            return Err(Error::new("no galaxies"));
+        }
         Ok(Universe::new(points))
     }
 }
@@ -91,11 +98,8 @@ impl Universe {
     }
 }
 
-pub fn run(input: &str, factor: u64) -> u64 {
-    input
-        .parse::<Universe>()
-        .unwrap()
-        .calc_distances_sum(factor)
+pub fn run(input: &str, factor: u64) -> Result<u64> {
+    Ok(input.parse::<Universe>()?.calc_distances_sum(factor))
 }
 
 #[cfg(test)]
@@ -103,11 +107,11 @@ mod tests {
     #[test]
     fn test1() {
         let input = include_str!("example_data.txt");
-        assert_eq!(super::run(input, 10), 1030);
+        assert_eq!(super::run(input, 10).unwrap(), 1030);
     }
     #[test]
     fn test2() {
         let input = include_str!("example_data.txt");
-        assert_eq!(super::run(input, 100), 8410);
+        assert_eq!(super::run(input, 100).unwrap(), 8410);
     }
 }
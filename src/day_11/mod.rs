@@ -1,8 +1,18 @@
+use crate::{
+    error::Result,
+    solution::{Answer, Solution},
+};
+
 mod a;
 mod b;
 
-pub fn run() {
-    let input = include_str!("data.txt");
-    println!("day 11 a {}", a::run(input));
-    println!("day 11 b {}", b::run(input, 1000000));
+pub struct Day11;
+
+impl Solution for Day11 {
+    fn part_a(&self, input: &str) -> Result<Answer> {
+        Ok(a::run(input)?.into())
+    }
+    fn part_b(&self, input: &str) -> Result<Answer> {
+        Ok(b::run(input, 1000000)?.into())
+    }
 }
@@ -2,6 +2,8 @@ use std::str::FromStr;
 
 use itertools::{Combinations, Itertools};
 
+use crate::error::{parse_chars, parse_lines, parse_token, Error, Result};
+
 #[derive(Clone, Copy, PartialEq, Debug)]
 enum Condition {
     Operational,
@@ -10,13 +12,13 @@ enum Condition {
 }
 
 impl TryFrom<char> for Condition {
-    type Error = &'static str;
-    fn try_from(c: char) -> Result<Self, <Self as TryFrom<char>>::Error> {
+    type Error = Error;
+    fn try_from(c: char) -> Result<Self> {
         match c {
             '.' => Ok(Condition::Operational),
             '#' => Ok(Condition::Damaged),
             '?' => Ok(Condition::Unknown),
-            _ => Err("Unknown char"),
+            _ => Err(Error::new(format!("Unknown char `{}`", c))),
         }
     }
 }
@@ -65,7 +67,7 @@ impl SpringRecordIterator {
         }
     }
 
-    fn check(&self, condition: &Vec<Condition>) -> bool {
+    fn check(&self, condition: &[Condition]) -> bool {
         let groups = condition
             .split(|&c| c == Condition::Operational)
             .map(|c| c.len())
@@ -85,9 +87,7 @@ impl Iterator for SpringRecordIterator {
     type Item = Vec<Condition>;
     fn next(&mut self) -> Option<<Self as Iterator>::Item> {
         loop {
-            let Some(loc) = self.combinations.next() else {
-                return None;
-            };
+            let loc = self.combinations.next()?;
             let mut out = self.record.clone();
             loc.iter().for_each(|&i| out[i] = Condition::Damaged);
             if self.check(&out) {
@@ -98,17 +98,14 @@ impl Iterator for SpringRecordIterator {
 }
 
 impl FromStr for SpringRecord {
-    type Err = &'static str;
-    fn from_str(line: &str) -> Result<Self, <Self as FromStr>::Err> {
-        let (record, groups) = line.split_once(" ").unwrap();
-        let record = record
-            .chars()
-            .map(|c| Condition::try_from(c).unwrap())
-            .collect::<Vec<_>>();
+    type Err = Error;
+    fn from_str(line: &str) -> Result<Self> {
+        let (record, groups) = line.split_once(' ').ok_or("expected a record and groups")?;
+        let record = parse_chars::<Condition>(record)?;
         let groups = groups
-            .split(",")
-            .map(|num| num.parse::<u32>().unwrap())
-            .collect::<Vec<_>>();
+            .split(',')
+            .map(|num| parse_token::<u32>(line, num))
+            .collect::<Result<Vec<_>>>()?;
         Ok(SpringRecord { record, groups })
     }
 }
@@ -123,12 +120,11 @@ impl SpringRecord {
     }
 }
 
-pub fn run(input: &str) -> usize {
-    input
-        .lines()
-        .map(|line| line.parse::<SpringRecord>().unwrap())
+pub fn run(input: &str) -> Result<usize> {
+    Ok(parse_lines::<SpringRecord>(input)?
+        .iter()
         .map(|sr| sr.count_solutions())
-        .sum()
+        .sum())
 }
 
 #[cfg(test)]
@@ -136,6 +132,8 @@ mod tests {
     #[test]
     fn test1() {
         let input = include_str!("example_data.txt");
-        assert_eq!(super::run(input), 21);
+        assert_eq!(super::run(input).unwrap(), 21);
+        let error = super::run("#.# 1,1\n#?x 1").unwrap_err();
+        assert_eq!(error.to_string(), "line 2, column 3: Unknown char `x`");
     }
 }
@@ -1,6 +1,8 @@
 use rayon::prelude::*;
 use simple_tqdm::ParTqdm;
-use std::{str::FromStr, time::Instant};
+use std::str::FromStr;
+
+use crate::error::{parse_chars, parse_lines, parse_token, Error, Result};
 
 #[derive(Clone, Copy, PartialEq, Debug)]
 enum Condition {
@@ -10,13 +12,13 @@ enum Condition {
 }
 
 impl TryFrom<char> for Condition {
-    type Error = &'static str;
-    fn try_from(c: char) -> Result<Self, <Self as TryFrom<char>>::Error> {
+    type Error = Error;
+    fn try_from(c: char) -> Result<Self> {
         match c {
             '.' => Ok(Condition::Opr),
             '#' => Ok(Condition::Dam),
             '?' => Ok(Condition::Unk),
-            _ => Err("Unknown char"),
+            _ => Err(Error::new(format!("Unknown char `{}`", c))),
         }
     }
 }
@@ -107,17 +109,14 @@ struct SpringRecord {
 }
 
 impl FromStr for SpringRecord {
-    type Err = &'static str;
-    fn from_str(line: &str) -> Result<Self, <Self as FromStr>::Err> {
-        let (record, groups) = line.split_once(" ").unwrap();
-        let record = record
-            .chars()
-            .map(|c| Condition::try_from(c).unwrap())
-            .collect::<Vec<_>>();
+    type Err = Error;
+    fn from_str(line: &str) -> Result<Self> {
+        let (record, groups) = line.split_once(' ').ok_or("expected a record and groups")?;
+        let record = parse_chars::<Condition>(record)?;
         let groups = groups
-            .split(",")
-            .map(|num| num.parse::<usize>().unwrap())
-            .collect::<Vec<_>>();
+            .split(',')
+            .map(|num| parse_token::<usize>(line, num))
+            .collect::<Result<Vec<_>>>()?;
         Ok(SpringRecord::new(record, groups))
     }
 }
@@ -140,7 +139,7 @@ impl SpringRecord {
     }
 
     fn count_solutions(&self) -> usize {
-        self.count_solutions_inner(&mut Solution::new(self))
+        self.count_solutions_inner(&Solution::new(self))
     }
 
     fn count_solutions_inner(&self, solution: &Solution) -> usize {
@@ -153,61 +152,30 @@ impl SpringRecord {
                 count += self.count_solutions_inner(&s);
             }
         }
-        return count;
-    }
-}
-
-pub enum RunMode {
-    Fast,
-    Time,
-}
-
-pub fn run(input: &str, mode: RunMode) -> usize {
-    match mode {
-        RunMode::Fast => run_fast(input),
-        RunMode::Time => run_time(input),
+        count
     }
 }
 
-pub fn run_fast(input: &str) -> usize {
-    let records = input
-        .lines()
-        .map(|line| line.parse::<SpringRecord>().unwrap().multiply(5))
+pub fn run(input: &str) -> Result<usize> {
+    let records = parse_lines::<SpringRecord>(input)?
+        .iter()
+        .map(|sr| sr.multiply(5))
         .collect::<Vec<_>>();
-    records
+    Ok(records
         .par_iter()
         .tqdm()
         .map(|sr| sr.count_solutions())
-        .sum()
-}
-
-pub fn run_time(input: &str) -> usize {
-    let records = input
-        .lines()
-        .map(|line| line.parse::<SpringRecord>().unwrap().multiply(5))
-        .collect::<Vec<_>>();
-    records
-        .iter()
-        .enumerate()
-        .map(|(i, sr)| {
-            let now = Instant::now();
-            let count = sr.count_solutions();
-            println!("{} took {}", i, now.elapsed().as_secs_f32());
-            count
-        })
-        .sum()
+        .sum())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::day_12::b::RunMode;
-
     use super::SpringRecord;
 
     #[test]
     fn test1() {
         let input = include_str!("example_data.txt");
-        assert_eq!(super::run(input, RunMode::Fast), 525152);
+        assert_eq!(super::run(input).unwrap(), 525152);
     }
     #[test]
     fn test_trivial_1() {
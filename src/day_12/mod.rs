@@ -1,10 +1,18 @@
-use self::b::RunMode;
+use crate::{
+    error::Result,
+    solution::{Answer, Solution},
+};
 
 mod a;
-pub mod b;
+mod b;
 
-pub fn run(mode: RunMode) {
-    let input = include_str!("data.txt");
-    println!("day 12 a {}", a::run(input));
-    println!("day 12 b {}", b::run(input, mode));
+pub struct Day12;
+
+impl Solution for Day12 {
+    fn part_a(&self, input: &str) -> Result<Answer> {
+        Ok(a::run(input)?.into())
+    }
+    fn part_b(&self, input: &str) -> Result<Answer> {
+        Ok(b::run(input)?.into())
+    }
 }
@@ -4,46 +4,42 @@ use std::{
     str::FromStr,
 };
 
-#[derive(Debug, Hash)]
+use crate::{
+    error::{Error, Result},
+    grid::Grid,
+};
+
+#[derive(Debug, Clone, Hash)]
 enum Pix {
     Roc,
     Ash,
 }
 
 impl TryFrom<char> for Pix {
-    type Error = &'static str;
-    fn try_from(value: char) -> Result<Self, Self::Error> {
+    type Error = Error;
+    fn try_from(value: char) -> Result<Self> {
         match value {
             '.' => Ok(Pix::Ash),
             '#' => Ok(Pix::Roc),
-            _ => Err("Invalid char"),
+            _ => Err(Error::new(format!("Invalid char `{}`", value))),
         }
     }
 }
 
 #[derive(Debug)]
 struct Pattern {
-    pix: Vec<Vec<Pix>>,
+    pix: Grid<Pix>,
 }
 
 impl FromStr for Pattern {
-    type Err = &'static str;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Pattern {
-            pix: s
-                .lines()
-                .map(|line| {
-                    line.chars()
-                        .map(|c| Pix::try_from(c).unwrap())
-                        .collect::<Vec<_>>()
-                })
-                .collect::<Vec<_>>(),
-        })
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(Pattern { pix: s.parse()? })
     }
 }
 
 impl Pattern {
-    fn find_mirror(seq: &Vec<u64>) -> Option<usize> {
+    fn find_mirror(seq: &[u64]) -> Option<usize> {
         let starts = seq
             .windows(2)
             .enumerate()
@@ -61,9 +57,10 @@ impl Pattern {
         }
         None
     }
-    fn hash_rows(&self) -> Vec<u64> {
-        self.pix
-            .iter()
+    fn hash_lines(pix: &Grid<Pix>) -> Vec<u64> {
+        pix.as_array()
+            .rows()
+            .into_iter()
             .map(|row| {
                 let mut s = DefaultHasher::new();
                 row.iter().for_each(|p| {
@@ -73,26 +70,20 @@ impl Pattern {
             })
             .collect::<Vec<_>>()
     }
+    fn hash_rows(&self) -> Vec<u64> {
+        Pattern::hash_lines(&self.pix)
+    }
     fn hash_cols(&self) -> Vec<u64> {
-        let mut hashers = self.pix[0]
-            .iter()
-            .map(|_| DefaultHasher::new())
-            .collect::<Vec<_>>();
-        self.pix.iter().for_each(|row| {
-            row.iter()
-                .zip(hashers.iter_mut())
-                .for_each(|(p, s)| p.hash(s))
-        });
-        hashers.iter().map(|s| s.finish()).collect::<Vec<_>>()
+        Pattern::hash_lines(&self.pix.transpose())
     }
-    fn summarize(&self) -> usize {
+    fn summarize(&self) -> Result<usize> {
         if let Some(row) = Pattern::find_mirror(&self.hash_rows()) {
-            return row * 100;
+            return Ok(row * 100);
         };
         if let Some(col) = Pattern::find_mirror(&self.hash_cols()) {
-            return col;
+            return Ok(col);
         };
-        panic!()
+        Err(Error::new("no line of reflection"))
     }
 }
 
@@ -102,31 +93,47 @@ struct Patterns {
 }
 
 impl Patterns {
-    fn summarize(&self) -> usize {
-        self.patterns.iter().map(|p| p.summarize()).sum()
+    fn summarize(&self) -> Result<usize> {
+        self.patterns
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                p.summarize()
+                    .map_err(|e| Error::new(format!("pattern {}: {}", i + 1, e.message)))
+            })
+            .sum()
     }
 }
 
 impl FromStr for Patterns {
-    type Err = &'static str;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
         let mut patterns = Vec::new();
         let mut lines = Vec::new();
-        for line in s.lines() {
-            if line.len() > 0 {
+        // number of lines before the pattern being collected
+        let mut start = 0;
+        let parse = |lines: &[&str], start: usize| {
+            lines
+                .join("\n")
+                .parse::<Pattern>()
+                .map_err(|e| e.offset_lines(start))
+        };
+        for (iline, line) in s.lines().enumerate() {
+            if !line.is_empty() {
                 lines.push(line);
             } else {
-                patterns.push(lines.join("\n").parse::<Pattern>().unwrap());
-                lines.clear()
+                patterns.push(parse(&lines, start)?);
+                lines.clear();
+                start = iline + 1;
             }
         }
-        patterns.push(lines.join("\n").parse::<Pattern>().unwrap());
+        patterns.push(parse(&lines, start)?);
         Ok(Patterns { patterns })
     }
 }
 
-pub fn run(input: &str) -> usize {
-    input.parse::<Patterns>().unwrap().summarize()
+pub fn run(input: &str) -> Result<usize> {
+    input.parse::<Patterns>()?.summarize()
 }
 
 #[cfg(test)]
@@ -134,6 +141,10 @@ mod tests {
     #[test]
     fn test1() {
         let input = include_str!("example_data.txt");
-        assert_eq!(super::run(input), 405);
+        assert_eq!(super::run(input).unwrap(), 405);
+        let error = super::run("#.\n.#\n\n##\n#o").unwrap_err();
+        assert_eq!(error.to_string(), "line 5, column 2: Invalid char `o`");
+        let error = super::run("#.#\n#.#\n\n#.#\n...").unwrap_err();
+        assert_eq!(error.to_string(), "pattern 2: no line of reflection");
     }
 }
@@ -4,6 +4,11 @@ use std::{
     str::FromStr,
 };
 
+use crate::{
+    error::{Error, Result},
+    grid::Grid,
+};
+
 #[derive(Debug, Copy, Clone, Hash)]
 enum Pix {
     Roc,
@@ -11,39 +16,30 @@ enum Pix {
 }
 
 impl TryFrom<char> for Pix {
-    type Error = &'static str;
-    fn try_from(value: char) -> Result<Self, Self::Error> {
+    type Error = Error;
+    fn try_from(value: char) -> Result<Self> {
         match value {
             '.' => Ok(Pix::Ash),
             '#' => Ok(Pix::Roc),
-            _ => Err("Invalid char"),
+            _ => Err(Error::new(format!("Invalid char `{}`", value))),
         }
     }
 }
 
 #[derive(Debug, Clone)]
 struct Pattern {
-    pix: Vec<Vec<Pix>>,
+    pix: Grid<Pix>,
 }
 
 impl FromStr for Pattern {
-    type Err = &'static str;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Pattern {
-            pix: s
-                .lines()
-                .map(|line| {
-                    line.chars()
-                        .map(|c| Pix::try_from(c).unwrap())
-                        .collect::<Vec<_>>()
-                })
-                .collect::<Vec<_>>(),
-        })
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(Pattern { pix: s.parse()? })
     }
 }
 
 impl Pattern {
-    fn find_mirror(seq: &Vec<u64>, skip: Option<usize>) -> Option<usize> {
+    fn find_mirror(seq: &[u64], skip: Option<usize>) -> Option<usize> {
         let starts = seq
             .windows(2)
             .enumerate()
@@ -68,9 +64,10 @@ impl Pattern {
         }
         None
     }
-    fn hash_rows(&self) -> Vec<u64> {
-        self.pix
-            .iter()
+    fn hash_lines(pix: &Grid<Pix>) -> Vec<u64> {
+        pix.as_array()
+            .rows()
+            .into_iter()
             .map(|row| {
                 let mut s = DefaultHasher::new();
                 row.iter().for_each(|p| {
@@ -80,59 +77,53 @@ impl Pattern {
             })
             .collect::<Vec<_>>()
     }
+    fn hash_rows(&self) -> Vec<u64> {
+        Pattern::hash_lines(&self.pix)
+    }
     fn hash_cols(&self) -> Vec<u64> {
-        let mut hashers = self.pix[0]
-            .iter()
-            .map(|_| DefaultHasher::new())
-            .collect::<Vec<_>>();
-        self.pix.iter().for_each(|row| {
-            row.iter()
-                .zip(hashers.iter_mut())
-                .for_each(|(p, s)| p.hash(s))
-        });
-        hashers.iter().map(|s| s.finish()).collect::<Vec<_>>()
+        Pattern::hash_lines(&self.pix.transpose())
     }
 
     fn swap(&mut self, irow: usize, icol: usize) {
-        let col = &mut self.pix[irow][icol];
+        let col = &mut self.pix[[irow, icol]];
         *col = match *col {
             Pix::Ash => Pix::Roc,
             Pix::Roc => Pix::Ash,
         };
     }
 
-    fn summarize(&self) -> usize {
+    fn summarize(&self) -> Result<usize> {
         if let Some(row) = Pattern::find_mirror(&self.hash_rows(), None) {
-            return row * 100;
+            return Ok(row * 100);
         };
         if let Some(col) = Pattern::find_mirror(&self.hash_cols(), None) {
-            return col;
+            return Ok(col);
         };
-        panic!()
+        Err(Error::new("no line of reflection"))
     }
 
-    fn summarize_smudge(&self) -> usize {
-        let s = self.summarize();
-        for (irow, row) in self.pix.iter().enumerate() {
-            for (icol, _col) in row.iter().enumerate() {
+    fn summarize_smudge(&self) -> Result<usize> {
+        let s = self.summarize()?;
+        for irow in 0..self.pix.nrows() {
+            for icol in 0..self.pix.ncols() {
                 let c = &mut self.clone();
                 c.swap(irow, icol);
                 if let Some(row) = Pattern::find_mirror(&c.hash_rows(), Some(s / 100)) {
                     let val = row * 100;
-                    // println!("irow {} icol {} row {} val {}", irow, icol, row, val);
                     if val != s {
-                        return val;
+                        return Ok(val);
                     }
                 };
                 if let Some(col) = Pattern::find_mirror(&c.hash_cols(), Some(s)) {
-                    // println!("irow {} icol {} col {}", irow, icol, col);
                     if col != s {
-                        return col;
+                        return Ok(col);
                     }
                 };
             }
         }
-        panic!()
+        Err(Error::new(
+            "no other line of reflection with a smudge fixed",
+        ))
     }
 }
 
@@ -142,36 +133,47 @@ struct Patterns {
 }
 
 impl Patterns {
-    fn summarize(&self) -> usize {
+    fn summarize(&self) -> Result<usize> {
         self.patterns
             .iter()
             .enumerate()
-            // .inspect(|(i, _)| println!("process map {}", i))
-            .map(|(_, p)| p.summarize_smudge())
+            .map(|(i, p)| {
+                p.summarize_smudge()
+                    .map_err(|e| Error::new(format!("pattern {}: {}", i + 1, e.message)))
+            })
             .sum()
     }
 }
 
 impl FromStr for Patterns {
-    type Err = &'static str;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
         let mut patterns = Vec::new();
         let mut lines = Vec::new();
-        for line in s.lines() {
-            if line.len() > 0 {
+        // number of lines before the pattern being collected
+        let mut start = 0;
+        let parse = |lines: &[&str], start: usize| {
+            lines
+                .join("\n")
+                .parse::<Pattern>()
+                .map_err(|e| e.offset_lines(start))
+        };
+        for (iline, line) in s.lines().enumerate() {
+            if !line.is_empty() {
                 lines.push(line);
             } else {
-                patterns.push(lines.join("\n").parse::<Pattern>().unwrap());
-                lines.clear()
+                patterns.push(parse(&lines, start)?);
+                lines.clear();
+                start = iline + 1;
             }
         }
-        patterns.push(lines.join("\n").parse::<Pattern>().unwrap());
+        patterns.push(parse(&lines, start)?);
         Ok(Patterns { patterns })
     }
 }
 
-pub fn run(input: &str) -> usize {
-    input.parse::<Patterns>().unwrap().summarize()
+pub fn run(input: &str) -> Result<usize> {
+    input.parse::<Patterns>()?.summarize()
 }
 
 #[cfg(test)]
@@ -181,7 +183,14 @@ mod tests {
     #[test]
     fn test1() {
         let input = include_str!("example_data.txt");
-        assert_eq!(super::run(input), 400);
+        assert_eq!(super::run(input).unwrap(), 400);
+        let error = super::run("#.#\n#.#\n\n#.#\n...").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "pattern 1: no other line of reflection with a smudge fixed"
+        );
+        let error = super::run("#.#\n...").unwrap_err();
+        assert_eq!(error.to_string(), "pattern 1: no line of reflection");
     }
     #[test]
     fn trouble_case() {
@@ -203,7 +212,7 @@ mod tests {
 ......#.#.##.#...
 ..#.####.##.####."#;
         let p = input.parse::<Pattern>().unwrap();
-        println!("summarize {}", p.summarize());
-        println!("summarize smudge {}", p.summarize_smudge());
+        assert_eq!(p.summarize().unwrap(), 1);
+        assert_eq!(p.summarize_smudge().unwrap(), 10);
     }
 }
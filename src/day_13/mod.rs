@@ -1,8 +1,18 @@
+use crate::{
+    error::Result,
+    solution::{Answer, Solution},
+};
+
 mod a;
 mod b;
 
-pub fn run() {
-    let input = include_str!("data.txt");
-    println!("day 13 a {}", a::run(input));
-    println!("day 13 b {}", b::run(input));
+pub struct Day13;
+
+impl Solution for Day13 {
+    fn part_a(&self, input: &str) -> Result<Answer> {
+        Ok(a::run(input)?.into())
+    }
+    fn part_b(&self, input: &str) -> Result<Answer> {
+        Ok(b::run(input)?.into())
+    }
 }
@@ -1,5 +1,11 @@
 use std::str::FromStr;
 
+use crate::{
+    error::{Error, Result},
+    geometry::Direction,
+    grid::Grid,
+};
+
 #[derive(Debug, PartialEq, Eq)]
 enum Rock {
     R,
@@ -7,49 +13,35 @@ enum Rock {
     N,
 }
 impl TryFrom<char> for Rock {
-    type Error = &'static str;
-    fn try_from(value: char) -> Result<Self, Self::Error> {
+    type Error = Error;
+    fn try_from(value: char) -> Result<Self> {
         match value {
             'O' => Ok(Rock::R),
             '.' => Ok(Rock::N),
             '#' => Ok(Rock::S),
-            _ => Err("Unknown Rock"),
+            _ => Err(Error::new(format!("Unknown Rock `{}`", value))),
         }
     }
 }
 struct RockField {
-    rocks: Vec<Vec<Rock>>,
+    rocks: Grid<Rock>,
 }
 impl FromStr for RockField {
-    type Err = &'static str;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let rocks = s
-            .lines()
-            .map(|line| {
-                line.chars()
-                    .map(|c| Rock::try_from(c).unwrap())
-                    .collect::<Vec<_>>()
-            })
-            .collect::<Vec<_>>();
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
+        let rocks = s.parse::<Grid<Rock>>()?;
         Ok(RockField { rocks })
     }
 }
-#[derive(Debug, PartialEq, Eq)]
-enum Direction {
-    N,
-    // E,
-    // S,
-    // W,
-}
 impl RockField {
     fn roll(&mut self, direction: Direction) -> bool {
         assert_eq!(direction, Direction::N);
         let mut changed = false;
-        for irow in 1..self.rocks.len() {
-            for icol in 0..self.rocks[0].len() {
-                if self.rocks[irow][icol] == Rock::R && self.rocks[irow - 1][icol] == Rock::N {
-                    self.rocks[irow][icol] = Rock::N;
-                    self.rocks[irow - 1][icol] = Rock::R;
+        for irow in 1..self.rocks.nrows() {
+            for icol in 0..self.rocks.ncols() {
+                if self.rocks[[irow, icol]] == Rock::R && self.rocks[[irow - 1, icol]] == Rock::N {
+                    self.rocks[[irow, icol]] = Rock::N;
+                    self.rocks[[irow - 1, icol]] = Rock::R;
                     changed = true
                 }
             }
@@ -57,26 +49,21 @@ impl RockField {
         changed
     }
     fn count_rocks(&self) -> usize {
-        let nrows = self.rocks.len();
+        let nrows = self.rocks.nrows();
         self.rocks
-            .iter()
-            .enumerate()
-            .map(|(irow, row)| {
-                row.iter()
-                    .map(|r| if *r == Rock::R { nrows - irow } else { 0 })
-                    .sum::<usize>()
-            })
+            .positions(|r| *r == Rock::R)
+            .map(|[irow, _]| nrows - irow)
             .sum::<usize>()
     }
 }
-pub fn run(input: &str) -> usize {
-    let mut field = input.parse::<RockField>().unwrap();
+pub fn run(input: &str) -> Result<usize> {
+    let mut field = input.parse::<RockField>()?;
     loop {
         if !field.roll(Direction::N) {
             break;
         }
     }
-    field.count_rocks()
+    Ok(field.count_rocks())
 }
 
 #[cfg(test)]
@@ -84,6 +71,8 @@ mod tests {
     #[test]
     fn test1() {
         let input = include_str!("example_data.txt");
-        assert_eq!(super::run(input), 136);
+        assert_eq!(super::run(input).unwrap(), 136);
+        let error = super::run("O.#\n.#").unwrap_err();
+        assert_eq!(error.to_string(), "line 2: expected 3 columns, found 2");
     }
 }
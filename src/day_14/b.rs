@@ -1,6 +1,13 @@
-use ndarray::{Array2, ArrayViewMut1, Axis};
+use ndarray::{ArrayViewMut1, Axis};
 use std::str::FromStr;
 
+use crate::{
+    debug,
+    error::{Error, Result},
+    geometry::Direction,
+    grid::Grid,
+};
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 enum Rock {
     R,
@@ -9,55 +16,30 @@ enum Rock {
 }
 
 impl TryFrom<char> for Rock {
-    type Error = &'static str;
-    fn try_from(value: char) -> Result<Self, Self::Error> {
+    type Error = Error;
+    fn try_from(value: char) -> Result<Self> {
         match value {
             'O' => Ok(Rock::R),
             '.' => Ok(Rock::N),
             '#' => Ok(Rock::S),
-            _ => Err("Unknown Rock"),
+            _ => Err(Error::new(format!("Unknown Rock `{}`", value))),
         }
     }
 }
 
 #[derive(PartialEq, Debug)]
 struct RockField {
-    rocks: Array2<Rock>,
-    nrows: usize,
-    // ncols: usize,
+    rocks: Grid<Rock>,
 }
 
 impl FromStr for RockField {
-    type Err = &'static str;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let rocks = s
-            .lines()
-            .map(|line| {
-                line.chars()
-                    .map(|c| Rock::try_from(c).unwrap())
-                    .collect::<Vec<_>>()
-            })
-            .collect::<Vec<_>>();
-        let nrows = rocks.len();
-        let ncols = rocks[0].len();
-        let rocks = rocks.concat();
-        let rocks = Array2::from_shape_vec((nrows, ncols), rocks).unwrap();
-        Ok(RockField {
-            rocks,
-            nrows,
-            // ncols,
-        })
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
+        let rocks = s.parse::<Grid<Rock>>()?;
+        Ok(RockField { rocks })
     }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
-enum Direction {
-    N,
-    E,
-    S,
-    W,
-}
-
 impl RockField {
     fn roll_arr(arr: &mut ArrayViewMut1<Rock>, forward: bool) {
         if forward {
@@ -102,21 +84,25 @@ impl RockField {
         match direction {
             Direction::S => {
                 self.rocks
+                    .as_array_mut()
                     .axis_iter_mut(Axis(1))
                     .for_each(|mut c| RockField::roll_arr(&mut c, true));
             }
             Direction::N => {
                 self.rocks
+                    .as_array_mut()
                     .axis_iter_mut(Axis(1))
                     .for_each(|mut c| RockField::roll_arr(&mut c, false));
             }
             Direction::E => {
                 self.rocks
+                    .as_array_mut()
                     .axis_iter_mut(Axis(0))
                     .for_each(|mut c| RockField::roll_arr(&mut c, true));
             }
             Direction::W => {
                 self.rocks
+                    .as_array_mut()
                     .axis_iter_mut(Axis(0))
                     .for_each(|mut c| RockField::roll_arr(&mut c, false));
             }
@@ -131,22 +117,15 @@ impl RockField {
     }
 
     fn count_rocks(&self) -> usize {
+        let nrows = self.rocks.nrows();
         self.rocks
-            .indexed_iter()
-            .map(
-                |((irow, _), r)| {
-                    if *r == Rock::R {
-                        self.nrows - irow
-                    } else {
-                        0
-                    }
-                },
-            )
+            .positions(|r| *r == Rock::R)
+            .map(|[irow, _]| nrows - irow)
             .sum::<usize>()
     }
 }
-pub fn run(input: &str) -> usize {
-    let mut field = input.parse::<RockField>().unwrap();
+pub fn run(input: &str) -> Result<usize> {
+    let mut field = input.parse::<RockField>()?;
     let data = (0..300)
         .map(|_| {
             field.roll_cycle();
@@ -163,12 +142,12 @@ pub fn run(input: &str) -> usize {
     let pdiff = pos.windows(2).map(|x| x[1] - x[0]).collect::<Vec<_>>();
     pos.iter()
         .zip(pdiff.iter())
-        .for_each(|(p, d)| println!("{} {}", p, d));
+        .for_each(|(p, d)| debug!("load {} after cycle {}, repeats {} later", last, p + 1, d));
     assert!(pdiff.len() > 1);
     assert!(pdiff.iter().all(|d| *d == pdiff[0]));
 
     let ncycles = 1_000_000_000;
-    data[pos[0] + ((ncycles - 1 - pos[0]) % pdiff[0])]
+    Ok(data[pos[0] + ((ncycles - 1 - pos[0]) % pdiff[0])])
 }
 
 #[cfg(test)]
@@ -178,7 +157,7 @@ mod tests {
     #[test]
     fn test1() {
         let input = include_str!("example_data.txt");
-        assert_eq!(super::run(input), 64);
+        assert_eq!(super::run(input).unwrap(), 64);
     }
     #[test]
     fn roll_test() {
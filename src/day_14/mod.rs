@@ -1,8 +1,18 @@
+use crate::{
+    error::Result,
+    solution::{Answer, Solution},
+};
+
 mod a;
 mod b;
 
-pub fn run() {
-    let input = include_str!("data.txt");
-    println!("day 14 a {}", a::run(input));
-    println!("day 14 b {}", b::run(input));
+pub struct Day14;
+
+impl Solution for Day14 {
+    fn part_a(&self, input: &str) -> Result<Answer> {
+        Ok(a::run(input)?.into())
+    }
+    fn part_b(&self, input: &str) -> Result<Answer> {
+        Ok(b::run(input)?.into())
+    }
 }
@@ -9,7 +9,7 @@ fn hash_str(s: &str) -> usize {
 }
 
 pub fn run(input: &str) -> usize {
-    input.split(",").map(|s| hash_str(s)).sum()
+    input.split(",").map(hash_str).sum()
 }
 
 #[cfg(test)]
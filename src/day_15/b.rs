@@ -1,5 +1,7 @@
 use std::{collections::HashMap, str::FromStr};
 
+use crate::error::{Error, Result};
+
 fn hash_str(s: &str) -> usize {
     let mut val = 0;
     s.chars().for_each(|c| {
@@ -22,11 +24,11 @@ enum Instruction {
 }
 
 impl FromStr for Instruction {
-    type Err = &'static str;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
         // rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7
         if s.len() < 3 {
-            return Err("Too few characters");
+            return Err(Error::new(format!("Too few characters in `{}`", s)));
         }
         let op_index = s
             .chars()
@@ -34,14 +36,18 @@ impl FromStr for Instruction {
             .filter(|(_, c)| *c == '-' || *c == '=')
             .map(|(i, _)| i)
             .collect::<Vec<_>>();
-        assert_eq!(op_index.len(), 1);
+        if op_index.len() != 1 {
+            return Err(Error::new(format!("expected one `=` or `-` in `{}`", s)));
+        }
         let op_index = op_index[0];
         match s.chars().nth(op_index).unwrap() {
             '=' => {
-                let (label, lens_power) = s.split_once("=").unwrap();
+                let (label, lens_power) = s.split_once('=').unwrap();
                 Ok(Instruction::Add(Lens {
                     label: label.to_string(),
-                    lens_power: lens_power.parse::<usize>().unwrap(),
+                    lens_power: lens_power
+                        .parse::<usize>()
+                        .map_err(|_| Error::new(format!("bad lens power `{}`", lens_power)))?,
                 }))
             }
             '-' => {
@@ -113,12 +119,9 @@ impl Boxes {
     fn execute(&mut self, instruction: Instruction) {
         let hash = match &instruction {
             Instruction::Add(lens) => hash_str(&lens.label),
-            Instruction::Remove(label) => hash_str(&label),
+            Instruction::Remove(label) => hash_str(label),
         };
-        if !self.boxes.contains_key(&hash) {
-            self.boxes.insert(hash, Box::default());
-        }
-        let b = self.boxes.get_mut(&hash).unwrap();
+        let b = self.boxes.entry(hash).or_default();
         match instruction {
             Instruction::Add(lens) => {
                 b.add(lens);
@@ -130,15 +133,17 @@ impl Boxes {
     }
 }
 
-pub fn run(input: &str) -> usize {
+pub fn run(input: &str) -> Result<usize> {
     let mut boxes = Boxes::default();
     input
-        .split(",")
-        .map(|s| s.parse::<Instruction>().unwrap())
+        .split(',')
+        .map(|s| s.parse::<Instruction>().map_err(|e| e.at_token(input, s)))
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
         .for_each(|x| {
             boxes.execute(x);
         });
-    boxes.focusing_power()
+    Ok(boxes.focusing_power())
 }
 
 #[cfg(test)]
@@ -146,7 +151,9 @@ mod tests {
     #[test]
     fn test1() {
         let input = include_str!("example_data.txt");
-        assert_eq!(super::run(input), 145);
+        assert_eq!(super::run(input).unwrap(), 145);
+        let error = super::run("rn=1,cm-,qp=x").unwrap_err();
+        assert_eq!(error.to_string(), "column 10: bad lens power `x`");
     }
     #[test]
     fn test_hash_1() {
@@ -0,0 +1,254 @@
+//! Exhaustive crucible search, the oracle the Dijkstra solver is checked
+//! against
+//!
+//! Maps the two disagree on are shrunk and written to `regressions/`, a
+//! first line of `min_run max_run reverse turn_cost` and then the map, and
+//! every case there is checked again by [tests::test_regressions].
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::panic;
+use std::path::PathBuf;
+
+use super::crucible::{Crucible, LossMap};
+use crate::{
+    geometry::{Direction, Point2},
+    grid::Grid,
+};
+
+/// Try every way the crucible can move one block at a time
+///
+/// A branch is only cut off when it reaches a state some other branch
+/// already reached losing no more heat, or loses no less than the best
+/// path found so far, so nothing the solver relies on is assumed here.
+pub(super) struct BruteSolver<'a> {
+    crucible: Crucible,
+    loss_map: &'a LossMap,
+    /// Least heat lost reaching each position, direction and run
+    seen: HashMap<(Point2<usize>, Direction, usize), usize>,
+    best: Option<usize>,
+}
+
+impl<'a> BruteSolver<'a> {
+    pub(super) fn new(crucible: Crucible, loss_map: &'a LossMap) -> BruteSolver<'a> {
+        BruteSolver {
+            crucible,
+            loss_map,
+            seen: HashMap::new(),
+            best: None,
+        }
+    }
+
+    /// Lowest heat loss to the bottom right, if it can be reached
+    pub(super) fn solve(mut self) -> Option<usize> {
+        let start = Point2::new(0, 0);
+        if start == self.end() {
+            return Some(0);
+        }
+        for direction in Direction::ALL {
+            self.enter(start, direction, 1, 0);
+        }
+        self.best
+    }
+
+    fn end(&self) -> Point2<usize> {
+        Point2::new(self.loss_map.nrows - 1, self.loss_map.ncols - 1)
+    }
+
+    /// Move one block from `from` towards `direction`, the `run`th block in
+    /// a row that way
+    fn enter(&mut self, from: Point2<usize>, direction: Direction, run: usize, loss: usize) {
+        let Some(position) = self.loss_map.moved(&from, 1, direction) else {
+            return;
+        };
+        let loss = loss + self.loss_map.data[position.index()] as usize;
+        if self.best.is_some_and(|best| loss >= best) {
+            return;
+        }
+        let seen = self
+            .seen
+            .entry((position, direction, run))
+            .or_insert(usize::MAX);
+        if *seen <= loss {
+            return;
+        }
+        *seen = loss;
+        if run >= self.crucible.min_run && position == self.end() {
+            self.best = Some(loss);
+            return;
+        }
+        if run < self.crucible.max_run {
+            self.enter(position, direction, run + 1, loss);
+        }
+        if run >= self.crucible.min_run {
+            let loss = loss + self.crucible.turn_cost as usize;
+            for turn in self.crucible.turns(direction) {
+                self.enter(position, turn, 1, loss);
+            }
+        }
+    }
+}
+
+fn rows(loss_map: &LossMap) -> Vec<Vec<u8>> {
+    (0..loss_map.nrows)
+        .map(|row| {
+            (0..loss_map.ncols)
+                .map(|col| loss_map.data[[row, col]])
+                .collect()
+        })
+        .collect()
+}
+
+fn from_rows(rows: Vec<Vec<u8>>) -> LossMap {
+    let data = Grid::from_rows(rows).unwrap();
+    LossMap {
+        nrows: data.nrows(),
+        ncols: data.ncols(),
+        data,
+    }
+}
+
+/// Whether the solver panics on `loss_map` or disagrees with the oracle
+fn fails(crucible: Crucible, loss_map: &LossMap) -> bool {
+    let fast = panic::catch_unwind(|| crucible.solve(loss_map).map(|path| path.loss));
+    !matches!(fast, Ok(fast) if fast == BruteSolver::new(crucible, loss_map).solve())
+}
+
+/// Smaller maps that `loss_map` shrinks to, one row or column fewer or one
+/// block losing less heat
+fn shrinks(loss_map: &LossMap) -> Vec<LossMap> {
+    let rows = rows(loss_map);
+    let mut shrinks = Vec::new();
+    if rows.len() > 1 {
+        shrinks.push(rows[1..].to_vec());
+        shrinks.push(rows[..rows.len() - 1].to_vec());
+    }
+    if rows[0].len() > 1 {
+        shrinks.push(rows.iter().map(|row| row[1..].to_vec()).collect());
+        shrinks.push(
+            rows.iter()
+                .map(|row| row[..row.len() - 1].to_vec())
+                .collect(),
+        );
+    }
+    for (r, row) in rows.iter().enumerate() {
+        for (c, &loss) in row.iter().enumerate().filter(|(_, &loss)| loss > 1) {
+            let mut smaller = rows.clone();
+            smaller[r][c] = if loss > 2 { loss / 2 } else { 1 };
+            shrinks.push(smaller);
+        }
+    }
+    shrinks.into_iter().map(from_rows).collect()
+}
+
+/// Smallest map `loss_map` shrinks to that still `fails`
+fn shrink(mut loss_map: LossMap, fails: impl Fn(&LossMap) -> bool) -> LossMap {
+    while let Some(smaller) = shrinks(&loss_map).into_iter().find(&fails) {
+        loss_map = smaller;
+    }
+    loss_map
+}
+
+fn regressions() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/day_17/regressions")
+}
+
+/// Write a failing case to [regressions], named after its hash
+fn write_regression(crucible: Crucible, loss_map: &LossMap) -> PathBuf {
+    let case = format!(
+        "{} {} {} {}\n{}",
+        crucible.min_run, crucible.max_run, crucible.reverse, crucible.turn_cost, loss_map
+    );
+    let mut hasher = DefaultHasher::new();
+    case.hash(&mut hasher);
+    std::fs::create_dir_all(regressions()).unwrap();
+    let path = regressions().join(format!("{:016x}.txt", hasher.finish()));
+    std::fs::write(&path, case).unwrap();
+    path
+}
+
+fn read_regression(case: &str) -> (Crucible, LossMap) {
+    let (header, map) = case.split_once('\n').unwrap();
+    let fields = header.split_whitespace().collect::<Vec<_>>();
+    let crucible = Crucible::new(fields[0].parse().unwrap(), fields[1].parse().unwrap())
+        .unwrap()
+        .reverse(fields[2].parse().unwrap())
+        .turn_cost(fields[3].parse().unwrap());
+    (crucible, map.parse().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::{
+        fails, read_regression, regressions, shrink, write_regression, BruteSolver, Crucible,
+        LossMap,
+    };
+
+    #[test]
+    fn test_brute() {
+        let input = include_str!("example_data.txt");
+        let loss_map = input.parse::<LossMap>().unwrap();
+        assert_eq!(
+            BruteSolver::new(Crucible::NORMAL, &loss_map).solve(),
+            Some(102)
+        );
+        assert_eq!(
+            BruteSolver::new(Crucible::ULTRA, &loss_map).solve(),
+            Some(94)
+        );
+        let loss_map = "11111".parse::<LossMap>().unwrap();
+        assert_eq!(BruteSolver::new(Crucible::NORMAL, &loss_map).solve(), None);
+        let crucible = Crucible::NORMAL.reverse(true).turn_cost(2);
+        assert_eq!(BruteSolver::new(crucible, &loss_map).solve(), Some(10));
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = StdRng::seed_from_u64(17);
+        let crucibles = [
+            (Crucible::NORMAL, 1..=6),
+            (Crucible::ULTRA, 1..=9),
+            (Crucible::NORMAL.turn_cost(3), 2..=6),
+            (Crucible::new(2, 5).unwrap().reverse(true), 1..=6),
+        ];
+        for (crucible, sizes) in crucibles {
+            for size in sizes {
+                for _ in 0..10 {
+                    let loss_map = LossMap::random(size, &mut rng);
+                    if fails(crucible, &loss_map) {
+                        let loss_map = shrink(loss_map, |l| fails(crucible, l));
+                        let path = write_regression(crucible, &loss_map);
+                        panic!(
+                            "{:?} disagrees with brute force on\n{}written to {}",
+                            crucible,
+                            loss_map,
+                            path.display()
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_regressions() {
+        for entry in std::fs::read_dir(regressions()).unwrap() {
+            let path = entry.unwrap().path();
+            let (crucible, loss_map) = read_regression(&std::fs::read_to_string(&path).unwrap());
+            assert!(!fails(crucible, &loss_map), "{}", path.display());
+        }
+    }
+
+    #[test]
+    fn test_shrink() {
+        let loss_map = "128\n793\n456".parse::<LossMap>().unwrap();
+        let has_9 = |l: &LossMap| l.data.iter().any(|&x| x == 9);
+        assert_eq!(shrink(loss_map, has_9).to_string(), "9\n");
+        let loss_map = "128\n793\n456".parse::<LossMap>().unwrap();
+        let has_big = |l: &LossMap| l.data.iter().filter(|&&x| x > 6).count() >= 2;
+        assert_eq!(shrink(loss_map, has_big).to_string(), "79\n");
+    }
+}
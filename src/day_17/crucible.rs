@@ -0,0 +1,422 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use super::path::{Path, Step};
+use crate::{
+    debug,
+    error::{digit, Error, Result},
+    geometry::{Direction, Point2},
+    grid::Grid,
+    log::Level,
+    log_enabled,
+    search::{self, Numbered, Problem},
+    trace,
+};
+
+/// Heat lost in every city block
+pub struct LossMap {
+    pub(super) data: Grid<u8>,
+    pub(super) nrows: usize,
+    pub(super) ncols: usize,
+}
+
+impl Display for LossMap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.data)
+    }
+}
+
+impl LossMap {
+    /// Position `distance` away towards `direction`, if it is on the map
+    pub(super) fn moved(
+        &self,
+        position: &Point2<usize>,
+        distance: usize,
+        direction: Direction,
+    ) -> Option<Point2<usize>> {
+        position
+            .moved(direction, distance)
+            .filter(|p| p.within(self.nrows, self.ncols))
+    }
+    /// Position `distance` away towards `direction` and the heat lost on
+    /// the way there, if it is on the map
+    fn walk(
+        &self,
+        position: &Point2<usize>,
+        distance: usize,
+        direction: Direction,
+    ) -> Option<(Point2<usize>, u32)> {
+        let end = self.moved(position, distance, direction)?;
+        let loss = (1..=distance)
+            .map(|d| self.data[position.moved(direction, d).unwrap().index()] as u32)
+            .sum();
+        Some((end, loss))
+    }
+    #[cfg(test)]
+    pub(super) fn random(size: usize, rng: &mut impl rand::Rng) -> LossMap {
+        let mut data = Grid::from_elem(size, size, 0);
+        data.iter_mut().for_each(|x| {
+            *x = rng.gen_range(1..10);
+        });
+        LossMap {
+            data,
+            nrows: size,
+            ncols: size,
+        }
+    }
+}
+
+impl FromStr for LossMap {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
+        let data = Grid::parse_with(s, digit)?;
+        Ok(LossMap {
+            nrows: data.nrows(),
+            ncols: data.ncols(),
+            data,
+        })
+    }
+}
+
+/// How a crucible may move: at least `min_run` and at most `max_run` blocks
+/// straight, turning left or right (and back, if `reverse`) in between,
+/// each turn losing `turn_cost` extra heat
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Crucible {
+    pub(super) min_run: usize,
+    pub(super) max_run: usize,
+    pub(super) reverse: bool,
+    pub(super) turn_cost: u32,
+    pub(super) heuristic: Heuristic,
+}
+
+/// Lower bound of the heat a crucible still loses getting to the end
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Heuristic {
+    /// None at all, plain Dijkstra to compare the bound against
+    None,
+    /// Blocks to the end times the least heat any block loses
+    #[default]
+    Manhattan,
+}
+
+impl Crucible {
+    /// Part a, at most 3 blocks straight
+    pub const NORMAL: Crucible = Crucible {
+        min_run: 1,
+        max_run: 3,
+        reverse: false,
+        turn_cost: 0,
+        heuristic: Heuristic::Manhattan,
+    };
+    /// Part b, 4 to 10 blocks straight
+    pub const ULTRA: Crucible = Crucible {
+        min_run: 4,
+        max_run: 10,
+        reverse: false,
+        turn_cost: 0,
+        heuristic: Heuristic::Manhattan,
+    };
+
+    /// Crucible moving `min_run..=max_run` blocks straight that can't turn
+    /// back and turns for free
+    pub fn new(min_run: usize, max_run: usize) -> Result<Crucible> {
+        if min_run == 0 || min_run > max_run {
+            return Err(Error::new(format!(
+                "bad straight run {}..={}",
+                min_run, max_run
+            )));
+        }
+        Ok(Crucible {
+            min_run,
+            max_run,
+            reverse: false,
+            turn_cost: 0,
+            heuristic: Heuristic::Manhattan,
+        })
+    }
+
+    /// Allow turning around instead of only left or right
+    pub fn reverse(mut self, reverse: bool) -> Crucible {
+        self.reverse = reverse;
+        self
+    }
+
+    /// Extra heat lost on every turn, the first move isn't one
+    pub fn turn_cost(mut self, turn_cost: u32) -> Crucible {
+        self.turn_cost = turn_cost;
+        self
+    }
+
+    /// Search guided by `heuristic`, [Heuristic::Manhattan] by default
+    pub fn heuristic(mut self, heuristic: Heuristic) -> Crucible {
+        self.heuristic = heuristic;
+        self
+    }
+
+    /// Directions a crucible heading towards `direction` can turn to
+    pub(super) fn turns(&self, direction: Direction) -> impl Iterator<Item = Direction> {
+        [direction.left(), direction.right()]
+            .into_iter()
+            .chain(self.reverse.then_some(direction.opposite()))
+    }
+
+    /// Path losing the least heat from the top left to the bottom right,
+    /// if there is one
+    pub fn solve(&self, loss_map: &LossMap) -> Option<Path> {
+        Solver::new(loss_map, *self).solve()
+    }
+
+    /// Lowest heat loss from the top left to the bottom right of `input`
+    pub fn min_heat_loss(&self, input: &str) -> Result<usize> {
+        let loss_map = input.parse::<LossMap>()?;
+        self.solve(&loss_map)
+            .map(|path| path.loss)
+            .ok_or_else(|| Error::new("can't reach the end"))
+    }
+}
+
+/// Crucible at `position` that got there moving `run` blocks straight
+/// towards `direction`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct State {
+    position: Point2<usize>,
+    direction: Direction,
+    run: usize,
+}
+
+impl Display for State {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}x{}", self.position, self.run, self.direction)
+    }
+}
+
+/// Crucible states on a loss map as a search problem, each move costing
+/// the heat lost on the way
+struct Solver<'a> {
+    crucible: Crucible,
+    loss_map: &'a LossMap,
+    end: Point2<usize>,
+    /// Least heat any block loses, every block still to go loses at least
+    /// that much
+    min_loss: u32,
+}
+
+impl Problem for Solver<'_> {
+    type State = State;
+
+    /// States one block further straight on, or the least number of blocks
+    /// further after a turn, with the heat lost getting there
+    fn successors(&self, state: &State) -> impl Iterator<Item = (State, u64)> {
+        let state = *state;
+        let Crucible {
+            min_run,
+            max_run,
+            turn_cost,
+            ..
+        } = self.crucible;
+        let straight = (state.run < max_run).then_some((state.direction, 1, state.run + 1, 0));
+        let turns = self
+            .crucible
+            .turns(state.direction)
+            .map(move |direction| (direction, min_run, min_run, turn_cost));
+        straight
+            .into_iter()
+            .chain(turns)
+            .filter_map(move |(direction, distance, run, cost)| {
+                let (position, loss) = self.loss_map.walk(&state.position, distance, direction)?;
+                let next = State {
+                    position,
+                    direction,
+                    run,
+                };
+                Some((next, (loss + cost) as u64))
+            })
+    }
+
+    fn heuristic(&self, state: &State) -> u64 {
+        match self.crucible.heuristic {
+            Heuristic::None => 0,
+            Heuristic::Manhattan => {
+                state.position.manhattan(&self.end) as u64 * self.min_loss as u64
+            }
+        }
+    }
+
+    fn is_goal(&self, state: &State) -> bool {
+        state.position == self.end
+    }
+}
+
+impl Numbered for Solver<'_> {
+    fn state_count(&self) -> usize {
+        self.loss_map.nrows * self.loss_map.ncols * 4 * self.runs()
+    }
+
+    fn index(&self, state: &State) -> usize {
+        let cell = state.position.row * self.loss_map.ncols + state.position.col;
+        let direction = usize::from(state.direction);
+        (cell * 4 + direction) * self.runs() + state.run - self.crucible.min_run
+    }
+
+    fn state(&self, index: usize) -> State {
+        let runs = self.runs();
+        let cell = index / runs / 4;
+        State {
+            position: Point2::new(cell / self.loss_map.ncols, cell % self.loss_map.ncols),
+            direction: Direction::ALL[index / runs % 4],
+            run: index % runs + self.crucible.min_run,
+        }
+    }
+}
+
+impl<'a> Solver<'a> {
+    fn new(loss_map: &'a LossMap, crucible: Crucible) -> Solver<'a> {
+        Solver {
+            crucible,
+            loss_map,
+            end: Point2::new(loss_map.nrows - 1, loss_map.ncols - 1),
+            min_loss: loss_map.data.iter().copied().min().unwrap_or(0) as u32,
+        }
+    }
+
+    /// Number of runs a state can have got there with
+    fn runs(&self) -> usize {
+        self.crucible.max_run - self.crucible.min_run + 1
+    }
+
+    /// Blocks moved through on the way through `states`, from the top left
+    fn path(&self, states: &[State], loss: u64, expanded: usize) -> Path {
+        let mut path = Path {
+            steps: Vec::new(),
+            loss: loss as usize,
+            expanded,
+        };
+        let mut position = Point2::new(0, 0);
+        for (i, state) in states.iter().enumerate() {
+            let blocks = if state.run > self.crucible.min_run {
+                1
+            } else {
+                self.crucible.min_run
+            };
+            for block in 0..blocks {
+                position = position.step(state.direction).unwrap();
+                path.steps.push(Step {
+                    position,
+                    direction: state.direction,
+                    loss: self.loss_map.data[position.index()] as u32,
+                    turn: block == 0 && state.run == self.crucible.min_run && i > 0,
+                });
+            }
+            debug_assert_eq!(position, state.position);
+        }
+        path
+    }
+
+    /// Solve and return the path losing the least heat, if the end can be
+    /// reached
+    fn solve(&self) -> Option<Path> {
+        let start = Point2::new(0, 0);
+        if self.end == start {
+            return Some(Path::default());
+        }
+        // the first move is like a turn from standing still, but free
+        let starts = [Direction::E, Direction::S]
+            .into_iter()
+            .filter_map(|direction| {
+                let min_run = self.crucible.min_run;
+                let (position, loss) = self.loss_map.walk(&start, min_run, direction)?;
+                let state = State {
+                    position,
+                    direction,
+                    run: min_run,
+                };
+                Some((state, loss as u64))
+            });
+        let search = match self.crucible.heuristic {
+            Heuristic::None => search::dense_dijkstra(self, starts),
+            Heuristic::Manhattan => search::dense_a_star(self, starts),
+        };
+        trace!("expanded {} states", search.expanded);
+        let found = search.found?;
+        let path = self.path(&found.path, found.cost, search.expanded);
+        if log_enabled!(Level::Debug) {
+            debug!(
+                "loss {} turning {} times, {} states expanded",
+                path.loss,
+                path.turns().count(),
+                path.expanded
+            );
+            for line in path.ascii(self.loss_map).lines() {
+                debug!("{}", line);
+            }
+        }
+        Some(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::{Crucible, Heuristic, LossMap, Solver};
+    use crate::search::{self, Numbered};
+
+    #[test]
+    fn test_crucible() {
+        let input = include_str!("example_data.txt");
+        assert_eq!(Crucible::new(1, 3).unwrap(), Crucible::NORMAL);
+        assert_eq!(Crucible::NORMAL.min_heat_loss(input).unwrap(), 102);
+        assert_eq!(Crucible::ULTRA.min_heat_loss(input).unwrap(), 94);
+        assert_eq!(
+            Crucible::new(4, 3).unwrap_err().to_string(),
+            "bad straight run 4..=3"
+        );
+        assert!(Crucible::new(0, 3).is_err());
+    }
+
+    #[test]
+    fn test_turns() {
+        // the cheapest path turns twice, each turn losing 5 more
+        let input = "1119\n9919\n9911";
+        assert_eq!(Crucible::NORMAL.min_heat_loss(input).unwrap(), 5);
+        let crucible = Crucible::NORMAL.turn_cost(5);
+        assert_eq!(crucible.min_heat_loss(input).unwrap(), 15);
+        assert_eq!(crucible.min_heat_loss("1111\n1111").unwrap(), 9);
+
+        // four blocks in a row are out of reach without turning around
+        let input = "11111";
+        assert!(Crucible::NORMAL.min_heat_loss(input).is_err());
+        let crucible = Crucible::NORMAL.reverse(true);
+        assert_eq!(crucible.min_heat_loss(input).unwrap(), 6);
+        assert_eq!(crucible.turn_cost(2).min_heat_loss(input).unwrap(), 10);
+    }
+
+    #[test]
+    fn test_heuristic() {
+        let input = include_str!("example_data.txt");
+        let loss_map = input.parse::<LossMap>().unwrap();
+        for crucible in [Crucible::NORMAL, Crucible::ULTRA] {
+            let a_star = crucible.solve(&loss_map).unwrap();
+            let dijkstra = crucible
+                .heuristic(Heuristic::None)
+                .solve(&loss_map)
+                .unwrap();
+            assert_eq!(a_star.loss, dijkstra.loss);
+            assert!(a_star.expanded < dijkstra.expanded);
+        }
+    }
+
+    #[test]
+    fn test_dense() {
+        let loss_map = LossMap::random(80, &mut StdRng::seed_from_u64(17));
+        let solver = Solver::new(&loss_map, Crucible::ULTRA);
+        let count = solver.state_count();
+        assert!((0..count).all(|index| solver.index(&solver.state(index)) == index));
+        // numbering the states changes how the search keeps track of them,
+        // not the path it finds
+        let start = [(solver.state(0), 0)];
+        let dense = search::dense_dijkstra(&solver, start).found.unwrap();
+        assert_eq!(search::dijkstra(&solver, start).found.unwrap(), dense);
+    }
+}
@@ -1,8 +1,22 @@
+use crate::{
+    error::Result,
+    solution::{Answer, Solution},
+};
+
 pub mod a;
 mod b;
+#[cfg(test)]
+mod brute;
+pub mod crucible;
+pub mod path;
+
+pub struct Day17;
 
-pub fn run() {
-    let input = include_str!("data.txt");
-    println!("day 17 a {}", a::run(input));
-    println!("day 17 b {}", b::run(input));
+impl Solution for Day17 {
+    fn part_a(&self, input: &str) -> Result<Answer> {
+        Ok(a::run(input)?.into())
+    }
+    fn part_b(&self, input: &str) -> Result<Answer> {
+        Ok(b::run(input)?.into())
+    }
 }
@@ -0,0 +1,233 @@
+use std::io::{self, Write};
+use std::str::FromStr;
+
+use super::crucible::{Crucible, LossMap};
+use crate::{
+    error::{Error, Result},
+    geometry::{Direction, Point2},
+};
+
+/// Block a crucible moves into and the heat lost there, `turn` when it
+/// changed direction to get there
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    pub position: Point2<usize>,
+    pub direction: Direction,
+    pub loss: u32,
+    pub turn: bool,
+}
+
+/// Blocks a crucible moves through from the top left, in order
+///
+/// `loss` is the total heat lost, the sum of the steps' losses plus the
+/// crucible's cost of every turn.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Path {
+    pub steps: Vec<Step>,
+    pub loss: usize,
+    /// Number of states the search expanded finding the path
+    pub expanded: usize,
+}
+
+/// Pixels per block side in PPM output
+const PPM_SCALE: usize = 4;
+
+impl Path {
+    /// Every position on the path, starting from the top left
+    pub fn positions(&self) -> impl Iterator<Item = Point2<usize>> + '_ {
+        std::iter::once(Point2::new(0, 0)).chain(self.steps.iter().map(|s| s.position))
+    }
+
+    /// Positions the crucible turns at
+    pub fn turns(&self) -> impl Iterator<Item = Point2<usize>> + '_ {
+        self.positions()
+            .zip(&self.steps)
+            .filter(|(_, step)| step.turn)
+            .map(|(from, _)| from)
+    }
+
+    /// The loss map with the path drawn over it as arrows, like the puzzle
+    /// shows it
+    pub fn ascii(&self, loss_map: &LossMap) -> String {
+        let mut chars = loss_map.data.map(|x| char::from(b'0' + x));
+        for step in &self.steps {
+            chars[step.position.index()] = match step.direction {
+                Direction::N => '^',
+                Direction::E => '>',
+                Direction::S => 'v',
+                Direction::W => '<',
+            };
+        }
+        (0..chars.nrows())
+            .map(|row| {
+                let mut line = (0..chars.ncols())
+                    .map(|col| chars[[row, col]])
+                    .collect::<String>();
+                line.push('\n');
+                line
+            })
+            .collect()
+    }
+
+    /// Write the path as a JSON object with one step per line
+    pub fn write_json(&self, out: &mut impl Write) -> io::Result<()> {
+        writeln!(out, "{{")?;
+        writeln!(out, "  \"loss\": {},", self.loss)?;
+        writeln!(out, "  \"expanded\": {},", self.expanded)?;
+        writeln!(out, "  \"steps\": [")?;
+        for (i, step) in self.steps.iter().enumerate() {
+            let separator = if i + 1 == self.steps.len() { "" } else { "," };
+            writeln!(
+                out,
+                "    {{\"row\": {}, \"col\": {}, \"direction\": \"{}\", \"loss\": {}, \"turn\": {}}}{}",
+                step.position.row,
+                step.position.col,
+                step.direction,
+                step.loss,
+                step.turn,
+                separator
+            )?;
+        }
+        writeln!(out, "  ]")?;
+        writeln!(out, "}}")
+    }
+
+    /// Write the loss map as a binary PPM heatmap, blue for little heat
+    /// lost up to red for a lot, with the path in white
+    pub fn write_ppm(&self, out: &mut impl Write, loss_map: &LossMap) -> io::Result<()> {
+        let mut on_path = loss_map.data.map(|_| false);
+        self.positions().for_each(|p| on_path[p.index()] = true);
+        let (width, height) = (loss_map.ncols * PPM_SCALE, loss_map.nrows * PPM_SCALE);
+        write!(out, "P6\n{} {}\n255\n", width, height)?;
+        let mut line = Vec::with_capacity(width * 3);
+        for row in 0..loss_map.nrows {
+            line.clear();
+            for col in 0..loss_map.ncols {
+                let color = if on_path[[row, col]] {
+                    [255, 255, 255]
+                } else {
+                    heat_color(loss_map.data[[row, col]])
+                };
+                (0..PPM_SCALE).for_each(|_| line.extend(color));
+            }
+            for _ in 0..PPM_SCALE {
+                out.write_all(&line)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write the path in the given format
+    pub fn write(
+        &self,
+        out: &mut impl Write,
+        format: PathFormat,
+        loss_map: &LossMap,
+    ) -> io::Result<()> {
+        match format {
+            PathFormat::Ascii => out.write_all(self.ascii(loss_map).as_bytes()),
+            PathFormat::Json => self.write_json(out),
+            PathFormat::Ppm => self.write_ppm(out, loss_map),
+        }
+    }
+}
+
+/// Color of a block losing `loss` heat, from 1 blue to 9 red
+fn heat_color(loss: u8) -> [u8; 3] {
+    let heat = (loss.clamp(1, 9) - 1) as u32 * 255 / 8;
+    [heat as u8, 0, (255 - heat) as u8]
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PathFormat {
+    #[default]
+    Ascii,
+    Json,
+    Ppm,
+}
+
+impl FromStr for PathFormat {
+    type Err = &'static str;
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "ascii" => Ok(PathFormat::Ascii),
+            "json" => Ok(PathFormat::Json),
+            "ppm" => Ok(PathFormat::Ppm),
+            _ => Err("Unknown format, expected ascii, json or ppm"),
+        }
+    }
+}
+
+/// Best path of `crucible` through the loss map in `input`, rendered in
+/// `format`
+pub fn render(input: &str, crucible: Crucible, format: PathFormat) -> Result<Vec<u8>> {
+    let loss_map = input.parse::<LossMap>()?;
+    let path = crucible
+        .solve(&loss_map)
+        .ok_or_else(|| Error::new("can't reach the end"))?;
+    let mut out = Vec::new();
+    path.write(&mut out, format, &loss_map).unwrap();
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{render, PathFormat};
+    use crate::{day_17::crucible::Crucible, geometry::Point2};
+
+    #[test]
+    fn test_path() {
+        let input = include_str!("example_data.txt");
+        let loss_map = input.parse().unwrap();
+        let path = Crucible::NORMAL.solve(&loss_map).unwrap();
+        assert_eq!(path.loss, 102);
+        assert_eq!(
+            path.steps.iter().map(|s| s.loss as usize).sum::<usize>(),
+            102
+        );
+        assert_eq!(path.positions().last(), Some(Point2::new(12, 12)));
+        // no turn lasts longer than 3 blocks
+        let mut turns = path.turns().collect::<Vec<_>>();
+        turns.push(Point2::new(12, 12));
+        assert!(turns
+            .windows(2)
+            .all(|w| w[0].manhattan(&w[1]) <= 3 && w[0].manhattan(&w[1]) > 0));
+
+        let ascii = String::from_utf8(render(input, Crucible::NORMAL, PathFormat::Ascii).unwrap());
+        let ascii = ascii.unwrap();
+        assert!(ascii.starts_with("2>>34^>>>1323\n"));
+        assert_eq!(ascii.lines().count(), 13);
+        assert_eq!(
+            ascii.chars().filter(|c| "^>v<".contains(*c)).count(),
+            path.steps.len()
+        );
+    }
+
+    #[test]
+    fn test_formats() {
+        let input = "112\n911";
+        let json = render(input, Crucible::NORMAL, PathFormat::Json).unwrap();
+        let expected = r#"{
+  "loss": 3,
+  "expanded": 2,
+  "steps": [
+    {"row": 0, "col": 1, "direction": "E", "loss": 1, "turn": false},
+    {"row": 1, "col": 1, "direction": "S", "loss": 1, "turn": true},
+    {"row": 1, "col": 2, "direction": "E", "loss": 1, "turn": true}
+  ]
+}
+"#;
+        assert_eq!(String::from_utf8(json).unwrap(), expected);
+
+        let ppm = render(input, Crucible::NORMAL, PathFormat::Ppm).unwrap();
+        let header = b"P6\n12 8\n255\n";
+        assert_eq!(&ppm[..header.len()], header);
+        assert_eq!(ppm.len(), header.len() + 12 * 8 * 3);
+        // the 9 isn't on the path
+        let pixel = header.len() + (4 * 12) * 3;
+        assert_eq!(ppm[pixel..pixel + 3], [255, 0, 0]);
+
+        assert_eq!("ppm".parse::<PathFormat>(), Ok(PathFormat::Ppm));
+        assert!("png".parse::<PathFormat>().is_err());
+    }
+}
@@ -1,25 +1,20 @@
 use std::{fmt::Display, str::FromStr};
 
-use ndarray::Array2;
+use crate::{
+    debug,
+    error::{parse_lines, parse_token, Error},
+    geometry::{Direction, Point2},
+    grid::Grid,
+};
 
-#[derive(Debug)]
-enum Direction {
-    U,
-    D,
-    L,
-    R,
-}
-
-impl FromStr for Direction {
-    type Err = &'static str;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "U" => Ok(Direction::U),
-            "D" => Ok(Direction::D),
-            "L" => Ok(Direction::L),
-            "R" => Ok(Direction::R),
-            _ => Err("Unknown direction"),
-        }
+/// Parse a dig direction given as `U`, `D`, `L` or `R`
+fn parse_direction(s: &str) -> Result<Direction, Error> {
+    match s {
+        "U" => Ok(Direction::N),
+        "D" => Ok(Direction::S),
+        "L" => Ok(Direction::W),
+        "R" => Ok(Direction::E),
+        _ => Err(Error::new(format!("Unknown direction `{}`", s))),
     }
 }
 
@@ -31,13 +26,15 @@ struct DigInstruction {
 }
 
 impl FromStr for DigInstruction {
-    type Err = &'static str;
+    type Err = Error;
     /// Read in something like "R 10 (#ffffff)"
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut s = s.split_whitespace();
-        let direction = s.next().unwrap().parse::<Direction>().unwrap();
-        let distance = s.next().unwrap().parse::<usize>().unwrap();
-        let color = s.next().unwrap().to_string();
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let mut s = line.split_whitespace();
+        let mut next = || s.next().ok_or("expected direction, distance and color");
+        let token = next()?;
+        let direction = parse_direction(token).map_err(|e| e.at_token(line, token))?;
+        let distance = parse_token::<usize>(line, next()?)?;
+        let color = next()?.to_string();
         Ok(DigInstruction {
             direction,
             distance,
@@ -51,72 +48,57 @@ struct DigPlan {
     instructions: Vec<DigInstruction>,
 }
 
-impl FromStr for DigPlan {
-    type Err = &'static str;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(DigPlan {
-            instructions: s
-                .lines()
-                .map(|line| line.parse::<DigInstruction>().unwrap())
-                .collect::<Vec<_>>(),
-        })
-    }
-}
-
-#[derive(Debug, Clone, Copy)]
-struct Position {
-    index: [i64; 2],
-}
-
-impl Display for Position {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "({}, {})", self.index[0], self.index[1])
-    }
-}
-
-impl Position {
-    fn new(row: i64, col: i64) -> Position {
-        Position { index: [row, col] }
-    }
-
-    fn row(&self) -> i64 {
-        self.index[0]
-    }
-
-    fn col(&self) -> i64 {
-        self.index[1]
-    }
-
-    fn index_from(&self, position: &Position) -> [usize; 2] {
-        [
-            (self.row() - position.row()) as usize,
-            (self.col() - position.col()) as usize,
-        ]
+/// Check that `(direction, distance)` steps, one per line, dig a closed
+/// trench turning left or right after every step, the last into the first
+pub(super) fn check_plan(steps: &[(Direction, usize)]) -> Result<(), Error> {
+    if steps.is_empty() {
+        return Err(Error::new("expected dig instructions"));
     }
-
-    fn move_by(&self, direction: &Direction, distance: &usize) -> Position {
-        let distance = *distance as i64;
-        match direction {
-            Direction::D => Position::new(self.row() + distance, self.col()),
-            Direction::U => Position::new(self.row() - distance, self.col()),
-            Direction::L => Position::new(self.row(), self.col() - distance),
-            Direction::R => Position::new(self.row(), self.col() + distance),
+    let mut position = Point2::new(0_i64, 0);
+    for (iline, (direction, distance)) in steps.iter().enumerate() {
+        let error = |message: String| Error::new(message).at_line(iline + 1);
+        if *distance == 0 {
+            return Err(error("expected a distance above 0".to_string()));
+        }
+        let next = steps[(iline + 1) % steps.len()].0;
+        if next != direction.left() && next != direction.right() {
+            return Err(error(format!(
+                "expected a turn after digging {}, found {}",
+                direction, next
+            )));
         }
+        position = i64::try_from(*distance)
+            .ok()
+            .and_then(|distance| position.moved(*direction, distance))
+            .ok_or_else(|| error("the trench goes too far".to_string()))?;
     }
-
-    fn max(&self, other: &Position) -> Position {
-        Position::new(self.row().max(other.row()), self.col().max(other.col()))
+    if position != Point2::new(0, 0) {
+        return Err(Error::new(format!(
+            "expected the trench to end where it starts, it ends at {}",
+            position
+        ))
+        .at_line(steps.len()));
     }
+    Ok(())
+}
 
-    fn min(&self, other: &Position) -> Position {
-        Position::new(self.row().min(other.row()), self.col().min(other.col()))
+impl FromStr for DigPlan {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let instructions = parse_lines::<DigInstruction>(s)?;
+        let steps = instructions
+            .iter()
+            .map(|instruction| (instruction.direction, instruction.distance))
+            .collect::<Vec<_>>();
+        check_plan(&steps)?;
+        Ok(DigPlan { instructions })
     }
 }
 
 #[derive(Debug)]
 struct Lagoon {
-    dug: Array2<bool>,
-    offset: Position,
+    dug: Grid<bool>,
+    offset: Point2<i64>,
 }
 
 enum ElementType {
@@ -130,9 +112,11 @@ enum ElementType {
 }
 
 impl Lagoon {
-    fn check_area(dug: &Array2<bool>, irow: usize, icol: usize) -> ElementType {
+    /// What part of the trench the cell at `irow`, `icol` is, None if it
+    /// doesn't join two neighbors
+    fn check_area(dug: &Grid<bool>, irow: usize, icol: usize) -> Option<ElementType> {
         if !dug[[irow, icol]] {
-            return ElementType::Empty;
+            return Some(ElementType::Empty);
         }
         let left = dug[[irow, icol - 1]];
         let right = dug[[irow, icol + 1]];
@@ -140,60 +124,63 @@ impl Lagoon {
         let bottom = dug[[irow + 1, icol]];
 
         if top && bottom {
-            return ElementType::Virtical;
+            return Some(ElementType::Virtical);
         }
         if left && right {
-            return ElementType::Horizontal;
+            return Some(ElementType::Horizontal);
         }
         if top {
             if right {
-                return ElementType::BottomLeft;
+                return Some(ElementType::BottomLeft);
             }
             if left {
-                return ElementType::BottomRight;
+                return Some(ElementType::BottomRight);
             }
         }
         if bottom {
             if right {
-                return ElementType::UpperLeft;
+                return Some(ElementType::UpperLeft);
             }
             if left {
-                return ElementType::UpperRight;
+                return Some(ElementType::UpperRight);
             }
         }
-        panic!()
+        None
     }
-    fn new(dig_plan: &DigPlan) -> Lagoon {
+    fn new(dig_plan: &DigPlan) -> Result<Lagoon, Error> {
         // find boundaries
         let (min_position, max_position) = {
-            let mut position = Position::new(0, 0);
+            let mut position = Point2::new(0, 0);
             let mut min_position = position;
             let mut max_position = position;
             dig_plan.instructions.iter().for_each(|instruction| {
-                position = position.move_by(&instruction.direction, &instruction.distance);
-                min_position = min_position.min(&position);
-                max_position = max_position.max(&position);
+                position = position
+                    .moved(instruction.direction, instruction.distance as i64)
+                    .unwrap();
+                min_position = min_position.min_each(&position);
+                max_position = max_position.max_each(&position);
             });
 
             // pad out a little
             (
-                Position::new(min_position.row() - 1, min_position.col() - 1),
-                Position::new(max_position.row() + 1, max_position.col() + 1),
+                Point2::new(min_position.row - 1, min_position.col - 1),
+                Point2::new(max_position.row + 1, max_position.col + 1),
             )
         };
 
         // init data
-        let nrows = usize::try_from(max_position.row() - min_position.row() + 1).unwrap();
-        let ncols = usize::try_from(max_position.col() - min_position.col() + 1).unwrap();
-        let mut dug = Array2::from_elem((nrows, ncols), false);
+        let nrows = usize::try_from(max_position.row - min_position.row + 1).unwrap();
+        let ncols = usize::try_from(max_position.col - min_position.col + 1).unwrap();
+        let mut dug = Grid::from_elem(nrows, ncols, false);
 
         // dig trench
         {
-            let mut position = Position::new(0, 0);
+            let mut position = Point2::new(0, 0);
             dig_plan.instructions.iter().for_each(|instruction| {
                 (1..=instruction.distance).for_each(|_| {
-                    position = position.move_by(&instruction.direction, &1);
-                    dug[position.index_from(&min_position)] = true;
+                    position = position.step(instruction.direction).unwrap();
+                    let [row, col] = <[i64; 2]>::from(position - min_position);
+                    dug[[row as usize, col as usize]] = true;
                 })
             });
         }
@@ -205,49 +192,41 @@ impl Lagoon {
             let mut inside = false;
             let mut last = None;
             for icol in 1..(ncols - 1) {
-                match Lagoon::check_area(&trench, irow, icol) {
-                    ElementType::BottomLeft => {
-                        // println!("{} {} BL", irow, icol);
-                        assert!(last.is_none());
+                let touching = || {
+                    let position = min_position + Point2::new(irow as i64, icol as i64);
+                    Error::new(format!("the trench touches itself at {}", position))
+                };
+                match Lagoon::check_area(&trench, irow, icol).ok_or_else(touching)? {
+                    ElementType::BottomLeft if last.is_none() => {
                         last = Some(ElementType::BottomLeft);
                     }
                     ElementType::Empty => (),
-                    ElementType::UpperLeft => {
-                        // println!("{} {} UL", irow, icol);
-                        assert!(last.is_none());
-                        last = Some(ElementType::UpperLeft)
-                    }
-                    ElementType::UpperRight => {
-                        // println!("{} {} UR", irow, icol);
-                        match last {
-                            Some(ElementType::BottomLeft) => {
-                                inside = !inside;
-                                last = None;
-                            }
-                            Some(ElementType::UpperLeft) => {
-                                last = None;
-                            }
-                            _ => panic!(),
+                    ElementType::UpperLeft if last.is_none() => last = Some(ElementType::UpperLeft),
+                    ElementType::UpperRight => match last {
+                        Some(ElementType::BottomLeft) => {
+                            inside = !inside;
+                            last = None;
                         }
-                    }
-                    ElementType::BottomRight => {
-                        // println!("{} {} BR", irow, icol);
-                        match last {
-                            Some(ElementType::BottomLeft) => {
-                                last = None;
-                            }
-                            Some(ElementType::UpperLeft) => {
-                                inside = !inside;
-                                last = None;
-                            }
-                            _ => panic!(),
+                        Some(ElementType::UpperLeft) => {
+                            last = None;
                         }
-                    }
+                        _ => return Err(touching()),
+                    },
+                    ElementType::BottomRight => match last {
+                        Some(ElementType::BottomLeft) => {
+                            last = None;
+                        }
+                        Some(ElementType::UpperLeft) => {
+                            inside = !inside;
+                            last = None;
+                        }
+                        _ => return Err(touching()),
+                    },
                     ElementType::Virtical => {
-                        // println!("{} {} V", irow, icol);
                         inside = !inside;
                     }
                     ElementType::Horizontal => (),
+                    _ => return Err(touching()),
                 }
                 if inside {
                     dug[[irow, icol]] = true;
@@ -255,10 +234,10 @@ impl Lagoon {
             }
         }
 
-        Lagoon {
+        Ok(Lagoon {
             dug,
             offset: min_position,
-        }
+        })
     }
 
     fn count(&self) -> usize {
@@ -268,23 +247,17 @@ impl Lagoon {
 
 impl Display for Lagoon {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "Lagoon: Offset: {}", self.offset).unwrap();
-        self.dug.rows().into_iter().for_each(|row| {
-            row.iter().for_each(|elem| match elem {
-                true => write!(f, "#").unwrap(),
-                false => write!(f, ".").unwrap(),
-            });
-            writeln!(f, "").unwrap();
-        });
-        Ok(())
+        writeln!(f, "Lagoon: Offset: {}", self.offset)?;
+        let dug = self.dug.map(|&dug| if dug { '#' } else { '.' });
+        write!(f, "{}", dug)
     }
 }
 
-pub fn run(input: &str) -> usize {
-    let plan = input.parse::<DigPlan>().unwrap();
-    let lagoon = Lagoon::new(&plan);
-    println!("{}", lagoon);
-    lagoon.count()
+pub fn run(input: &str) -> Result<usize, Error> {
+    let plan = input.parse::<DigPlan>()?;
+    let lagoon = Lagoon::new(&plan)?;
+    debug!("{}", lagoon);
+    Ok(lagoon.count())
 }
 
 #[cfg(test)]
@@ -292,6 +265,21 @@ mod tests {
     #[test]
     fn test1() {
         let input = include_str!("example_data.txt");
-        assert_eq!(super::run(input), 62);
+        assert_eq!(super::run(input).unwrap(), 62);
+        let error = super::run("R 6 (#70c710)\nX 5 (#0dc571)").unwrap_err();
+        assert_eq!(error.to_string(), "line 2, column 1: Unknown direction `X`");
+        let error = super::run("R 6 (#70c710)\nD 5 (#0dc571)").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2: expected the trench to end where it starts, it ends at (5, 6)"
+        );
+        let error = super::run("R 2 (#70c710)\nL 2 (#0dc571)").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 1: expected a turn after digging E, found W"
+        );
+        let error = super::run("R 1 (#70c710)\nD 0 (#0dc571)").unwrap_err();
+        assert_eq!(error.to_string(), "line 2: expected a distance above 0");
+        assert!(super::run("").is_err());
     }
 }
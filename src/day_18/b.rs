@@ -1,34 +1,20 @@
 use std::{fmt::Display, str::FromStr};
 
-#[derive(Debug)]
-enum Direction {
-    U,
-    D,
-    L,
-    R,
-}
-
-impl Display for Direction {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Direction::U => write!(f, "U"),
-            Direction::D => write!(f, "D"),
-            Direction::L => write!(f, "L"),
-            Direction::R => write!(f, "R"),
-        }
-    }
-}
-
-impl FromStr for Direction {
-    type Err = &'static str;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "0" => Ok(Direction::R),
-            "1" => Ok(Direction::D),
-            "2" => Ok(Direction::L),
-            "3" => Ok(Direction::U),
-            _ => Err("Unknown direction"),
-        }
+use super::a::check_plan;
+use crate::{
+    debug,
+    error::{parse_lines, Error},
+    geometry::{Direction, Point2},
+};
+
+/// Parse a dig direction given as the last hex digit of a color
+fn parse_direction(s: &str) -> Result<Direction, Error> {
+    match s {
+        "0" => Ok(Direction::E),
+        "1" => Ok(Direction::S),
+        "2" => Ok(Direction::W),
+        "3" => Ok(Direction::N),
+        _ => Err(Error::new(format!("Unknown direction `{}`", s))),
     }
 }
 
@@ -45,14 +31,23 @@ impl Display for DigInstruction {
 }
 
 impl FromStr for DigInstruction {
-    type Err = &'static str;
+    type Err = Error;
     /// Read in something like "R 10 (#ffffff)"
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut s = s.split_whitespace().skip(2).next().unwrap();
-        s = &s[2..8];
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let color = line
+            .split_whitespace()
+            .nth(2)
+            .ok_or("expected direction, distance and color")?;
+        let s = color
+            .strip_prefix("(#")
+            .and_then(|s| s.strip_suffix(')'))
+            .filter(|s| s.len() == 6)
+            .ok_or_else(|| Error::new("expected a color like `(#70c710)`").at_token(line, color))?;
         let (distance, direction) = s.split_at(5);
-        let distance = usize::from_str_radix(distance, 16).unwrap();
-        let direction = direction.parse::<Direction>().unwrap();
+        let distance = usize::from_str_radix(distance, 16).map_err(|_| {
+            Error::new(format!("bad hex distance `{}`", distance)).at_token(line, distance)
+        })?;
+        let direction = parse_direction(direction).map_err(|e| e.at_token(line, direction))?;
         Ok(DigInstruction {
             direction,
             distance,
@@ -75,49 +70,27 @@ impl Display for DigPlan {
 }
 
 impl FromStr for DigPlan {
-    type Err = &'static str;
+    type Err = Error;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(DigPlan {
-            instructions: s
-                .lines()
-                .map(|line| line.parse::<DigInstruction>().unwrap())
-                .collect::<Vec<_>>(),
-        })
-    }
-}
-
-#[derive(Debug, Clone, Copy)]
-struct Position {
-    index: [i64; 2],
-}
-
-impl Display for Position {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({}, {})", self.index[0], self.index[1])
+        let instructions = parse_lines::<DigInstruction>(s)?;
+        let steps = instructions
+            .iter()
+            .map(|instruction| (instruction.direction, instruction.distance))
+            .collect::<Vec<_>>();
+        check_plan(&steps)?;
+        Ok(DigPlan { instructions })
     }
 }
 
-impl Position {
-    fn new(row: i64, col: i64) -> Position {
-        Position { index: [row, col] }
-    }
-
-    fn row(&self) -> i64 {
-        self.index[0]
-    }
-
-    fn col(&self) -> i64 {
-        self.index[1]
-    }
-
-    fn move_by(&self, direction: &Direction, distance: &usize) -> Position {
-        let distance = *distance as i64;
-        match direction {
-            Direction::D => Position::new(self.row() + distance, self.col()),
-            Direction::U => Position::new(self.row() - distance, self.col()),
-            Direction::L => Position::new(self.row(), self.col() - distance),
-            Direction::R => Position::new(self.row(), self.col() + distance),
-        }
+/// Offset from the cell where digging `direction` turns to `next` to the
+/// corner of the lagoon's outline there, digging clockwise
+fn corner(direction: Direction, next: Direction) -> Option<[i64; 2]> {
+    match (direction, next) {
+        (Direction::W, Direction::N) | (Direction::N, Direction::W) => Some([1, 0]),
+        (Direction::W, Direction::S) | (Direction::S, Direction::W) => Some([1, 1]),
+        (Direction::N, Direction::E) | (Direction::E, Direction::N) => Some([0, 0]),
+        (Direction::S, Direction::E) | (Direction::E, Direction::S) => Some([0, 1]),
+        _ => None,
     }
 }
 
@@ -128,7 +101,7 @@ struct PolyLagoon {
 
 #[derive(Debug)]
 struct Segment {
-    points: [Position; 2],
+    points: [Point2<i64>; 2],
 }
 
 impl Display for Segment {
@@ -138,15 +111,15 @@ impl Display for Segment {
 }
 
 impl Segment {
-    fn new(a: Position, b: Position) -> Segment {
+    fn new(a: Point2<i64>, b: Point2<i64>) -> Segment {
         let points = [a, b];
         Segment { points }
     }
     fn rows(&self) -> [i64; 2] {
-        [self.points[0].row(), self.points[1].row()]
+        [self.points[0].row, self.points[1].row]
     }
     fn cols(&self) -> [i64; 2] {
-        [self.points[0].col(), self.points[1].col()]
+        [self.points[0].col, self.points[1].col]
     }
     fn vertical(&self) -> bool {
         let rows = self.rows();
@@ -173,76 +146,39 @@ impl Segment {
 }
 
 impl PolyLagoon {
-    fn new(dig_plan: &DigPlan) -> PolyLagoon {
+    fn new(dig_plan: &DigPlan) -> Result<PolyLagoon, Error> {
         // create vertices
-        let mut position = Position::new(0, 0);
+        let mut position = Point2::new(0, 0);
         let mut vertices = Vec::new();
-        dig_plan
-            .instructions
-            .iter()
-            .enumerate()
-            .for_each(|(i, instruction)| {
-                let next_instruction = match dig_plan.instructions.get(i + 1) {
-                    Some(ni) => ni,
-                    None => dig_plan.instructions.first().unwrap(),
-                };
-                position = position.move_by(&instruction.direction, &instruction.distance);
-                match instruction.direction {
-                    Direction::L => match next_instruction.direction {
-                        Direction::U => {
-                            vertices.push(Position::new(position.row() + 1, position.col()));
-                        }
-                        Direction::D => {
-                            vertices.push(Position::new(position.row() + 1, position.col() + 1));
-                        }
-                        _ => panic!(),
-                    },
-                    Direction::U => match next_instruction.direction {
-                        Direction::L => {
-                            vertices.push(Position::new(position.row() + 1, position.col()));
-                        }
-                        Direction::R => {
-                            vertices.push(Position::new(position.row(), position.col()));
-                        }
-                        _ => panic!(),
-                    },
-                    Direction::D => match next_instruction.direction {
-                        Direction::L => {
-                            vertices.push(Position::new(position.row() + 1, position.col() + 1));
-                        }
-                        Direction::R => {
-                            vertices.push(Position::new(position.row(), position.col() + 1));
-                        }
-                        _ => panic!(),
-                    },
-                    Direction::R => match next_instruction.direction {
-                        Direction::U => {
-                            vertices.push(Position::new(position.row(), position.col()));
-                        }
-                        Direction::D => {
-                            vertices.push(Position::new(position.row(), position.col() + 1));
-                        }
-                        _ => panic!(),
-                    },
-                }
-            });
-
-        // vertices.iter().for_each(|v| println!("{}", v));
+        let instructions = &dig_plan.instructions;
+        for (i, instruction) in instructions.iter().enumerate() {
+            let next = &instructions[(i + 1) % instructions.len()];
+            position = position
+                .moved(instruction.direction, instruction.distance as i64)
+                .ok_or_else(|| Error::new("the trench goes too far").at_line(i + 1))?;
+            let [drow, dcol] = corner(instruction.direction, next.direction).ok_or_else(|| {
+                Error::new(format!(
+                    "expected a turn after digging {}, found {}",
+                    instruction.direction, next.direction
+                ))
+                .at_line(i + 1)
+            })?;
+            vertices.push(Point2::new(position.row + drow, position.col + dcol));
+        }
 
         let mut segments = vertices
             .windows(2)
             .map(|positions| Segment::new(positions[0], positions[1]))
             .collect::<Vec<_>>();
-        segments.push(Segment::new(
-            *vertices.first().unwrap(),
-            *vertices.last().unwrap(),
-        ));
+        if let (Some(first), Some(last)) = (vertices.first(), vertices.last()) {
+            segments.push(Segment::new(*first, *last));
+        }
         let segments = segments
             .into_iter()
             .filter(|s| s.vertical())
             .collect::<Vec<_>>();
 
-        PolyLagoon { segments }
+        Ok(PolyLagoon { segments })
     }
 
     fn sorted_rows(&self) -> Vec<i64> {
@@ -256,7 +192,7 @@ impl PolyLagoon {
         rows
     }
 
-    fn count(&self) -> usize {
+    fn count(&self) -> Result<usize, Error> {
         let rows = self.sorted_rows();
         let mut area = 0;
         for row_slice in rows.windows(2) {
@@ -273,18 +209,21 @@ impl PolyLagoon {
                 cols.sort();
                 cols
             };
-            assert_eq!(cols.len() % 2, 0);
-            let width = cols
-                .chunks(2)
-                .map(|c| c[1] - c[0])
-                .inspect(|w| assert!(*w > 0))
-                .map(|w| w as usize)
-                .sum::<usize>();
-            let length = row_slice[1] - row_slice[0];
-            assert!(length > 0);
-            area += width * length as usize;
+            let crossing =
+                || Error::new(format!("the trench crosses itself at row {}", row_slice[0]));
+            if cols.len() % 2 != 0 {
+                return Err(crossing());
+            }
+            let mut width = 0;
+            for c in cols.chunks(2) {
+                if c[1] <= c[0] {
+                    return Err(crossing());
+                }
+                width += (c[1] - c[0]) as usize;
+            }
+            area += width * (row_slice[1] - row_slice[0]) as usize;
         }
-        area
+        Ok(area)
     }
 }
 
@@ -297,16 +236,17 @@ impl Display for PolyLagoon {
     }
 }
 
-pub fn run(input: &str) -> usize {
-    let plan = input.parse::<DigPlan>().unwrap();
-    let lagoon = PolyLagoon::new(&plan);
-    // println!("{}", lagoon);
+pub fn run(input: &str) -> Result<usize, Error> {
+    let plan = input.parse::<DigPlan>()?;
+    let lagoon = PolyLagoon::new(&plan)?;
+    debug!("{}", lagoon);
     lagoon.count()
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{DigInstruction, DigPlan, Direction, PolyLagoon};
+    use super::{DigInstruction, DigPlan, PolyLagoon};
+    use crate::geometry::Direction;
 
     impl DigInstruction {
         fn new(direction: Direction, distance: usize) -> DigInstruction {
@@ -326,19 +266,32 @@ mod tests {
     #[test]
     fn test1() {
         let input = include_str!("example_data.txt");
-        assert_eq!(super::run(input), 952_408_144_115);
+        assert_eq!(super::run(input).unwrap(), 952_408_144_115);
+        assert_eq!(
+            super::run("").unwrap_err().to_string(),
+            "expected dig instructions"
+        );
+        let error = super::run("R 6 (#000010)\nR 6 (#000020)\nR 6 (#000011)").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 1: expected a turn after digging E, found E"
+        );
+        let error = super::run("R 6 (#000060)\nD 5 (#000051)").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2: expected the trench to end where it starts, it ends at (5, 6)"
+        );
     }
     #[test]
     fn test_small() {
         let plan = DigPlan::new(vec![
-            DigInstruction::new(super::Direction::R, 1),
-            DigInstruction::new(super::Direction::D, 1),
-            DigInstruction::new(super::Direction::L, 1),
-            DigInstruction::new(super::Direction::U, 1),
+            DigInstruction::new(Direction::E, 1),
+            DigInstruction::new(Direction::S, 1),
+            DigInstruction::new(Direction::W, 1),
+            DigInstruction::new(Direction::N, 1),
         ]);
-        let lagoon = PolyLagoon::new(&plan);
-        // println!("{}", lagoon);
-        assert_eq!(lagoon.count(), 4);
+        let lagoon = PolyLagoon::new(&plan).unwrap();
+        assert_eq!(lagoon.count().unwrap(), 4);
     }
 
     #[test]
@@ -353,29 +306,28 @@ mod tests {
         // 6 ###.###
         // 7
         let plan = DigPlan::new(vec![
-            DigInstruction::new(super::Direction::R, 2),
-            DigInstruction::new(super::Direction::D, 1),
-            DigInstruction::new(super::Direction::R, 2),
-            DigInstruction::new(super::Direction::U, 1),
-            DigInstruction::new(super::Direction::R, 2),
-            DigInstruction::new(super::Direction::D, 2),
-            DigInstruction::new(super::Direction::L, 1),
-            DigInstruction::new(super::Direction::D, 2),
-            DigInstruction::new(super::Direction::R, 1),
-            DigInstruction::new(super::Direction::D, 2),
-            DigInstruction::new(super::Direction::L, 2),
-            DigInstruction::new(super::Direction::U, 1),
-            DigInstruction::new(super::Direction::L, 2),
-            DigInstruction::new(super::Direction::D, 1),
-            DigInstruction::new(super::Direction::L, 2),
-            DigInstruction::new(super::Direction::U, 2),
-            DigInstruction::new(super::Direction::R, 1),
-            DigInstruction::new(super::Direction::U, 2),
-            DigInstruction::new(super::Direction::L, 1),
-            DigInstruction::new(super::Direction::U, 2),
+            DigInstruction::new(Direction::E, 2),
+            DigInstruction::new(Direction::S, 1),
+            DigInstruction::new(Direction::E, 2),
+            DigInstruction::new(Direction::N, 1),
+            DigInstruction::new(Direction::E, 2),
+            DigInstruction::new(Direction::S, 2),
+            DigInstruction::new(Direction::W, 1),
+            DigInstruction::new(Direction::S, 2),
+            DigInstruction::new(Direction::E, 1),
+            DigInstruction::new(Direction::S, 2),
+            DigInstruction::new(Direction::W, 2),
+            DigInstruction::new(Direction::N, 1),
+            DigInstruction::new(Direction::W, 2),
+            DigInstruction::new(Direction::S, 1),
+            DigInstruction::new(Direction::W, 2),
+            DigInstruction::new(Direction::N, 2),
+            DigInstruction::new(Direction::E, 1),
+            DigInstruction::new(Direction::N, 2),
+            DigInstruction::new(Direction::W, 1),
+            DigInstruction::new(Direction::N, 2),
         ]);
-        let lagoon = PolyLagoon::new(&plan);
-        // println!("{}", lagoon);
-        assert_eq!(lagoon.count(), 45);
+        let lagoon = PolyLagoon::new(&plan).unwrap();
+        assert_eq!(lagoon.count().unwrap(), 45);
     }
 }
@@ -5,6 +5,9 @@ use std::{
     str::FromStr,
 };
 
+use super::a::check_destinations;
+use crate::error::{parse_token, Error};
+
 #[derive(PartialEq, Eq, PartialOrd, Ord, Debug)]
 enum Parameter {
     X,
@@ -14,14 +17,14 @@ enum Parameter {
 }
 
 impl FromStr for Parameter {
-    type Err = &'static str;
+    type Err = Error;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             "x" => Ok(Parameter::X),
             "m" => Ok(Parameter::M),
             "a" => Ok(Parameter::A),
             "s" => Ok(Parameter::S),
-            _ => Err("unknown parameter"),
+            _ => Err(Error::new(format!("unknown parameter `{}`", s))),
         }
     }
 }
@@ -33,25 +36,23 @@ enum Instruction {
     Goto(String),
 }
 impl FromStr for Instruction {
-    type Err = &'static str;
+    type Err = Error;
     /// Parse a<2006:qkq, m>2090:A, rfg
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.contains(">") {
-            let (parameter, s) = s.split_once(">").unwrap();
-            let (value, destination) = s.split_once(":").unwrap();
-            let value = value.parse::<usize>().unwrap();
-            let parameter = parameter.parse::<Parameter>().unwrap();
+        if let Some((parameter, rest)) = s.split_once('>') {
+            let (value, destination) = rest.split_once(':').ok_or("expected `:`")?;
+            let value = parse_token::<usize>(s, value)?;
+            let parameter = parameter.parse::<Parameter>()?;
             return Ok(Instruction::GreaterThan((
                 parameter,
                 value,
                 destination.to_string(),
             )));
         }
-        if s.contains("<") {
-            let (parameter, s) = s.split_once("<").unwrap();
-            let (value, destination) = s.split_once(":").unwrap();
-            let value = value.parse::<usize>().unwrap();
-            let parameter = parameter.parse::<Parameter>().unwrap();
+        if let Some((parameter, rest)) = s.split_once('<') {
+            let (value, destination) = rest.split_once(':').ok_or("expected `:`")?;
+            let value = parse_token::<usize>(s, value)?;
+            let parameter = parameter.parse::<Parameter>()?;
             return Ok(Instruction::LessThan((
                 parameter,
                 value,
@@ -72,18 +73,18 @@ impl Workflow {
         for x in &self.instructions {
             match x {
                 Instruction::Goto(destination) => {
-                    return &destination;
+                    return destination;
                 }
                 Instruction::LessThan((parameter, value, destination)) => {
                     let parameter = part.get_parameter(parameter);
                     if parameter < *value {
-                        return &destination;
+                        return destination;
                     }
                 }
                 Instruction::GreaterThan((parameter, value, destination)) => {
                     let parameter = part.get_parameter(parameter);
                     if parameter > *value {
-                        return &destination;
+                        return destination;
                     }
                 }
             }
@@ -131,16 +132,29 @@ struct NamedWorkflow {
 }
 
 impl FromStr for NamedWorkflow {
-    type Err = &'static str;
+    type Err = Error;
     fn from_str(line: &str) -> Result<Self, Self::Err> {
-        let (workflow_name, line) = line.split_once("{").unwrap();
-        let (line, _) = line.split_once("}").unwrap();
+        let (workflow_name, rules) = line
+            .split_once('{')
+            .and_then(|(name, rules)| Some((name, rules.strip_suffix('}')?)))
+            .ok_or("expected `name{rules}`")?;
         let workflow = Workflow {
-            instructions: line
-                .split(",")
-                .map(|s| s.parse::<Instruction>().unwrap())
-                .collect::<Vec<_>>(),
+            instructions: rules
+                .split(',')
+                .map(|s| {
+                    s.parse::<Instruction>()
+                        .map_err(|e| Error::new(e.message).at_token(line, s))
+                })
+                .collect::<Result<Vec<_>, _>>()?,
         };
+        if let Some(Instruction::LessThan(_) | Instruction::GreaterThan(_)) =
+            workflow.instructions.last()
+        {
+            let last = rules.rsplit(',').next().unwrap_or(rules);
+            return Err(
+                Error::new("expected the last rule to name a workflow").at_token(line, last)
+            );
+        }
         Ok(NamedWorkflow {
             name: workflow_name.to_string(),
             workflow,
@@ -171,18 +185,22 @@ impl Part {
 }
 
 impl FromStr for Part {
-    type Err = &'static str;
+    type Err = Error;
     /// Parse {x=787,m=2655,a=1222,s=2876}
     fn from_str(line: &str) -> Result<Self, Self::Err> {
-        let (_, line) = line.split_once("{").unwrap();
-        let (line, _) = line.split_once("}").unwrap();
+        let ratings = line
+            .strip_prefix('{')
+            .and_then(|line| line.strip_suffix('}'))
+            .ok_or("expected `{x=..,m=..,a=..,s=..}`")?;
         let mut x = None;
         let mut m = None;
         let mut a = None;
         let mut s = None;
-        line.split(",").for_each(|sub| {
-            let (symbol, value) = sub.split_once("=").unwrap();
-            let value = value.parse::<usize>().unwrap();
+        for sub in ratings.split(',') {
+            let (symbol, value) = sub
+                .split_once('=')
+                .ok_or_else(|| Error::new("expected `=`").at_token(line, sub))?;
+            let value = parse_token::<usize>(line, value)?;
             match symbol {
                 "x" => {
                     x = Some(value);
@@ -196,17 +214,18 @@ impl FromStr for Part {
                 "s" => {
                     s = Some(value);
                 }
-                _ => panic!(),
+                _ => {
+                    return Err(
+                        Error::new(format!("unknown rating `{}`", symbol)).at_token(line, symbol)
+                    )
+                }
             };
-        });
-        assert!(x.is_some());
-        assert!(m.is_some());
-        assert!(a.is_some());
-        assert!(s.is_some());
-        let x = x.unwrap();
-        let m = m.unwrap();
-        let a = a.unwrap();
-        let s = s.unwrap();
+        }
+        let missing = || Error::new("expected ratings for x, m, a and s");
+        let x = x.ok_or_else(missing)?;
+        let m = m.ok_or_else(missing)?;
+        let a = a.ok_or_else(missing)?;
+        let s = s.ok_or_else(missing)?;
         Ok(Part { x, m, a, s })
     }
 }
@@ -250,9 +269,9 @@ impl System {
     fn run_x_win(
         &self,
         x_win: &[usize],
-        m_breaks: &Vec<usize>,
-        a_breaks: &Vec<usize>,
-        s_breaks: &Vec<usize>,
+        m_breaks: &[usize],
+        a_breaks: &[usize],
+        s_breaks: &[usize],
     ) -> usize {
         let mut count = 0;
         for m_win in m_breaks.windows(2) {
@@ -275,8 +294,8 @@ impl System {
     fn get_breaks(&self, parameter: &Parameter) -> Vec<usize> {
         let ret = self
             .workflows
-            .iter()
-            .map(|(_, x)| x.get_breaks(parameter))
+            .values()
+            .map(|x| x.get_breaks(parameter))
             .collect::<Vec<_>>();
         let ret = ret.concat();
         let mut ret = ret.into_iter().collect::<BTreeSet<_>>();
@@ -289,23 +308,27 @@ impl System {
 }
 
 impl FromStr for System {
-    type Err = &'static str;
+    type Err = Error;
     /// Parse px{a<2006:qkq,m>2090:A,rfg} and {x=787,m=2655,a=1222,s=2876}
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let workflows = s
             .lines()
-            .filter(|line| line.len() > 0 && !line.starts_with("{"))
-            .map(|line| {
-                let named_workflow = line.parse::<NamedWorkflow>().unwrap();
-                (named_workflow.name, named_workflow.workflow)
+            .enumerate()
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('{'))
+            .map(|(iline, line)| {
+                let named_workflow = line
+                    .parse::<NamedWorkflow>()
+                    .map_err(|e| e.at_line(iline + 1))?;
+                Ok((named_workflow.name, named_workflow.workflow))
             })
-            .collect::<HashMap<String, Workflow>>();
+            .collect::<Result<HashMap<String, Workflow>, Error>>()?;
+        check_destinations(s, |name| workflows.contains_key(name))?;
         Ok(System { workflows })
     }
 }
 
-pub fn run(input: &str) -> usize {
-    input.parse::<System>().unwrap().run()
+pub fn run(input: &str) -> Result<usize, Error> {
+    Ok(input.parse::<System>()?.run())
 }
 
 #[cfg(test)]
@@ -313,6 +336,6 @@ mod tests {
     #[test]
     fn test1() {
         let input = include_str!("example_data.txt");
-        assert_eq!(super::run(input), 167409079868000);
+        assert_eq!(super::run(input).unwrap(), 167409079868000);
     }
 }
@@ -1,69 +1,85 @@
-pub fn day_2() {
-    let input = include_str!("day_2_data.txt");
-    println!("day 2a {}", day_2a(input));
-    println!("day 2b {}", day_2b(input));
+use crate::{
+    error::{parse_token, Error, Result},
+    solution::{Answer, Solution},
+};
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    fn part_a(&self, input: &str) -> Result<Answer> {
+        Ok(day_2a(input)?.into())
+    }
+    fn part_b(&self, input: &str) -> Result<Answer> {
+        Ok(day_2b(input)?.into())
+    }
 }
 
-fn day_2a(input: &str) -> u32 {
-    let mut sum: u32 = 0;
-    for line in input.split("\n") {
-        let (game_str, data_str) = line.split_at(line.find(":").unwrap());
-        let game_num = game_str[game_str.find("Game ").unwrap() + 5..]
-            .parse::<u32>()
-            .unwrap();
-        let mut possible = true;
-        for grab_str in data_str[1..].split(";") {
-            for count_str in grab_str.split(",") {
-                let count_str = &count_str[1..];
-                let (num, color) = count_str.split_at(count_str.find(" ").unwrap());
-                let color = color.trim();
-                let num = num.parse::<u32>().unwrap();
-                possible &= match color {
-                    "red" => num <= 12,
-                    "green" => num <= 13,
-                    "blue" => num <= 14,
-                    other => panic!("Got bad color with len {}: {}", other.len(), &other),
-                };
+/// Parse a line like `Game 1: 3 blue, 4 red; 1 red` into the game number and
+/// every count and color shown
+fn parse_game(line: &str) -> Result<(u32, Vec<(u32, &str)>)> {
+    let (game_str, data_str) = line.split_at(line.find(':').ok_or("expected `:`")?);
+    let game_num = game_str
+        .strip_prefix("Game ")
+        .ok_or_else(|| Error::new("expected `Game `").at_column(1))?;
+    let game_num = parse_token::<u32>(line, game_num)?;
+    let mut counts = Vec::new();
+    for grab_str in data_str[1..].split(';') {
+        for count_str in grab_str.split(',') {
+            let count_str = count_str.trim_start();
+            let (num, color) = count_str.split_once(' ').ok_or_else(|| {
+                Error::new(format!("expected a count and color, got `{}`", count_str))
+            })?;
+            let num = parse_token::<u32>(line, num)?;
+            let color = color.trim();
+            match color {
+                "red" | "green" | "blue" => counts.push((num, color)),
+                other => return Err(Error::new(format!("bad color `{}`", other))),
             }
         }
+    }
+    Ok((game_num, counts))
+}
+
+fn day_2a(input: &str) -> Result<u32> {
+    let mut sum: u32 = 0;
+    for (iline, line) in input.split('\n').enumerate() {
+        let (game_num, counts) = parse_game(line).map_err(|e| e.at_line(iline + 1))?;
+        let possible = counts.iter().all(|(num, color)| match *color {
+            "red" => *num <= 12,
+            "green" => *num <= 13,
+            _ => *num <= 14,
+        });
         if possible {
             sum += game_num;
         }
     }
-    sum
+    Ok(sum)
 }
 
-fn day_2b(input: &str) -> u32 {
+fn day_2b(input: &str) -> Result<u32> {
     let mut sum: u32 = 0;
-    for line in input.split("\n") {
-        let (_game_str, data_str) = line.split_at(line.find(":").unwrap());
+    for (iline, line) in input.split('\n').enumerate() {
+        let (_game_num, counts) = parse_game(line).map_err(|e| e.at_line(iline + 1))?;
         let mut max_red: u32 = 0;
         let mut max_green: u32 = 0;
         let mut max_blue: u32 = 0;
-        for grab_str in data_str[1..].split(";") {
-            for count_str in grab_str.split(",") {
-                let count_str = &count_str[1..];
-                let (num, color) = count_str.split_at(count_str.find(" ").unwrap());
-                let color = color.trim();
-                let num = num.parse::<u32>().unwrap();
-                match color {
-                    "red" => {
-                        max_red = max_red.max(num);
-                    }
-                    "green" => {
-                        max_green = max_green.max(num);
-                    }
-                    "blue" => {
-                        max_blue = max_blue.max(num);
-                    }
-                    other => panic!("Got bad color with len {}: {}", other.len(), &other),
-                };
-            }
+        for (num, color) in counts {
+            match color {
+                "red" => {
+                    max_red = max_red.max(num);
+                }
+                "green" => {
+                    max_green = max_green.max(num);
+                }
+                _ => {
+                    max_blue = max_blue.max(num);
+                }
+            };
         }
         let power = max_red * max_blue * max_green;
         sum += power;
     }
-    sum
+    Ok(sum)
 }
 
 #[cfg(test)]
@@ -75,7 +91,14 @@ Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
 Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
 Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
 Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green"#;
-        assert_eq!(super::day_2a(input), 8);
-        assert_eq!(super::day_2b(input), 2286);
+        assert_eq!(super::day_2a(input).unwrap(), 8);
+        assert_eq!(super::day_2b(input).unwrap(), 2286);
+    }
+
+    #[test]
+    fn test_bad_color() {
+        let input = "Game 1: 3 blue\nGame 2: 1 purple";
+        let error = super::day_2a(input).unwrap_err();
+        assert_eq!(error.to_string(), "line 2: bad color `purple`");
     }
 }
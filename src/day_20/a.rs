@@ -1,13 +1,25 @@
-use std::{collections::HashMap, str::FromStr};
+use std::{collections::HashMap, fmt::Display, str::FromStr};
 
 use itertools::Itertools;
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
-enum Pulse {
+use super::trace::Trace;
+use crate::{debug, error::Error};
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum Pulse {
     High,
     Low,
 }
 
+impl Display for Pulse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Pulse::High => write!(f, "high"),
+            Pulse::Low => write!(f, "low"),
+        }
+    }
+}
+
 #[derive(Clone)]
 struct DirectedPulse {
     source: String,
@@ -31,7 +43,7 @@ impl DirectedPulse {
             .collect::<Vec<_>>()
     }
 }
-trait Module {
+trait Module: Display {
     fn run(&mut self, pulse: &DirectedPulse) -> Option<Vec<DirectedPulse>>;
     fn get_name(&self) -> &str;
     fn get_destinations(&self) -> &Vec<String>;
@@ -42,6 +54,15 @@ enum State {
     On,
     Off,
 }
+
+impl Display for State {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            State::On => write!(f, "on"),
+            State::Off => write!(f, "off"),
+        }
+    }
+}
 struct FlipFlop {
     state: State,
     name: String,
@@ -49,12 +70,12 @@ struct FlipFlop {
 }
 
 impl FromStr for FlipFlop {
-    type Err = &'static str;
+    type Err = Error;
 
     /// Parse things like "%zs -> db, fx"
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (_, s) = s.split_once("%").unwrap();
-        let (name, s) = s.split_once(" -> ").unwrap();
+        let s = s.strip_prefix('%').ok_or("expected `%`")?;
+        let (name, s) = s.split_once(" -> ").ok_or("expected ` -> `")?;
         let destinations = s
             .split(", ")
             .map(|s| s.to_string())
@@ -73,6 +94,12 @@ impl FlipFlop {
     }
 }
 
+impl Display for FlipFlop {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "%{} state:{}", self.name, self.state)
+    }
+}
+
 impl Module for FlipFlop {
     fn run(&mut self, pulse: &DirectedPulse) -> Option<Vec<DirectedPulse>> {
         match pulse.pulse {
@@ -116,11 +143,11 @@ struct Conjunction {
 }
 
 impl FromStr for Conjunction {
-    type Err = &'static str;
+    type Err = Error;
     /// Parse things like "&sd -> mh, tx, sh, xf, zn, xs"
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (_, s) = s.split_once("&").unwrap();
-        let (name, s) = s.split_once(" -> ").unwrap();
+        let s = s.strip_prefix('&').ok_or("expected `&`")?;
+        let (name, s) = s.split_once(" -> ").ok_or("expected ` -> `")?;
         let destinations = s.split(", ").map(|s| s.to_string()).collect::<Vec<_>>();
         Ok(Conjunction::new(name.to_string(), Vec::new(), destinations))
     }
@@ -143,6 +170,18 @@ impl Conjunction {
     }
 }
 
+impl Display for Conjunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mem_str = self
+            .memory
+            .iter()
+            .sorted()
+            .map(|(name, pulse)| format!("{}:{}", name, pulse))
+            .join(", ");
+        write!(f, "&{} memory:{}", self.name, mem_str)
+    }
+}
+
 impl Module for Conjunction {
     fn run(&mut self, pulse: &DirectedPulse) -> Option<Vec<DirectedPulse>> {
         let mem = self.memory.get_mut(&pulse.source).unwrap();
@@ -180,10 +219,12 @@ struct Broadcast {
 }
 
 impl FromStr for Broadcast {
-    type Err = &'static str;
+    type Err = Error;
     /// Parse things like "broadcaster -> a, b, c"
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (_, s) = s.split_once("broadcaster -> ").unwrap();
+        let s = s
+            .strip_prefix("broadcaster -> ")
+            .ok_or("expected `broadcaster -> `")?;
         let destinations = s.split(", ").map(|s| s.to_string()).collect::<Vec<_>>();
         Ok(Broadcast::new(destinations))
     }
@@ -195,6 +236,12 @@ impl Broadcast {
     }
 }
 
+impl Display for Broadcast {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "broadcast")
+    }
+}
+
 impl Module for Broadcast {
     fn run(&mut self, pulse: &DirectedPulse) -> Option<Vec<DirectedPulse>> {
         Some(DirectedPulse::from_vec(
@@ -217,6 +264,10 @@ impl Module for Broadcast {
 
 struct System {
     modules: HashMap<String, Box<dyn Module>>,
+    /// Button presses so far
+    press: usize,
+    /// Every pulse delivered, when tracing
+    trace: Option<Trace>,
 }
 
 impl System {
@@ -224,72 +275,118 @@ impl System {
         if let Some(module) = self.modules.get_mut(&pulse.destination) {
             module.run(pulse)
         } else {
-            // println!(
-            //     "tried to send pulse to non-existant module {}",
-            //     pulse.destination
-            // );
             None
         }
     }
 
     fn process_pulses(&mut self, pulses: &Vec<DirectedPulse>) -> Vec<DirectedPulse> {
+        if let Some(trace) = &mut self.trace {
+            for pulse in pulses {
+                trace.record(self.press, &pulse.source, &pulse.destination, pulse.pulse);
+            }
+        }
         pulses
             .iter()
-            .map(|pulse| self.process_pulse(pulse))
-            .filter(|x| x.is_some())
-            .map(|x| x.unwrap())
+            .filter_map(|pulse| self.process_pulse(pulse))
             .collect::<Vec<_>>()
             .concat()
     }
 
-    fn run(&mut self) -> usize {
+    /// Press the button once, returning the number of low and high pulses
+    /// sent
+    fn press(&mut self) -> (usize, usize) {
         let mut low_count = 0;
         let mut high_count = 0;
-        for _ in 0..1000 {
-            let mut pulses = vec![DirectedPulse::new(
-                "button".to_string(),
-                "broadcast".to_string(),
-                Pulse::Low,
-            )];
-            while !pulses.is_empty() {
-                pulses.iter().for_each(|p| match p.pulse {
-                    Pulse::High => {
-                        high_count += 1;
-                    }
-                    Pulse::Low => {
-                        low_count += 1;
-                    }
-                });
-                pulses = self.process_pulses(&pulses);
-            }
+        self.press += 1;
+        let mut pulses = vec![DirectedPulse::new(
+            "button".to_string(),
+            "broadcast".to_string(),
+            Pulse::Low,
+        )];
+        while !pulses.is_empty() {
+            pulses.iter().for_each(|p| match p.pulse {
+                Pulse::High => {
+                    high_count += 1;
+                }
+                Pulse::Low => {
+                    low_count += 1;
+                }
+            });
+            pulses = self.process_pulses(&pulses);
         }
+        (low_count, high_count)
+    }
+
+    fn run(&mut self) -> usize {
+        let (low_count, high_count) = (0..1000)
+            .map(|_| self.press())
+            .fold((0, 0), |(low, high), (l, h)| (low + l, high + h));
         low_count * high_count
     }
+
+    /// Deliver the pulses of `trace` up to the end of press `press` without
+    /// sending any on, leaving every module as it was then
+    fn replay(&mut self, trace: &Trace, press: usize) -> Result<(), Error> {
+        for (i, traced) in trace.pulses.iter().enumerate() {
+            if traced.press > press {
+                break;
+            }
+            let wired = match self.modules.get(&traced.source) {
+                Some(module) => module.get_destinations().contains(&traced.destination),
+                None => traced.source == "button" && traced.destination == "broadcast",
+            };
+            if !wired {
+                return Err(Error::new(format!(
+                    "no wire from {} to {}",
+                    traced.source, traced.destination
+                ))
+                .at_line(i + 1));
+            }
+            let pulse = DirectedPulse::new(
+                traced.source.clone(),
+                traced.destination.clone(),
+                traced.pulse,
+            );
+            self.process_pulse(&pulse);
+            self.press = traced.press;
+        }
+        Ok(())
+    }
+
+    /// State of every module, by name
+    fn states(&self) -> Vec<String> {
+        self.modules
+            .iter()
+            .sorted_by_key(|(name, _)| *name)
+            .map(|(_, module)| module.to_string())
+            .collect()
+    }
 }
 
 /// Parse things like "broadcaster -> a, b, c" and "%a -> b"
-fn parse_module(line: &str) -> Box<dyn Module> {
+fn parse_module(line: &str) -> Result<Box<dyn Module>, Error> {
     if line.starts_with("broadcaster") {
-        Box::new(line.parse::<Broadcast>().unwrap())
-    } else if line.starts_with("%") {
-        Box::new(line.parse::<FlipFlop>().unwrap())
-    } else if line.starts_with("&") {
-        Box::new(line.parse::<Conjunction>().unwrap())
+        Ok(Box::new(line.parse::<Broadcast>()?))
+    } else if line.starts_with('%') {
+        Ok(Box::new(line.parse::<FlipFlop>()?))
+    } else if line.starts_with('&') {
+        Ok(Box::new(line.parse::<Conjunction>()?))
     } else {
-        panic!()
+        Err(Error::new("expected `broadcaster`, `%` or `&`").at_column(1))
     }
 }
 impl FromStr for System {
-    type Err = &'static str;
+    type Err = Error;
     /// Parse things like "broadcaster -> a, b, c" and "%a -> b"
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut modules = s
             .lines()
-            .map(|line| {
-                let module = parse_module(line);
-                (module.get_name().to_string(), module)
+            .enumerate()
+            .map(|(iline, line)| {
+                let module = parse_module(line).map_err(|e| e.at_line(iline + 1))?;
+                Ok((module.get_name().to_string(), module))
             })
-            .collect::<HashMap<_, _>>();
+            .collect::<Result<HashMap<_, _>, Error>>()?;
         let source_dest = modules
             .values()
             .map(|module| {
@@ -305,27 +402,104 @@ impl FromStr for System {
             if let Some(module) = modules.get_mut(d) {
                 module.add_source(s);
             } else {
-                println!("tried to add source for non-existant module {}", d);
+                debug!("tried to add source for non-existent module {}", d);
             }
         });
-        Ok(System { modules })
+        Ok(System {
+            modules,
+            press: 0,
+            trace: None,
+        })
     }
 }
 
-pub fn run(input: &str) -> usize {
-    input.parse::<System>().unwrap().run()
+pub fn run(input: &str) -> Result<usize, Error> {
+    Ok(input.parse::<System>()?.run())
+}
+
+/// Every pulse delivered pressing the button `presses` times
+pub fn trace(input: &str, presses: usize) -> Result<Trace, Error> {
+    let mut system = input.parse::<System>()?;
+    system.trace = Some(Trace::default());
+    (0..presses).for_each(|_| {
+        system.press();
+    });
+    Ok(system.trace.unwrap())
+}
+
+/// State of every module in `input` at the end of press `press` of `trace`
+pub fn replay(input: &str, trace: &Trace, press: usize) -> Result<Vec<String>, Error> {
+    if press > trace.presses() {
+        return Err(Error::new(format!(
+            "the trace ends at press {}",
+            trace.presses()
+        )));
+    }
+    let mut system = input.parse::<System>()?;
+    system.replay(trace, press)?;
+    Ok(system.states())
 }
 
 #[cfg(test)]
 mod tests {
+    use super::System;
+
     #[test]
     fn test1() {
         let input = include_str!("example_data.txt");
-        assert_eq!(super::run(input), 32_000_000);
+        assert_eq!(super::run(input).unwrap(), 32_000_000);
     }
     #[test]
     fn test2() {
         let input = include_str!("example_data_2.txt");
-        assert_eq!(super::run(input), 11_687_500);
+        assert_eq!(super::run(input).unwrap(), 11_687_500);
+    }
+    #[test]
+    fn test_trace() {
+        let input = include_str!("example_data.txt");
+        let trace = super::trace(input, 1).unwrap();
+        assert_eq!(trace.pulses.len(), 12);
+        assert_eq!(trace.pulses[11].step, 11);
+        assert_eq!(
+            super::replay(input, &trace, 1).unwrap(),
+            [
+                "%a state:off",
+                "%b state:off",
+                "broadcast",
+                "%c state:off",
+                "&inv memory:c:low"
+            ]
+        );
+
+        // replaying gets every module where running it did
+        let input = include_str!("example_data_2.txt");
+        let trace = super::trace(input, 4).unwrap();
+        let mut system = input.parse::<System>().unwrap();
+        for press in 0..=4 {
+            assert_eq!(
+                super::replay(input, &trace, press).unwrap(),
+                system.states()
+            );
+            system.press();
+        }
+        assert_eq!(
+            super::replay(input, &trace, 1).unwrap()[3],
+            "&con memory:a:high, b:high"
+        );
+
+        let error = super::replay(input, &trace, 5).unwrap_err();
+        assert_eq!(error.to_string(), "the trace ends at press 4");
+        let mut trace = trace;
+        trace.pulses[1].destination = "b".to_string();
+        let error = super::replay(input, &trace, 1).unwrap_err();
+        assert_eq!(error.to_string(), "line 2: no wire from broadcast to b");
+    }
+    #[test]
+    fn test_bad_module() {
+        let error = super::run("broadcaster -> a\n%a -> b\n$b -> a").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 3, column 1: expected `broadcaster`, `%` or `&`"
+        );
     }
 }
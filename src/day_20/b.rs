@@ -2,6 +2,8 @@ use itertools::Itertools;
 use num::Integer;
 use std::{collections::HashMap, fmt::Display, str::FromStr};
 
+use crate::{debug, error::Error, info, trace};
+
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
 enum Pulse {
     High,
@@ -37,7 +39,6 @@ trait Module: Display {
     fn get_destinations(&self) -> &Vec<String>;
     fn get_memory(&self) -> Option<&HashMap<String, Pulse>>;
     fn add_source(&mut self, source: &str);
-    fn all_high(&self) -> Option<bool>;
 }
 
 #[derive(Clone, Copy)]
@@ -54,12 +55,12 @@ struct FlipFlop {
 }
 
 impl FromStr for FlipFlop {
-    type Err = &'static str;
+    type Err = Error;
 
     /// Parse things like "%zs -> db, fx"
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (_, s) = s.split_once("%").unwrap();
-        let (name, s) = s.split_once(" -> ").unwrap();
+        let s = s.strip_prefix('%').ok_or("expected `%`")?;
+        let (name, s) = s.split_once(" -> ").ok_or("expected ` -> `")?;
         let destinations = s
             .split(", ")
             .map(|s| s.to_string())
@@ -127,7 +128,7 @@ impl Module for FlipFlop {
                 State::On => {
                     if !self.reported {
                         self.reported = true;
-                        // println!("{} state: {}", self.name, self.state);
+                        trace!("{} state: {}", self.name, self.state);
                     }
                     self.state = State::Off;
                     Some(DirectedPulse::from_vec(
@@ -161,10 +162,6 @@ impl Module for FlipFlop {
     fn get_memory(&self) -> Option<&HashMap<String, Pulse>> {
         None
     }
-
-    fn all_high(&self) -> Option<bool> {
-        None
-    }
 }
 
 #[derive(Clone)]
@@ -176,11 +173,11 @@ struct Conjunction {
 }
 
 impl FromStr for Conjunction {
-    type Err = &'static str;
+    type Err = Error;
     /// Parse things like "&sd -> mh, tx, sh, xf, zn, xs"
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (_, s) = s.split_once("&").unwrap();
-        let (name, s) = s.split_once(" -> ").unwrap();
+        let s = s.strip_prefix('&').ok_or("expected `&`")?;
+        let (name, s) = s.split_once(" -> ").ok_or("expected ` -> `")?;
         let destinations = s.split(", ").map(|s| s.to_string()).collect::<Vec<_>>();
         Ok(Conjunction::new(name.to_string(), Vec::new(), destinations))
     }
@@ -211,7 +208,7 @@ impl Module for Conjunction {
         if self.all_high() {
             if !self.reported {
                 self.reported = true;
-                // println!("all high {}", self.name);
+                trace!("all high {}", self.name);
             }
             Some(DirectedPulse::from_vec(
                 self.name.clone(),
@@ -242,10 +239,6 @@ impl Module for Conjunction {
     fn get_memory(&self) -> Option<&HashMap<String, Pulse>> {
         Some(&self.memory)
     }
-
-    fn all_high(&self) -> Option<bool> {
-        Some(self.all_high())
-    }
 }
 
 #[derive(Clone)]
@@ -254,10 +247,12 @@ struct Broadcast {
 }
 
 impl FromStr for Broadcast {
-    type Err = &'static str;
+    type Err = Error;
     /// Parse things like "broadcaster -> a, b, c"
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (_, s) = s.split_once("broadcaster -> ").unwrap();
+        let s = s
+            .strip_prefix("broadcaster -> ")
+            .ok_or("expected `broadcaster -> `")?;
         let destinations = s.split(", ").map(|s| s.to_string()).collect::<Vec<_>>();
         Ok(Broadcast::new(destinations))
     }
@@ -291,76 +286,69 @@ impl Module for Broadcast {
     fn get_memory(&self) -> Option<&HashMap<String, Pulse>> {
         None
     }
-
-    fn all_high(&self) -> Option<bool> {
-        None
-    }
 }
 
+/// Presses after which `rx` is taken to never get a low pulse
+const MAX_PRESSES: usize = 1_000_000;
+
 struct System {
     modules: HashMap<String, Box<dyn Module>>,
     count: usize,
     done: bool,
-    lcm_data: HashMap<String, Option<usize>>,
+    /// Conjunction sending to `rx`, which only gets a low pulse once every
+    /// source of the hub has sent it a high pulse
+    hub: Option<String>,
+    /// Presses each source of the hub sent it a high pulse on, the first
+    /// two of them
+    cycles: HashMap<String, Vec<usize>>,
 }
 
 impl System {
     fn process_pulse(&mut self, pulse: &DirectedPulse) -> Option<Vec<DirectedPulse>> {
+        if pulse.pulse == Pulse::High && self.hub.as_ref() == Some(&pulse.destination) {
+            let presses = self.cycles.get_mut(&pulse.source).unwrap();
+            if presses.len() < 2 && presses.last() != Some(&self.count) {
+                presses.push(self.count);
+            }
+        }
         if let Some(module) = self.modules.get_mut(&pulse.destination) {
             module.run(pulse)
         } else if pulse.destination == "rx" && pulse.pulse == Pulse::Low {
-            println!("done after {} button presses", self.count);
+            info!("done after {} button presses", self.count);
             self.done = true;
             None
         } else {
-            // println!(
-            //     "tried to send pulse to non-existant module {} {:?} after {} button pushed",
-            //     pulse.destination, pulse.pulse, self.count
-            // );
-            // self.done = true;
             None
         }
     }
 
-    fn process_pulses(&mut self, pulses: &Vec<DirectedPulse>) -> Vec<DirectedPulse> {
-        self.lcm_data
-            .iter_mut()
-            .filter(|x| x.1.is_none())
-            .for_each(|x| {
-                if self.modules[x.0].all_high().unwrap() {
-                    *x.1 = Some(self.count);
-                }
-            });
-        if self.lcm_data.values().all(|x| x.is_some()) {
-            let data = self
-                .lcm_data
-                .values()
-                .map(|x| x.unwrap())
-                .collect::<Vec<_>>();
-            let lcm = data.into_iter().reduce(|acc, e| acc.lcm(&e)).unwrap();
-            println!("lcm {}", lcm);
-        }
+    fn process_pulses(&mut self, pulses: &[DirectedPulse]) -> Vec<DirectedPulse> {
         pulses
             .iter()
-            .map(|pulse| self.process_pulse(pulse))
-            .filter(|x| x.is_some())
-            .map(|x| x.unwrap())
+            .filter_map(|pulse| self.process_pulse(pulse))
             .collect::<Vec<_>>()
             .concat()
     }
 
-    fn run(&mut self) -> usize {
-        let mut bq_mem = self.modules["bq"].get_memory().unwrap().clone();
-        while !self.done && self.count < 10000 {
-            if *self.modules["bq"].get_memory().unwrap() != bq_mem {
-                println!("pressed: {}", self.count);
-                println!("bq {}", self.modules["bq"]);
-                bq_mem = self.modules["bq"].get_memory().unwrap().clone();
-            }
-            if self.modules["lx"].all_high().unwrap() {
-                println!("pressed: {}", self.count);
-                println!("lx all high");
-            }
+    /// Press at which every source of the hub sends it a high pulse, once
+    /// each has done so twice and turned out to repeat every so many
+    /// presses from the start
+    fn cycle_lcm(&self) -> Option<usize> {
+        if self.cycles.is_empty() {
+            return None;
+        }
+        self.cycles
+            .values()
+            .map(|presses| match presses[..] {
+                [first, second] if second - first == first => Some(first),
+                _ => None,
+            })
+            .try_fold(1, |acc, period| Some(acc.lcm(&period?)))
+    }
+
+    /// Number of button presses until `rx` gets a low pulse
+    fn run(&mut self) -> Result<usize, Error> {
+        while self.count < MAX_PRESSES {
             self.count += 1;
             let mut pulses = vec![DirectedPulse::new(
                 "button".to_string(),
@@ -370,34 +358,50 @@ impl System {
             while !pulses.is_empty() {
                 pulses = self.process_pulses(&pulses);
             }
+            if self.done {
+                return Ok(self.count);
+            }
+            if let Some(lcm) = self.cycle_lcm() {
+                debug!(
+                    "sources of {} repeat {:?}, all high after {} presses",
+                    self.hub.as_ref().unwrap(),
+                    self.cycles,
+                    lcm
+                );
+                return Ok(lcm);
+            }
         }
-        self.count
+        Err(Error::new(format!(
+            "no low pulse to rx after {} presses",
+            MAX_PRESSES
+        )))
     }
 }
 
 /// Parse things like "broadcaster -> a, b, c" and "%a -> b"
-fn parse_module(line: &str) -> Box<dyn Module> {
+fn parse_module(line: &str) -> Result<Box<dyn Module>, Error> {
     if line.starts_with("broadcaster") {
-        Box::new(line.parse::<Broadcast>().unwrap())
-    } else if line.starts_with("%") {
-        Box::new(line.parse::<FlipFlop>().unwrap())
-    } else if line.starts_with("&") {
-        Box::new(line.parse::<Conjunction>().unwrap())
+        Ok(Box::new(line.parse::<Broadcast>()?))
+    } else if line.starts_with('%') {
+        Ok(Box::new(line.parse::<FlipFlop>()?))
+    } else if line.starts_with('&') {
+        Ok(Box::new(line.parse::<Conjunction>()?))
     } else {
-        panic!()
+        Err(Error::new("expected `broadcaster`, `%` or `&`").at_column(1))
     }
 }
 impl FromStr for System {
-    type Err = &'static str;
+    type Err = Error;
     /// Parse things like "broadcaster -> a, b, c" and "%a -> b"
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut modules = s
             .lines()
-            .map(|line| {
-                let module = parse_module(line);
-                (module.get_name().to_string(), module)
+            .enumerate()
+            .map(|(iline, line)| {
+                let module = parse_module(line).map_err(|e| e.at_line(iline + 1))?;
+                Ok((module.get_name().to_string(), module))
             })
-            .collect::<HashMap<_, _>>();
+            .collect::<Result<HashMap<_, _>, Error>>()?;
         let source_dest = modules
             .values()
             .map(|module| {
@@ -412,38 +416,68 @@ impl FromStr for System {
         source_dest.iter().for_each(|(s, d)| {
             if let Some(module) = modules.get_mut(d) {
                 module.add_source(s);
-            } else {
-                // println!("tried to add source for non-existant module {}", d);
             }
         });
-        let mut lcm_data = HashMap::new();
-        lcm_data.insert("lx".to_string(), None);
-        lcm_data.insert("db".to_string(), None);
-        lcm_data.insert("sd".to_string(), None);
-        lcm_data.insert("qz".to_string(), None);
+        let feeders = modules
+            .values()
+            .filter(|module| module.get_destinations().iter().any(|d| d == "rx"))
+            .collect::<Vec<_>>();
+        if feeders.is_empty() {
+            return Err(Error::new("no module sends pulses to rx"));
+        }
+        // a lone conjunction is the hub, anything else is only simulated
+        let hub = match feeders[..] {
+            [feeder] => feeder
+                .get_memory()
+                .map(|memory| (feeder.get_name().to_string(), memory)),
+            _ => None,
+        };
+        let (hub, cycles) = match hub {
+            Some((name, memory)) => {
+                let cycles = memory
+                    .keys()
+                    .map(|source| (source.clone(), Vec::new()))
+                    .collect();
+                (Some(name), cycles)
+            }
+            None => (None, HashMap::new()),
+        };
         Ok(System {
             modules,
             count: 0,
             done: false,
-            lcm_data,
+            hub,
+            cycles,
         })
     }
 }
 
-pub fn run(input: &str) -> usize {
-    input.parse::<System>().unwrap().run()
+pub fn run(input: &str) -> Result<usize, Error> {
+    input.parse::<System>()?.run()
 }
 
 #[cfg(test)]
 mod tests {
-    // #[test]
-    // fn test1() {
-    //     let input = include_str!("example_data.txt");
-    //     assert_eq!(super::run(input), 32_000_000);
-    // }
-    // #[test]
-    // fn test2() {
-    //     let input = include_str!("example_data_2.txt");
-    //     assert_eq!(super::run(input), 11_687_500);
-    // }
+    use super::System;
+
+    #[test]
+    fn test_main() {
+        let input = include_str!("data.txt");
+        let mut system = input.parse::<System>().unwrap();
+        assert_eq!(system.run().unwrap(), 238593356738827);
+        // found from the cycles long before pressing that many times
+        assert!(system.count < 10_000);
+    }
+    #[test]
+    fn test_rx() {
+        // rx gets a low pulse on the fourth press, before the sources of
+        // the hub are seen to repeat
+        let input = "broadcaster -> a\n%a -> f1, b\n%b -> f2\n&f1 -> h\n&f2 -> h\n&h -> rx";
+        assert_eq!(super::run(input).unwrap(), 4);
+        // no hub to find cycles of
+        assert_eq!(super::run("broadcaster -> a\n%a -> rx").unwrap(), 2);
+
+        let error = super::run(include_str!("example_data.txt")).unwrap_err();
+        assert_eq!(error.to_string(), "no module sends pulses to rx");
+    }
 }
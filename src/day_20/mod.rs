@@ -1,8 +1,19 @@
-mod a;
+use crate::{
+    error::Result,
+    solution::{Answer, Solution},
+};
+
+pub mod a;
 mod b;
+pub mod trace;
+
+pub struct Day20;
 
-pub fn run() {
-    let input = include_str!("data.txt");
-    println!("day 20 a {}", a::run(input));
-    println!("day 20 b {}", b::run(input));
+impl Solution for Day20 {
+    fn part_a(&self, input: &str) -> Result<Answer> {
+        Ok(a::run(input)?.into())
+    }
+    fn part_b(&self, input: &str) -> Result<Answer> {
+        Ok(b::run(input)?.into())
+    }
 }
@@ -0,0 +1,139 @@
+//! Pulses the module network delivers, recorded press by press as JSON
+//! lines so the state of every module can be rebuilt at any press
+
+use std::io::{self, Write};
+use std::str::FromStr;
+
+use super::a::Pulse;
+use crate::error::{parse_token, Error, Result};
+
+/// Pulse delivered `step` pulses into button press `press`, both counted
+/// from the button's own pulse
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TracedPulse {
+    pub press: usize,
+    pub step: usize,
+    pub source: String,
+    pub destination: String,
+    pub pulse: Pulse,
+}
+
+/// Every pulse delivered, in order
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Trace {
+    pub pulses: Vec<TracedPulse>,
+}
+
+impl Trace {
+    /// Add a pulse delivered during `press` after the ones before it
+    pub fn record(&mut self, press: usize, source: &str, destination: &str, pulse: Pulse) {
+        let step = match self.pulses.last() {
+            Some(last) if last.press == press => last.step + 1,
+            _ => 0,
+        };
+        self.pulses.push(TracedPulse {
+            press,
+            step,
+            source: source.to_string(),
+            destination: destination.to_string(),
+            pulse,
+        });
+    }
+
+    /// Last button press recorded, 0 when nothing was
+    pub fn presses(&self) -> usize {
+        self.pulses.last().map_or(0, |pulse| pulse.press)
+    }
+
+    /// Write one JSON object per pulse per line
+    pub fn write_jsonl(&self, out: &mut impl Write) -> io::Result<()> {
+        for pulse in &self.pulses {
+            writeln!(
+                out,
+                "{{\"press\": {}, \"step\": {}, \"source\": \"{}\", \"destination\": \"{}\", \"pulse\": \"{}\"}}",
+                pulse.press, pulse.step, pulse.source, pulse.destination, pulse.pulse
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Value of `key` in a line written by [Trace::write_jsonl], without quotes
+fn field<'a>(line: &'a str, key: &str) -> Result<&'a str> {
+    let start = format!("\"{}\": ", key);
+    let (_, value) = line
+        .split_once(&start)
+        .ok_or_else(|| Error::new(format!("expected `{}`", key)))?;
+    let end = value.find([',', '}']).unwrap_or(value.len());
+    Ok(value[..end].trim_matches('"'))
+}
+
+impl FromStr for TracedPulse {
+    type Err = Error;
+    /// Parse things like {"press": 1, "step": 0, "source": "button",
+    /// "destination": "broadcast", "pulse": "low"}
+    fn from_str(s: &str) -> Result<Self> {
+        let pulse = field(s, "pulse")?;
+        Ok(TracedPulse {
+            press: parse_token(s, field(s, "press")?)?,
+            step: parse_token(s, field(s, "step")?)?,
+            source: field(s, "source")?.to_string(),
+            destination: field(s, "destination")?.to_string(),
+            pulse: match pulse {
+                "high" => Pulse::High,
+                "low" => Pulse::Low,
+                _ => return Err(Error::new("expected `high` or `low`").at_token(s, pulse)),
+            },
+        })
+    }
+}
+
+impl FromStr for Trace {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
+        let pulses = s
+            .lines()
+            .enumerate()
+            .map(|(iline, line)| line.parse().map_err(|e: Error| e.at_line(iline + 1)))
+            .collect::<Result<_>>()?;
+        Ok(Trace { pulses })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Pulse, Trace};
+
+    #[test]
+    fn test_jsonl() {
+        let mut trace = Trace::default();
+        trace.record(1, "button", "broadcast", Pulse::Low);
+        trace.record(1, "broadcast", "a", Pulse::Low);
+        trace.record(2, "button", "broadcast", Pulse::Low);
+        assert_eq!(
+            trace.pulses.iter().map(|p| p.step).collect::<Vec<_>>(),
+            [0, 1, 0]
+        );
+        assert_eq!(trace.presses(), 2);
+
+        let mut out = Vec::new();
+        trace.write_jsonl(&mut out).unwrap();
+        let jsonl = String::from_utf8(out).unwrap();
+        assert_eq!(
+            jsonl.lines().nth(1),
+            Some(
+                r#"{"press": 1, "step": 1, "source": "broadcast", "destination": "a", "pulse": "low"}"#
+            )
+        );
+        assert_eq!(jsonl.parse::<Trace>().unwrap(), trace);
+
+        let error = jsonl.replace("\"a\", \"pulse\": \"low\"", "\"a\", \"pulse\": \"loud\"");
+        let error = error.parse::<Trace>().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 78: expected `high` or `low`"
+        );
+        let error = "{\"press\": 1}".parse::<Trace>().unwrap_err();
+        assert_eq!(error.to_string(), "line 1: expected `pulse`");
+    }
+}
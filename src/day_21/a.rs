@@ -1,111 +1,62 @@
 use std::str::FromStr;
 
-use ndarray::Array2;
-
-#[derive(Debug, Default)]
-struct Position {
-    index: [usize; 2],
-}
-
-impl Position {
-    fn new(row: usize, col: usize) -> Position {
-        Position { index: [row, col] }
-    }
-}
+use crate::{
+    error::{at_char, Error},
+    geometry::Point2,
+    grid::Grid,
+};
 
 struct Positions {
-    at: Array2<bool>,
+    at: Grid<bool>,
 }
 impl Positions {
     fn step(&self, garden_map: &GardenMap) -> Positions {
-        let mut at = Array2::from_elem((garden_map.nrows, garden_map.ncols), false);
-        at.indexed_iter_mut().for_each(|((irow, icol), x)| {
-            if !garden_map.plot[[irow, icol]] {
-                return;
-            }
-            if irow > 0 {
-                if let Some(val) = self.at.get([irow - 1, icol]) {
-                    if *val {
-                        *x = true;
-                        return;
-                    }
-                }
-            }
-            if let Some(val) = self.at.get([irow + 1, icol]) {
-                if *val {
-                    *x = true;
-                    return;
-                }
-            }
-            if icol > 0 {
-                if let Some(val) = self.at.get([irow, icol - 1]) {
-                    if *val {
-                        *x = true;
-                        return;
-                    }
-                }
-            }
-            if let Some(val) = self.at.get([irow, icol + 1]) {
-                if *val {
-                    *x = true;
-                    return;
-                }
-            }
-        });
+        let plot = &garden_map.plot;
+        let mut at = Grid::from_elem(plot.nrows(), plot.ncols(), false);
+        for position in plot.positions(|x| *x) {
+            at[position] = self.at.neighbors4(position).any(|n| self.at[n]);
+        }
         Positions { at }
     }
 }
 
 impl From<&GardenMap> for Positions {
     fn from(value: &GardenMap) -> Self {
-        let mut at = Array2::from_elem((value.nrows, value.ncols), false);
-        *at.get_mut(value.start.index).unwrap() = true;
+        let plot = &value.plot;
+        let mut at = Grid::from_elem(plot.nrows(), plot.ncols(), false);
+        at[value.start.index()] = true;
         Positions { at }
     }
 }
 
 #[derive(Debug)]
 struct GardenMap {
-    plot: Array2<bool>,
-    start: Position,
-    nrows: usize,
-    ncols: usize,
+    plot: Grid<bool>,
+    start: Point2<usize>,
 }
 
 impl FromStr for GardenMap {
-    type Err = &'static str;
+    type Err = Error;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut start = Position::default();
-        'outer: for (irow, line) in s.lines().enumerate() {
+        let mut start = None;
+        for (irow, line) in s.lines().enumerate() {
             for (icol, c) in line.chars().enumerate() {
                 if c == 'S' {
-                    start = Position::new(irow, icol);
-                    break 'outer;
+                    if start.is_some() {
+                        return Err(at_char(icol, "second start").at_line(irow + 1));
+                    }
+                    start = Some(Point2::new(irow, icol));
                 }
             }
         }
-        let plot = s
-            .lines()
-            .map(|line| {
-                line.chars()
-                    .map(|c| match c {
-                        'S' => true,
-                        '.' => true,
-                        '#' => false,
-                        _ => panic!(),
-                    })
-                    .collect::<Vec<_>>()
-            })
-            .collect::<Vec<_>>();
-        let nrows = plot.len();
-        let ncols = plot[0].len();
-        let plot = Array2::from_shape_vec((nrows, ncols), plot.concat()).unwrap();
-        Ok(GardenMap {
-            plot,
-            start,
-            nrows,
-            ncols,
-        })
+        let start = start.ok_or("no start")?;
+        let plot = Grid::parse_with(s, |c| match c {
+            'S' => Ok(true),
+            '.' => Ok(true),
+            '#' => Ok(false),
+            _ => Err(Error::new(format!("bad plot `{}`", c))),
+        })?;
+        Ok(GardenMap { plot, start })
     }
 }
 
@@ -119,8 +70,8 @@ impl GardenMap {
     }
 }
 
-pub fn run(input: &str, steps: usize) -> usize {
-    input.parse::<GardenMap>().unwrap().count_positions(steps)
+pub fn run(input: &str, steps: usize) -> Result<usize, Error> {
+    Ok(input.parse::<GardenMap>()?.count_positions(steps))
 }
 
 #[cfg(test)]
@@ -128,11 +79,11 @@ mod tests {
     #[test]
     fn test1() {
         let input = include_str!("example_data.txt");
-        assert_eq!(super::run(input, 6), 16);
+        assert_eq!(super::run(input, 6).unwrap(), 16);
     }
     #[test]
     fn test2() {
         let input = include_str!("example_data.txt");
-        assert_eq!(super::run(input, 5), 13);
+        assert_eq!(super::run(input, 5).unwrap(), 13);
     }
 }
@@ -1,71 +1,47 @@
-use std::{collections::BTreeMap, fmt::Display, ops::Add, str::FromStr};
+use std::{collections::BTreeMap, ops::Add, str::FromStr};
 
 use ndarray::{s, Array1, Array2};
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Default)]
-struct Position {
-    index: [i64; 2],
-}
-
-impl Position {
-    fn new(row: i64, col: i64) -> Position {
-        Position { index: [row, col] }
-    }
-    fn row(&self) -> i64 {
-        self.index[0]
-    }
-    fn col(&self) -> i64 {
-        self.index[1]
-    }
-}
-
-impl Display for Position {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({}, {})", self.row(), self.col())
-    }
-}
+use crate::{
+    error::{at_char, Error},
+    geometry::Point2,
+    grid::Grid,
+};
 
 #[derive(Debug)]
 struct GardenMap {
-    plot: Array2<bool>,
-    start: Position,
+    plot: Grid<bool>,
+    start: Point2<i64>,
     nrows: i64,
     ncols: i64,
 }
 
 impl FromStr for GardenMap {
-    type Err = &'static str;
+    type Err = Error;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut start = Position::default();
-        'outer: for (irow, line) in s.lines().enumerate() {
+        let mut start = None;
+        for (irow, line) in s.lines().enumerate() {
             for (icol, c) in line.chars().enumerate() {
                 if c == 'S' {
-                    start = Position::new(irow as i64, icol as i64);
-                    break 'outer;
+                    if start.is_some() {
+                        return Err(at_char(icol, "second start").at_line(irow + 1));
+                    }
+                    start = Some(Point2::new(irow as i64, icol as i64));
                 }
             }
         }
-        let plot = s
-            .lines()
-            .map(|line| {
-                line.chars()
-                    .map(|c| match c {
-                        'S' => true,
-                        '.' => true,
-                        '#' => false,
-                        _ => panic!(),
-                    })
-                    .collect::<Vec<_>>()
-            })
-            .collect::<Vec<_>>();
-        let nrows = plot.len();
-        let ncols = plot[0].len();
-        let plot = Array2::from_shape_vec((nrows, ncols), plot.concat()).unwrap();
+        let start = start.ok_or("no start")?;
+        let plot = Grid::parse_with(s, |c| match c {
+            'S' => Ok(true),
+            '.' => Ok(true),
+            '#' => Ok(false),
+            _ => Err(Error::new(format!("bad plot `{}`", c))),
+        })?;
         Ok(GardenMap {
-            plot,
             start,
-            nrows: nrows as i64,
-            ncols: ncols as i64,
+            nrows: plot.nrows() as i64,
+            ncols: plot.ncols() as i64,
+            plot,
         })
     }
 }
@@ -105,11 +81,11 @@ impl DTTileCore {
             dt: Array2::from_elem((nrows, ncols), usize::MAX),
         }
     }
-    fn from_point(start: &Position, garden_map: &GardenMap) -> Self {
+    fn from_point(start: &Point2<i64>, garden_map: &GardenMap) -> Self {
         let nrows = garden_map.nrows as usize;
         let ncols = garden_map.ncols as usize;
         let mut core = Self::new(nrows, ncols);
-        let start = (start.row() as usize, start.col() as usize);
+        let start = (start.row as usize, start.col as usize);
         *core.dt.get_mut(start).unwrap() = 0;
         core.populate_distance_transform(garden_map);
         core
@@ -178,8 +154,8 @@ impl DTTileCore {
             self.dt[[nrows - 1, ncols - 1]],
             steps,
         );
-        if prediction.is_some() {
-            return *prediction.unwrap();
+        if let Some(&count) = prediction {
+            return count;
         }
         let count = self
             .dt
@@ -204,18 +180,18 @@ impl DTTileCore {
             let mut changed = false;
             for irow in 0..nrows {
                 for icol in 0..ncols {
-                    if !garden_map.plot[(irow, icol)] {
+                    if !garden_map.plot[[irow, icol]] {
                         continue;
                     }
                     let mut min_val = self.dt[[irow, icol]];
                     if irow > 0 {
-                        let n = (irow - 1, icol);
+                        let n = [irow - 1, icol];
                         if garden_map.plot[n] && self.dt[n] != usize::MAX {
                             min_val = min_val.min(self.dt[n] + 1);
                         }
                     }
                     if icol > 0 {
-                        let w = (irow, icol - 1);
+                        let w = [irow, icol - 1];
                         if garden_map.plot[w] && self.dt[w] != usize::MAX {
                             min_val = min_val.min(self.dt[w] + 1);
                         }
@@ -228,18 +204,18 @@ impl DTTileCore {
             }
             for irow in (0..nrows).rev() {
                 for icol in (0..ncols).rev() {
-                    if !garden_map.plot[(irow, icol)] {
+                    if !garden_map.plot[[irow, icol]] {
                         continue;
                     }
                     let mut min_val = self.dt[[irow, icol]];
                     if irow < nrows - 1 {
-                        let s = (irow + 1, icol);
+                        let s = [irow + 1, icol];
                         if garden_map.plot[s] && self.dt[s] != usize::MAX {
                             min_val = min_val.min(self.dt[s] + 1);
                         }
                     }
                     if icol < ncols - 1 {
-                        let e = (irow, icol + 1);
+                        let e = [irow, icol + 1];
                         if garden_map.plot[e] && self.dt[e] != usize::MAX {
                             min_val = min_val.min(self.dt[e] + 1);
                         }
@@ -255,153 +231,7 @@ impl DTTileCore {
             }
         }
     }
-    // fn print(&self, garden_map: &GardenMap) {
-    //     let nrows = self.dt.shape()[0];
-    //     let ncols = self.dt.shape()[1];
-    //     for irow in 0..nrows {
-    //         for icol in 0..ncols {
-    //             if garden_map.plot[[irow, icol]] {
-    //                 let d = self.dt[[irow, icol]];
-    //                 if d == usize::MAX {
-    //                     panic!()
-    //                 }
-    //                 print!("{:02}", d);
-    //             } else {
-    //                 assert_eq!(self.dt[[irow, icol]], usize::MAX);
-    //                 print!(" #");
-    //             }
-    //         }
-    //         println!("");
-    //     }
-    // }
 }
-// struct CompositeDT {
-//     count: usize,
-//     left: Vec<Edge>,
-//     right: Vec<Edge>,
-//     top: Vec<Edge>,
-//     bottom: Vec<Edge>,
-// }
-
-// impl CompositeDT {
-//     fn expand(&self, garden_map: &GardenMap, steps: usize, cmem: &mut CountMem) -> Self {
-//         let mut top = vec![Edge::default(); self.top.len() + 2];
-//         let mut left = vec![Edge::default(); self.left.len() + 2];
-//         let mut right = vec![Edge::default(); self.right.len() + 2];
-//         let mut bottom = vec![Edge::default(); self.bottom.len() + 2];
-//         let mut count = self.count;
-//         let debug = false;
-//         self.top.iter().enumerate().for_each(|(i, old)| {
-//             let tile = DTTileCore::from_edge(old, garden_map);
-//             if debug {
-//                 println!("top tile {} {}", i + 1, tile.count_dt(steps, cmem));
-//                 // tile.print(garden_map);
-//             }
-//             count += tile.count_dt(steps, cmem);
-//             *top.get_mut(i + 1).unwrap() = tile.get_top_edge();
-//             if i == 0 {
-//                 // top left corner
-//                 let top_left = DTTileCore::from_edge(&tile.get_left_edge(), garden_map);
-//                 if debug {
-//                     println!("top left {}", top_left.count_dt(steps, cmem));
-//                     // top_left.print(garden_map);
-//                 }
-//                 count += top_left.count_dt(steps, cmem);
-//                 *top.first_mut().unwrap() = top_left.get_top_edge();
-//                 *left.first_mut().unwrap() = top_left.get_left_edge();
-//             }
-//             if i == self.top.len() - 1 {
-//                 // top right corner
-//                 let top_right = DTTileCore::from_edge(&tile.get_right_edge(), garden_map);
-//                 if debug {
-//                     println!("top right {}", top_right.count_dt(steps, cmem));
-//                     // top_right.print(garden_map);
-//                 }
-//                 count += top_right.count_dt(steps, cmem);
-//                 *top.last_mut().unwrap() = top_right.get_top_edge();
-//                 *right.first_mut().unwrap() = top_right.get_right_edge();
-//             }
-//         });
-//         self.bottom.iter().enumerate().for_each(|(i, old)| {
-//             let tile = DTTileCore::from_edge(old, garden_map);
-//             if debug {
-//                 println!("bottom tile {} {}", i + 1, tile.count_dt(steps, cmem));
-//                 // tile.print(garden_map);
-//             }
-//             count += tile.count_dt(steps, cmem);
-//             *bottom.get_mut(i + 1).unwrap() = tile.get_bottom_edge();
-//             if i == 0 {
-//                 // bottom left corner
-//                 let bottom_left = DTTileCore::from_edge(&tile.get_left_edge(), garden_map);
-//                 if debug {
-//                     println!("bottom left {}", bottom_left.count_dt(steps, cmem));
-//                     // bottom_left.print(garden_map);
-//                 }
-//                 count += bottom_left.count_dt(steps, cmem);
-//                 *bottom.first_mut().unwrap() = bottom_left.get_bottom_edge();
-//                 *left.last_mut().unwrap() = bottom_left.get_left_edge();
-//             }
-//             if i == self.bottom.len() - 1 {
-//                 // bottom right corner
-//                 let bottom_right = DTTileCore::from_edge(&tile.get_right_edge(), garden_map);
-//                 if debug {
-//                     println!("bottom right {}", bottom_right.count_dt(steps, cmem));
-//                     // bottom_right.print(garden_map);
-//                 }
-//                 count += bottom_right.count_dt(steps, cmem);
-//                 *bottom.last_mut().unwrap() = bottom_right.get_bottom_edge();
-//                 *right.last_mut().unwrap() = bottom_right.get_right_edge();
-//             }
-//         });
-//         self.left.iter().enumerate().for_each(|(i, old)| {
-//             let tile = DTTileCore::from_edge(old, garden_map);
-//             if debug {
-//                 println!("left tile {} {}", i + 1, tile.count_dt(steps, cmem));
-//                 // tile.print(garden_map);
-//             }
-//             count += tile.count_dt(steps, cmem);
-//             *left.get_mut(i + 1).unwrap() = tile.get_left_edge();
-//         });
-//         self.right.iter().enumerate().for_each(|(i, old)| {
-//             let tile = DTTileCore::from_edge(old, garden_map);
-//             if debug {
-//                 println!("right tile {} {}", i + 1, tile.count_dt(steps, cmem));
-//                 // tile.print(garden_map);
-//             }
-//             count += tile.count_dt(steps, cmem);
-//             *right.get_mut(i + 1).unwrap() = tile.get_right_edge();
-//         });
-//         CompositeDT {
-//             count,
-//             left,
-//             right,
-//             top,
-//             bottom,
-//         }
-//     }
-//     fn new(garden_map: &GardenMap, steps: usize, cmem: &mut CountMem) -> CompositeDT {
-//         let center = DTTileCore::from_point(&garden_map.start, garden_map);
-//         let debug = false;
-//         if debug {
-//             println!("center {}", center.count_dt(steps, cmem));
-//             // center.print(garden_map);
-//         }
-//         let count = center.count_dt(steps, cmem);
-//         // make first ring
-//         let left = vec![center.get_left_edge()];
-//         let right = vec![center.get_right_edge()];
-//         let top = vec![center.get_top_edge()];
-//         let bottom = vec![center.get_bottom_edge()];
-//         CompositeDT {
-//             count,
-//             left,
-//             right,
-//             bottom,
-//             top,
-//         }
-//     }
-// }
-
 struct CountMem {
     data: BTreeMap<(usize, usize, usize, usize), usize>,
     nrows: usize,
@@ -537,7 +367,6 @@ fn fast_expand(garden_map: &GardenMap, steps: usize) -> usize {
     // center
     let center = DTTileCore::from_point(&garden_map.start, garden_map);
     let mut count = center.count_dt(steps, cmem);
-    // let mut test_count = count;
 
     // center cross
     count += fast_expand_edge(&center.get_left_edge(), garden_map, steps, cmem);
@@ -594,74 +423,51 @@ fn fast_expand(garden_map: &GardenMap, steps: usize) -> usize {
 
     count
 }
-pub fn run(input: &str, steps: usize) -> usize {
-    let garden_map = input.parse::<GardenMap>().unwrap();
-    fast_expand(&garden_map, steps)
+pub fn run(input: &str, steps: usize) -> Result<usize, Error> {
+    let garden_map = input.parse::<GardenMap>()?;
+    Ok(fast_expand(&garden_map, steps))
 }
 
-// pub fn run(input: &str, steps: usize) -> usize {
-//     let garden_map = input.parse::<GardenMap>().unwrap();
-//     let mut cmem = CountMem::new(garden_map.nrows as usize);
-//     let mut cdt = CompositeDT::new(&garden_map, steps, &mut cmem);
-//     let debug = false;
-//     if debug {
-//         println!("core count {}", cdt.count);
-//     }
-//     let mut rings = 0;
-//     loop {
-//         let new_cdt = cdt.expand(&garden_map, steps, &mut cmem);
-//         if debug {
-//             rings += 1;
-//             println!("\nring {} count {}", rings, new_cdt.count);
-//         }
-//         if new_cdt.count == cdt.count {
-//             break;
-//         }
-//         cdt = new_cdt;
-//     }
-//     cdt.count
-// }
-
 #[cfg(test)]
 mod tests {
     #[test]
     fn test_odd() {
         let input = include_str!("example_data.txt");
-        assert_eq!(super::run(input, 5,), 13);
+        assert_eq!(super::run(input, 5).unwrap(), 13);
     }
     #[test]
     fn test1_dt() {
         let input = include_str!("example_data.txt");
-        assert_eq!(super::run(input, 6,), 16);
+        assert_eq!(super::run(input, 6).unwrap(), 16);
     }
     #[test]
     fn test2_dt() {
         let input = include_str!("example_data.txt");
-        assert_eq!(super::run(input, 10,), 50);
+        assert_eq!(super::run(input, 10).unwrap(), 50);
     }
     #[test]
     fn test3_dt() {
         let input = include_str!("example_data.txt");
-        assert_eq!(super::run(input, 50,), 1594);
+        assert_eq!(super::run(input, 50).unwrap(), 1594);
     }
     #[test]
     fn test4_dt() {
         let input = include_str!("example_data.txt");
-        assert_eq!(super::run(input, 100,), 6536);
+        assert_eq!(super::run(input, 100).unwrap(), 6536);
     }
     #[test]
     fn test5_dt() {
         let input = include_str!("example_data.txt");
-        assert_eq!(super::run(input, 500,), 167004);
+        assert_eq!(super::run(input, 500).unwrap(), 167004);
     }
     #[test]
     fn test6_dt() {
         let input = include_str!("example_data.txt");
-        assert_eq!(super::run(input, 1000,), 668697);
+        assert_eq!(super::run(input, 1000).unwrap(), 668697);
     }
     #[test]
     fn test7_dt() {
         let input = include_str!("example_data.txt");
-        assert_eq!(super::run(input, 5000,), 16733044);
+        assert_eq!(super::run(input, 5000).unwrap(), 16733044);
     }
 }
@@ -1,8 +1,18 @@
+use crate::{
+    error::Result,
+    solution::{Answer, Solution},
+};
+
 mod a;
 mod b;
 
-pub fn run() {
-    let input = include_str!("data.txt");
-    println!("day 21 a {}", a::run(input, 64));
-    println!("day 21 b {}", b::run(input, 26_501_365,));
+pub struct Day21;
+
+impl Solution for Day21 {
+    fn part_a(&self, input: &str) -> Result<Answer> {
+        Ok(a::run(input, 64)?.into())
+    }
+    fn part_b(&self, input: &str) -> Result<Answer> {
+        Ok(b::run(input, 26_501_365)?.into())
+    }
 }
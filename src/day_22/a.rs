@@ -2,6 +2,8 @@ use std::{collections::BTreeSet, fmt::Display, str::FromStr};
 
 use ndarray::{s, Array3, Dim, SliceInfo, SliceInfoElem};
 
+use crate::error::{parse_lines, parse_token, Error};
+
 struct Brick {
     x: [u16; 2],
     y: [u16; 2],
@@ -93,24 +95,29 @@ impl Brick {
     }
 }
 /// Parse something like 1,0,1 => x,y,z
-fn parse_xyz(s: &str) -> Vec<u16> {
-    s.split(",")
-        .map(|x| x.parse::<u16>().unwrap())
-        .collect::<Vec<_>>()
+fn parse_xyz(line: &str, s: &str) -> Result<Vec<u16>, Error> {
+    s.split(',')
+        .map(|x| parse_token::<u16>(line, x))
+        .collect::<Result<Vec<_>, _>>()
 }
 
 impl FromStr for Brick {
-    type Err = &'static str;
+    type Err = Error;
     /// Parse something like 1,0,1~1,2,1 => x,y,z~x,y,z
     /// start_xyz~end_xyz (non-slice)
     /// Convert from non-slice to slice coords
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let start_end = s.split("~").map(|x| parse_xyz(x)).collect::<Vec<_>>();
+        let start_end = s
+            .split('~')
+            .map(|x| parse_xyz(s, x))
+            .collect::<Result<Vec<_>, _>>()?;
         if start_end.len() != 2 {
-            Err("Wrong number of parts")
+            Err(Error::new("Wrong number of parts"))
         } else {
             if start_end.iter().any(|x| x.len() != 3) {
-                Err("Wrong number of components")
+                Err(Error::new("Wrong number of components"))
+            } else if (0..3).any(|i| start_end[1][i] < start_end[0][i]) {
+                Err(Error::new("End is before start"))
             } else {
                 let start = &start_end[0];
                 let end = &start_end[1];
@@ -146,12 +153,12 @@ struct Bricks {
     has_brick: Array3<u16>,
 }
 impl FromStr for Bricks {
-    type Err = &'static str;
+    type Err = Error;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let bricks = s
-            .lines()
-            .map(|line| line.parse::<Brick>().unwrap())
-            .collect::<Vec<_>>();
+        let bricks = parse_lines::<Brick>(s)?;
+        if bricks.is_empty() {
+            return Err(Error::new("No bricks"));
+        }
         Ok(Bricks::new(bricks))
     }
 }
@@ -261,10 +268,10 @@ impl Bricks {
     }
 }
 
-pub fn run(input: &str) -> usize {
-    let mut bricks = input.parse::<Bricks>().unwrap();
+pub fn run(input: &str) -> Result<usize, Error> {
+    let mut bricks = input.parse::<Bricks>()?;
     bricks.settle_all();
-    bricks.count_non_esential() as usize
+    Ok(bricks.count_non_esential() as usize)
 }
 
 #[cfg(test)]
@@ -272,6 +279,8 @@ mod tests {
     #[test]
     fn test1() {
         let input = include_str!("example_data.txt");
-        assert_eq!(super::run(input), 5);
+        assert_eq!(super::run(input).unwrap(), 5);
+        let error = super::run("1,0,1~1,2,1\n0,0,2~2,0,2~3\n0,2,3~2,2,3").unwrap_err();
+        assert_eq!(error.to_string(), "line 2: Wrong number of parts");
     }
 }
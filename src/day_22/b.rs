@@ -6,6 +6,8 @@ use std::{
 
 use ndarray::{s, Array3, Dim, SliceInfo, SliceInfoElem};
 
+use crate::error::{parse_lines, parse_token, Error};
+
 struct Brick {
     x: [u16; 2],
     y: [u16; 2],
@@ -97,24 +99,29 @@ impl Brick {
     }
 }
 /// Parse something like 1,0,1 => x,y,z
-fn parse_xyz(s: &str) -> Vec<u16> {
-    s.split(",")
-        .map(|x| x.parse::<u16>().unwrap())
-        .collect::<Vec<_>>()
+fn parse_xyz(line: &str, s: &str) -> Result<Vec<u16>, Error> {
+    s.split(',')
+        .map(|x| parse_token::<u16>(line, x))
+        .collect::<Result<Vec<_>, _>>()
 }
 
 impl FromStr for Brick {
-    type Err = &'static str;
+    type Err = Error;
     /// Parse something like 1,0,1~1,2,1 => x,y,z~x,y,z
     /// start_xyz~end_xyz (non-slice)
     /// Convert from non-slice to slice coords
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let start_end = s.split("~").map(|x| parse_xyz(x)).collect::<Vec<_>>();
+        let start_end = s
+            .split('~')
+            .map(|x| parse_xyz(s, x))
+            .collect::<Result<Vec<_>, _>>()?;
         if start_end.len() != 2 {
-            Err("Wrong number of parts")
+            Err(Error::new("Wrong number of parts"))
         } else {
             if start_end.iter().any(|x| x.len() != 3) {
-                Err("Wrong number of components")
+                Err(Error::new("Wrong number of components"))
+            } else if (0..3).any(|i| start_end[1][i] < start_end[0][i]) {
+                Err(Error::new("End is before start"))
             } else {
                 let start = &start_end[0];
                 let end = &start_end[1];
@@ -150,12 +157,12 @@ struct Bricks {
     has_brick: Array3<u16>,
 }
 impl FromStr for Bricks {
-    type Err = &'static str;
+    type Err = Error;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let bricks = s
-            .lines()
-            .map(|line| line.parse::<Brick>().unwrap())
-            .collect::<Vec<_>>();
+        let bricks = parse_lines::<Brick>(s)?;
+        if bricks.is_empty() {
+            return Err(Error::new("No bricks"));
+        }
         Ok(Bricks::new(bricks))
     }
 }
@@ -256,14 +263,12 @@ impl Bricks {
                     .has_brick
                     .slice(drop_rim.slice())
                     .into_iter()
-                    .map(|x| *x)
+                    .copied()
                     .filter(|x| *x > 0)
                     .collect::<BTreeSet<_>>();
                 supported_by.insert(b.id, lower_ids.clone());
                 for id in lower_ids.clone() {
-                    if !supports.contains_key(&id) {
-                        supports.insert(id, BTreeSet::new());
-                    }
+                    supports.entry(id).or_insert_with(BTreeSet::new);
                     let set = supports.get_mut(&id).unwrap();
                     set.insert(b.id);
                 }
@@ -276,7 +281,7 @@ impl Bricks {
             loop {
                 let mut newly_removed = BTreeSet::new();
                 for removed_id in &removed {
-                    if let Some(endangered) = supports.get(&removed_id) {
+                    if let Some(endangered) = supports.get(removed_id) {
                         for endangered_id in endangered {
                             if removed.contains(endangered_id)
                                 | newly_removed.contains(endangered_id)
@@ -303,10 +308,10 @@ impl Bricks {
     }
 }
 
-pub fn run(input: &str) -> usize {
-    let mut bricks = input.parse::<Bricks>().unwrap();
+pub fn run(input: &str) -> Result<usize, Error> {
+    let mut bricks = input.parse::<Bricks>()?;
     bricks.settle_all();
-    bricks.count_b() as usize
+    Ok(bricks.count_b())
 }
 
 #[cfg(test)]
@@ -314,6 +319,6 @@ mod tests {
     #[test]
     fn test1() {
         let input = include_str!("example_data.txt");
-        assert_eq!(super::run(input), 7);
+        assert_eq!(super::run(input).unwrap(), 7);
     }
 }
@@ -4,68 +4,29 @@ use std::{
     str::FromStr,
 };
 
-use ndarray::{s, Array2};
-
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-impl Direction {
-    fn opposite(&self) -> Direction {
-        match self {
-            Direction::Down => Direction::Up,
-            Direction::Up => Direction::Down,
-            Direction::Left => Direction::Right,
-            Direction::Right => Direction::Left,
-        }
-    }
-}
-
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-struct Position {
-    index: [usize; 2],
-}
-
-impl Position {
-    fn new(row: usize, col: usize) -> Position {
-        Position { index: [row, col] }
-    }
-    fn row(&self) -> usize {
-        self.index[0]
-    }
-    fn col(&self) -> usize {
-        self.index[1]
-    }
-    fn neighbor(&self, direction: &Direction) -> Position {
-        match direction {
-            Direction::Down => Position::new(self.row() + 1, self.col()),
-            Direction::Up => Position::new(self.row() - 1, self.col()),
-            Direction::Left => Position::new(self.row(), self.col() - 1),
-            Direction::Right => Position::new(self.row(), self.col() + 1),
-        }
-    }
-}
+use crate::{
+    debug,
+    error::Error,
+    geometry::{Direction, Point2},
+    grid::Grid,
+};
 
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 struct Node {
-    position: Position,
+    position: Point2<usize>,
 }
 
 impl Node {
     fn new(row: usize, col: usize) -> Node {
         Node {
-            position: Position::new(row, col),
+            position: Point2::new(row, col),
         }
     }
     fn row(&self) -> usize {
-        return self.position.row();
+        self.position.row
     }
     fn col(&self) -> usize {
-        return self.position.col();
+        self.position.col
     }
 }
 impl Display for Node {
@@ -104,7 +65,7 @@ enum MapSymbol {
 }
 
 impl TryFrom<char> for MapSymbol {
-    type Error = &'static str;
+    type Error = Error;
     fn try_from(value: char) -> Result<Self, Self::Error> {
         match value {
             '#' => Ok(MapSymbol::Forest),
@@ -113,7 +74,7 @@ impl TryFrom<char> for MapSymbol {
             'v' => Ok(MapSymbol::Down),
             '<' => Ok(MapSymbol::Left),
             '>' => Ok(MapSymbol::Right),
-            _ => Err("Unknown symbol"),
+            _ => Err(Error::new(format!("Unknown symbol `{}`", value))),
         }
     }
 }
@@ -123,39 +84,27 @@ struct FourDirections {
     down: Option<MapSymbol>,
     left: Option<MapSymbol>,
     right: Option<MapSymbol>,
-    position: Position,
+    position: Point2<usize>,
     direction: Option<Direction>,
 }
 impl FourDirections {
     fn new(
-        map: &Array2<MapSymbol>,
+        map: &Grid<MapSymbol>,
         irow: usize,
         icol: usize,
         direction: Option<Direction>,
     ) -> Result<FourDirections, ()> {
-        let nrows = map.shape()[0];
-        let ncols = map.shape()[1];
-        if irow <= nrows - 1 && icol <= ncols - 1 && map[[irow, icol]] != MapSymbol::Forest {
-            let pos = Position::new(irow, icol);
-            let own = |x: Option<&MapSymbol>| {
-                if x.is_none() {
-                    None
-                } else {
-                    Some(*(x.unwrap()))
-                }
-            };
-            let up = if irow == 0 {
-                None
-            } else {
-                own(map.get(pos.neighbor(&Direction::Up).index))
+        if irow < map.nrows() && icol < map.ncols() && map[[irow, icol]] != MapSymbol::Forest {
+            let pos = Point2::new(irow, icol);
+            let look = |direction: Direction| {
+                pos.step(direction)
+                    .and_then(|p| map.get(p.index()))
+                    .copied()
             };
-            let down = own(map.get(pos.neighbor(&Direction::Down).index));
-            let left = if icol == 0 {
-                None
-            } else {
-                own(map.get(pos.neighbor(&Direction::Left).index))
-            };
-            let right = own(map.get(pos.neighbor(&Direction::Right).index));
+            let up = look(Direction::N);
+            let down = look(Direction::S);
+            let left = look(Direction::W);
+            let right = look(Direction::E);
             Ok(FourDirections {
                 up,
                 down,
@@ -169,10 +118,10 @@ impl FourDirections {
         }
     }
     fn row(&self) -> usize {
-        self.position.row()
+        self.position.row
     }
     fn col(&self) -> usize {
-        self.position.col()
+        self.position.col
     }
     fn count_directions(&self) -> usize {
         let mut out = 0;
@@ -196,73 +145,63 @@ impl FourDirections {
         }
         out
     }
-    fn follow_path(&self, map: &Array2<MapSymbol>) -> FourDirections {
+    fn follow_path(&self, map: &Grid<MapSymbol>) -> FourDirections {
         let direction = self.direction.unwrap();
-        let new_position = self.position.neighbor(&direction);
+        let new_position = self.position.step(direction).unwrap();
         let mut next_fd =
-            FourDirections::new(map, new_position.row(), new_position.col(), None).unwrap();
+            FourDirections::new(map, new_position.row, new_position.col, None).unwrap();
         let op = direction.opposite();
-        if Direction::Up != op && next_fd.up.is_some() && next_fd.up.unwrap() != MapSymbol::Forest {
-            next_fd.direction = Some(Direction::Up);
+        if Direction::N != op && next_fd.up.is_some() && next_fd.up.unwrap() != MapSymbol::Forest {
+            next_fd.direction = Some(Direction::N);
         }
-        if Direction::Down != op
+        if Direction::S != op
             && next_fd.down.is_some()
             && next_fd.down.unwrap() != MapSymbol::Forest
         {
-            next_fd.direction = Some(Direction::Down);
+            next_fd.direction = Some(Direction::S);
         }
-        if Direction::Left != op
+        if Direction::W != op
             && next_fd.left.is_some()
             && next_fd.left.unwrap() != MapSymbol::Forest
         {
-            next_fd.direction = Some(Direction::Left);
+            next_fd.direction = Some(Direction::W);
         }
-        if Direction::Right != op
+        if Direction::E != op
             && next_fd.right.is_some()
             && next_fd.right.unwrap() != MapSymbol::Forest
         {
-            next_fd.direction = Some(Direction::Right);
+            next_fd.direction = Some(Direction::E);
         }
         next_fd
     }
 }
 
 fn trace_edge(
-    map: &Array2<MapSymbol>,
-    node_set: &BTreeSet<Position>,
-    pos: &Position,
+    map: &Grid<MapSymbol>,
+    node_set: &BTreeSet<Point2<usize>>,
+    pos: &Point2<usize>,
     direction: &Direction,
 ) -> Edge {
-    let mut fd = FourDirections::new(map, pos.row(), pos.col(), Some(*direction)).unwrap();
+    let mut fd = FourDirections::new(map, pos.row, pos.col, Some(*direction)).unwrap();
     let mut weight = 0;
     loop {
-        fd = fd.follow_path(&map);
+        fd = fd.follow_path(map);
         weight += 1;
         if node_set.contains(&fd.position) {
             break;
         }
     }
     Edge::new(
-        Node::new(pos.row(), pos.col()),
+        Node::new(pos.row, pos.col),
         Node::new(fd.row(), fd.col()),
         weight,
     )
 }
 
 impl FromStr for Graph {
-    type Err = &'static str;
+    type Err = Error;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let map = s
-            .lines()
-            .map(|line| {
-                line.chars()
-                    .map(|c| MapSymbol::try_from(c).unwrap())
-                    .collect::<Vec<_>>()
-            })
-            .collect::<Vec<_>>();
-        let nrows = map.len();
-        let ncols = map[0].len();
-        let map = Array2::from_shape_vec((nrows, ncols), map.concat()).unwrap();
+        let map = s.parse::<Grid<MapSymbol>>()?;
 
         // find Nodes
         let mut nodes = Vec::new();
@@ -270,13 +209,14 @@ impl FromStr for Graph {
         {
             let start_row = 0;
             let start_col = map
-                .slice(s![start_row, ..])
+                .as_array()
+                .row(start_row)
                 .iter()
                 .enumerate()
                 .filter(|(_, x)| **x == MapSymbol::Path)
                 .map(|(i, _)| i)
                 .nth(0)
-                .unwrap();
+                .ok_or_else(|| Error::new("no path").at_line(start_row + 1))?;
             nodes.push(Node::new(start_row, start_col));
         }
         // find middle nodes
@@ -284,27 +224,28 @@ impl FromStr for Graph {
             let mut middle_nodes = map
                 .indexed_iter()
                 .filter(|x| *x.1 != MapSymbol::Forest)
-                .filter(|((irow, icol), _)| {
+                .filter(|([irow, icol], _)| {
                     FourDirections::new(&map, *irow, *icol, None)
                         .unwrap()
                         .count_directions()
                         > 2
                 })
-                .map(|((irow, icol), _)| Node::new(irow, icol))
+                .map(|([irow, icol], _)| Node::new(irow, icol))
                 .collect::<Vec<_>>();
             nodes.append(&mut middle_nodes);
         }
         // find end node
         {
-            let end_row = nrows - 1;
+            let end_row = map.nrows() - 1;
             let end_col = map
-                .slice(s![end_row, ..])
+                .as_array()
+                .row(end_row)
                 .iter()
                 .enumerate()
                 .filter(|(_, x)| **x == MapSymbol::Path)
                 .map(|(i, _)| i)
                 .nth(0)
-                .unwrap();
+                .ok_or_else(|| Error::new("no path").at_line(end_row + 1))?;
             nodes.push(Node::new(end_row, end_col));
         }
         let node_set = nodes.iter().map(|x| x.position).collect::<BTreeSet<_>>();
@@ -314,23 +255,23 @@ impl FromStr for Graph {
             &map,
             &node_set,
             &nodes[0].position,
-            &Direction::Down,
+            &Direction::S,
         ));
         // add other edges
         {
             for node in &nodes[1..nodes.len() - 1] {
                 let fd = FourDirections::new(&map, node.row(), node.col(), None).unwrap();
                 if fd.up.is_some() && fd.up.unwrap() == MapSymbol::Up {
-                    edges.push(trace_edge(&map, &node_set, &fd.position, &Direction::Up));
+                    edges.push(trace_edge(&map, &node_set, &fd.position, &Direction::N));
                 }
                 if fd.down.is_some() && fd.down.unwrap() == MapSymbol::Down {
-                    edges.push(trace_edge(&map, &node_set, &fd.position, &Direction::Down));
+                    edges.push(trace_edge(&map, &node_set, &fd.position, &Direction::S));
                 }
                 if fd.left.is_some() && fd.left.unwrap() == MapSymbol::Left {
-                    edges.push(trace_edge(&map, &node_set, &fd.position, &Direction::Left));
+                    edges.push(trace_edge(&map, &node_set, &fd.position, &Direction::W));
                 }
                 if fd.right.is_some() && fd.right.unwrap() == MapSymbol::Right {
-                    edges.push(trace_edge(&map, &node_set, &fd.position, &Direction::Right));
+                    edges.push(trace_edge(&map, &node_set, &fd.position, &Direction::E));
                 }
             }
         }
@@ -354,9 +295,7 @@ impl Graph {
     fn find_longest_path(&self) -> usize {
         let mut edge_map = BTreeMap::new();
         for edge in &self.edges {
-            if !edge_map.contains_key(&edge.start) {
-                edge_map.insert(edge.start, Vec::new());
-            }
+            edge_map.entry(edge.start).or_insert_with(Vec::new);
             edge_map.get_mut(&edge.start).unwrap().push(edge);
         }
         let mut visited = BTreeSet::new();
@@ -388,10 +327,10 @@ impl Graph {
     }
 }
 
-pub fn run(input: &str) -> usize {
-    let graph = input.parse::<Graph>().unwrap();
-    // println!("{}", graph);
-    graph.find_longest_path()
+pub fn run(input: &str) -> Result<usize, Error> {
+    let graph = input.parse::<Graph>()?;
+    debug!("{}", graph);
+    Ok(graph.find_longest_path())
 }
 
 #[cfg(test)]
@@ -399,6 +338,6 @@ mod tests {
     #[test]
     fn test1() {
         let input = include_str!("example_data.txt");
-        assert_eq!(super::run(input), 94);
+        assert_eq!(super::run(input).unwrap(), 94);
     }
 }
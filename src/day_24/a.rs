@@ -1,83 +1,80 @@
-use std::{fmt::Display, str::FromStr};
+use std::{collections::BTreeMap, fmt::Display, str::FromStr};
 
-#[derive(Clone, Copy)]
-struct Position {
-    vec: [usize; 3],
-}
-impl Position {
-    fn new(vec: [usize; 3]) -> Position {
-        Position { vec }
-    }
-}
-impl Display for Position {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}, {}, {}", self.vec[0], self.vec[1], self.vec[2])
-    }
-}
-impl FromStr for Position {
-    type Err = ();
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let vec = s
-            .split(", ")
-            .map(|x| x.parse::<usize>().unwrap())
-            .collect::<Vec<_>>();
-        assert_eq!(vec.len(), 3);
-        let vec = [vec[0], vec[1], vec[2]];
-        Ok(Position::new(vec))
-    }
-}
-#[derive(Clone, Copy)]
-struct Velocity {
-    vec: [i32; 3],
-}
-impl Velocity {
-    fn new(vec: [i32; 3]) -> Velocity {
-        vec.iter().for_each(|x| {
-            assert_ne!(*x, 0);
-        });
-        Velocity { vec }
-    }
-    // fn norm_xy(&self) -> [f64; 2] {
-    //     let out = [self.vec[0] as f64, self.vec[1] as f64];
-    //     let norm = f64::sqrt(out[0].powi(2) + out[1].powi(2));
-    //     [out[0] / norm, out[1] / norm]
-    // }
-}
-impl Display for Velocity {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}, {}, {}", self.vec[0], self.vec[1], self.vec[2])
-    }
+use num::{BigInt, BigRational};
+
+use crate::{
+    debug,
+    error::{parse_lines, parse_token, Error},
+    geometry::Point3,
+};
+
+/// Parse three comma separated numbers taken from `line`
+fn parse_triple<T: FromStr>(line: &str, s: &str) -> Result<[T; 3], Error> {
+    s.split(',')
+        .map(|x| parse_token::<T>(line, x.trim()))
+        .collect::<Result<Vec<_>, _>>()?
+        .try_into()
+        .map_err(|v: Vec<T>| {
+            Error::new(format!("expected 3 numbers, found {}", v.len())).at_token(line, s)
+        })
 }
-impl FromStr for Velocity {
-    type Err = ();
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let vec = s
-            .split(", ")
-            .map(|x| x.trim().parse::<i32>().unwrap())
-            .collect::<Vec<_>>();
-        assert_eq!(vec.len(), 3);
-        let vec = [vec[0], vec[1], vec[2]];
-        Ok(Velocity::new(vec))
+
+/// Bound on the size of every position coordinate
+const MAX_POSITION: i64 = 1 << 50;
+/// Bound on the size of every velocity component
+const MAX_SPEED: i64 = 1 << 12;
+
+/// Check every number of the triple `s` taken from `line` is smaller than
+/// `bound` either way
+fn check_bound(line: &str, s: &str, triple: [i64; 3], bound: i64, what: &str) -> Result<(), Error> {
+    match triple
+        .iter()
+        .zip(s.split(','))
+        .find(|(x, _)| x.unsigned_abs() >= bound as u64)
+    {
+        Some((_, token)) => Err(Error::new(format!(
+            "{} can't be more than {} either way",
+            what,
+            bound - 1
+        ))
+        .at_token(line, token.trim())),
+        None => Ok(()),
     }
 }
+
 #[derive(Clone, Copy)]
 struct InitialCondition {
-    position: Position,
-    velocity: Velocity,
+    position: Point3<i64>,
+    velocity: Point3<i64>,
 }
 impl InitialCondition {
-    fn new(position: Position, velocity: Velocity) -> InitialCondition {
+    fn new(position: Point3<i64>, velocity: Point3<i64>) -> InitialCondition {
         InitialCondition { position, velocity }
     }
+    /// Position and velocity in the axes `axes` compares, wide enough to
+    /// multiply
+    fn project(&self, axes: Axes) -> (Point3<i128>, Point3<i128>) {
+        let project = |p: Point3<i64>| match axes {
+            Axes::Xy => Point3::new(p.x as i128, p.y as i128, 0),
+            Axes::Xyz => p.map(|x| x as i128),
+        };
+        (project(self.position), project(self.velocity))
+    }
 }
 impl FromStr for InitialCondition {
-    type Err = ();
+    type Err = Error;
     /// Parse things like 19, 13, 30 @ -2,  1, -2
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (pos, vel) = s.split_once(" @ ").unwrap();
-        let pos = pos.parse::<Position>().unwrap();
-        let vel = vel.parse::<Velocity>().unwrap();
-        Ok(InitialCondition::new(pos, vel))
+        let (pos, vel) = s.split_once(" @ ").ok_or("expected ` @ `")?;
+        let position = parse_triple(s, pos)?;
+        let velocity = parse_triple(s, vel)?;
+        check_bound(s, pos, position, MAX_POSITION, "position")?;
+        check_bound(s, vel, velocity, MAX_SPEED, "velocity")?;
+        let (position, velocity) = (Point3::from(position), Point3::from(velocity));
+        if velocity == Point3::default() {
+            return Err(Error::new("velocity can't be zero").at_token(s, vel));
+        }
+        Ok(InitialCondition::new(position, velocity))
     }
 }
 impl Display for InitialCondition {
@@ -85,148 +82,406 @@ impl Display for InitialCondition {
         write!(f, "{} @ {}", self.position, self.velocity)
     }
 }
-struct Line {
-    m: f64,
-    b: f64,
+
+/// Axes the paths of stones are compared in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axes {
+    /// Looking down on the paths, ignoring Z like part a does
+    Xy,
+    Xyz,
+}
+
+/// How the paths of two stones meet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Crossing {
+    /// Both stones pass through `point`, `times` after they start
+    Intersecting {
+        point: [BigRational; 3],
+        times: [BigRational; 2],
+    },
+    /// Both stones are at `point` together, `time` after they start
+    Colliding {
+        point: [BigRational; 3],
+        time: BigRational,
+    },
+    /// The paths cross, but at least one stone was there before it started
+    PastOnly { times: [BigRational; 2] },
+    /// Side by side, never meeting
+    Parallel,
+    /// Along the same line
+    Coincident,
+    /// Never meeting without being parallel
+    Skew,
+}
+/// When two stones are closest, from the time they start on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Approach {
+    pub time: BigRational,
+    pub distance_squared: BigRational,
+}
+
+/// How two paths meet, classified with integers only
+///
+/// The times a crossing is reached are `times` over `denominator`, which
+/// is positive, and it's at `start + heading * times[0] / denominator`.
+/// With positions below [MAX_POSITION] and velocities below [MAX_SPEED],
+/// which parsing makes sure of, every product stays inside i128.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Meeting {
+    Crossing {
+        start: Point3<i128>,
+        heading: Point3<i128>,
+        times: [i128; 2],
+        denominator: i128,
+    },
+    Parallel,
+    Coincident,
+    Skew,
 }
-impl Line {
-    fn new(m: f64, b: f64) -> Line {
-        Line { m, b }
+impl Meeting {
+    /// Whether the crossing is reached by both stones after they start
+    fn is_ahead(&self) -> bool {
+        matches!(self, Meeting::Crossing { times, .. } if times.iter().all(|t| *t >= 0))
+    }
+    /// Name of the [Crossing] this becomes
+    fn name(&self) -> &'static str {
+        match self {
+            Meeting::Crossing { .. } if !self.is_ahead() => "past only",
+            Meeting::Crossing { times, .. } if times[0] == times[1] => "colliding",
+            Meeting::Crossing { .. } => "intersecting",
+            Meeting::Parallel => "parallel",
+            Meeting::Coincident => "coincident",
+            Meeting::Skew => "skew",
+        }
     }
 }
-impl Display for Line {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "y = {} x + {}", self.m, self.b)
-    }
-}
-impl TryFrom<InitialCondition> for Line {
-    type Error = ();
-    fn try_from(value: InitialCondition) -> Result<Self, Self::Error> {
-        // pos x,y moving at vel dx,dy
-        // y = mx + b
-        // when x is x we get
-        // y = b
-        let x = value.position.vec[0] as f64;
-        let y = value.position.vec[1] as f64;
-        let dx = value.velocity.vec[0] as f64;
-        let dy = value.velocity.vec[1] as f64;
-        let b = {
-            // we want to find the value for t when x is 0
-            // newx = x + dx * t
-            // -x = dx * t
-            // t = -x / dx
-            let t = -x / dx;
-            y + dy * t
-        };
-        if dx == 0_f64 {
-            return Err(());
+
+/// Box the intersections are counted in, bounds included
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TestVolume {
+    pub min: Point3<i64>,
+    pub max: Point3<i64>,
+}
+impl TestVolume {
+    /// From `min` to `max` along every axis
+    pub fn cube(min: i64, max: i64) -> TestVolume {
+        TestVolume {
+            min: Point3::new(min, min, min),
+            max: Point3::new(max, max, max),
         }
-        let m = dy / dx;
-        Ok(Line::new(m, b))
-    }
-}
-fn find_intersection(
-    a_cond: &InitialCondition,
-    a_line: &Line,
-    b_cond: &InitialCondition,
-    b_line: &Line,
-) -> Option<[f64; 2]> {
-    if a_line.m == b_line.m {
-        return None;
-    }
-    // y = mx + b
-    // m0 * x + b0 - m1 * x - b1 = 0
-    // m0 * x - m1 * x = b1 - b0
-    // x (m0 - m1) = b1 - b0
-    // x = (b1 - b0) / (m0 - m1)
-    let x = (b_line.b - a_line.b) / (a_line.m - b_line.m);
-    // x = pos_x + vel_x * t
-    // t = (x - pos_x) / vel_x
-    let t_a = (x - a_cond.position.vec[0] as f64) / a_cond.velocity.vec[0] as f64;
-    let t_b = (x - b_cond.position.vec[0] as f64) / b_cond.velocity.vec[0] as f64;
-    if t_a < 0.0 || t_b < 0.0 {
-        return None;
-    }
-    let y_a = a_line.m * x + a_line.b;
-    // let y_b = b.m * x + b.b;
-    // assert_eq!(y_a, y_b);
-    Some([x, y_a])
-}
-struct HailCloud {
+    }
+    /// Whether the crossing of `meeting` is inside, in the axes `axes`
+    /// compares
+    ///
+    /// Scaling the bounds by the denominator instead of dividing keeps it
+    /// exact.
+    fn contains(&self, meeting: &Meeting, axes: Axes) -> bool {
+        let Meeting::Crossing {
+            start,
+            heading,
+            times,
+            denominator,
+        } = meeting
+        else {
+            return false;
+        };
+        let dims = match axes {
+            Axes::Xy => 2,
+            Axes::Xyz => 3,
+        };
+        let point = (*start * *denominator + *heading * times[0]).to_array();
+        let (min, max) = (self.min.to_array(), self.max.to_array());
+        (0..dims).all(|n| {
+            min[n] as i128 * denominator <= point[n] && point[n] <= max[n] as i128 * denominator
+        })
+    }
+}
+
+fn ratio(x: i128) -> BigRational {
+    BigRational::from_integer(BigInt::from(x))
+}
+
+/// How a stone staying at `point` meets the path of one starting at
+/// `position` moving at `velocity`, with the times swapped if `swap`
+fn point_meeting(
+    point: Point3<i128>,
+    position: Point3<i128>,
+    velocity: Point3<i128>,
+    swap: bool,
+) -> Meeting {
+    let gap = point - position;
+    if velocity == Point3::default() {
+        return if gap == Point3::default() {
+            Meeting::Coincident
+        } else {
+            Meeting::Parallel
+        };
+    }
+    if gap.cross(&velocity) != Point3::default() {
+        return Meeting::Skew;
+    }
+    let t = gap.dot(&velocity);
+    Meeting::Crossing {
+        start: point,
+        heading: Point3::default(),
+        times: if swap { [t, 0] } else { [0, t] },
+        denominator: velocity.dot(&velocity),
+    }
+}
+
+pub struct HailCloud {
     stones: Vec<InitialCondition>,
-    stone_lines: Vec<Line>,
 }
 impl HailCloud {
     fn new(stones: Vec<InitialCondition>) -> HailCloud {
-        let stone_lines = stones
-            .iter()
-            .map(|x| Line::try_from(*x).unwrap())
-            .collect::<Vec<_>>();
-        HailCloud {
-            stones,
-            stone_lines,
+        HailCloud { stones }
+    }
+    /// How the paths of stones `i` and `j` meet in `axes`
+    ///
+    /// With `n` the cross product of the velocities the paths are
+    /// parallel when it's zero, and otherwise meet when the gap between
+    /// the starts has nothing along `n`.
+    fn meeting(&self, i: usize, j: usize, axes: Axes) -> Meeting {
+        let (pa, va) = self.stones[i].project(axes);
+        let (pb, vb) = self.stones[j].project(axes);
+        // looking down on a stone moving straight up or down, it's a point
+        if va == Point3::default() {
+            return point_meeting(pa, pb, vb, false);
+        }
+        if vb == Point3::default() {
+            return point_meeting(pb, pa, va, true);
+        }
+        let gap = pb - pa;
+        let n = va.cross(&vb);
+        if n == Point3::default() {
+            return if gap.cross(&va) == Point3::default() {
+                Meeting::Coincident
+            } else {
+                Meeting::Parallel
+            };
+        }
+        if gap.dot(&n) != 0 {
+            return Meeting::Skew;
+        }
+        Meeting::Crossing {
+            start: pa,
+            heading: va,
+            times: [gap.cross(&vb).dot(&n), gap.cross(&va).dot(&n)],
+            denominator: n.dot(&n),
+        }
+    }
+    /// How the paths of stones `i` and `j` meet in `axes`, exactly
+    pub fn crossing(&self, i: usize, j: usize, axes: Axes) -> Crossing {
+        let (start, heading, times, denominator) = match self.meeting(i, j, axes) {
+            Meeting::Crossing {
+                start,
+                heading,
+                times,
+                denominator,
+            } => (start, heading, times, denominator),
+            Meeting::Parallel => return Crossing::Parallel,
+            Meeting::Coincident => return Crossing::Coincident,
+            Meeting::Skew => return Crossing::Skew,
+        };
+        let denominator = ratio(denominator);
+        let [ta, tb] = times.map(|t| ratio(t) / &denominator);
+        if times.iter().any(|t| *t < 0) {
+            return Crossing::PastOnly { times: [ta, tb] };
+        }
+        let (start, heading) = (start.to_array(), heading.to_array());
+        let point = std::array::from_fn(|n| ratio(start[n]) + ratio(heading[n]) * &ta);
+        if times[0] == times[1] {
+            Crossing::Colliding { point, time: ta }
+        } else {
+            Crossing::Intersecting {
+                point,
+                times: [ta, tb],
+            }
         }
     }
-    fn count_intersections_in_test_area(&self, min_test: f64, max_test: f64) -> usize {
+    /// How every pair of paths meets in `axes`
+    pub fn crossings(&self, axes: Axes) -> impl Iterator<Item = ((usize, usize), Crossing)> + '_ {
+        (0..self.stones.len())
+            .flat_map(move |i| (i + 1..self.stones.len()).map(move |j| (i, j)))
+            .map(move |(i, j)| ((i, j), self.crossing(i, j, axes)))
+    }
+    /// When stones `i` and `j` are closest in `axes`, and how far apart
+    ///
+    /// With `gap` and `closing` the differences of their positions and
+    /// velocities, the squared distance `|gap + t closing|^2` is least at
+    /// `t = -gap.closing / |closing|^2`, or when they start if that's
+    /// before.
+    pub fn closest_approach(&self, i: usize, j: usize, axes: Axes) -> Approach {
+        let (pa, va) = self.stones[i].project(axes);
+        let (pb, vb) = self.stones[j].project(axes);
+        let (gap, closing) = (pb - pa, vb - va);
+        let time = if closing == Point3::default() || gap.dot(&closing) >= 0 {
+            ratio(0)
+        } else {
+            ratio(-gap.dot(&closing)) / ratio(closing.dot(&closing))
+        };
+        let (gap, closing) = (gap.to_array(), closing.to_array());
+        let distance_squared = (0..3)
+            .map(|n| {
+                let d = ratio(gap[n]) + ratio(closing[n]) * &time;
+                &d * &d
+            })
+            .fold(ratio(0), |sum, d| sum + d);
+        Approach {
+            time,
+            distance_squared,
+        }
+    }
+    /// Number of pairs of paths that cross inside `volume` in `axes`
+    pub fn count_intersections(&self, volume: &TestVolume, axes: Axes) -> usize {
+        let mut kinds = BTreeMap::new();
         let mut count = 0;
         for i in 0..self.stones.len() {
             for j in i + 1..self.stones.len() {
-                let intersection = find_intersection(
-                    &self.stones[i],
-                    &self.stone_lines[i],
-                    &self.stones[j],
-                    &self.stone_lines[j],
-                );
-                if intersection.is_none() {
-                    continue;
-                }
-                // println!(
-                //     "\nintersect\n\t{}\n\t{}\n\t{:?}",
-                //     self.stones[i], self.stones[j], intersection
-                // );
-                let intersection = intersection.unwrap();
-                if intersection[0] >= min_test
-                    && intersection[0] <= max_test
-                    && intersection[1] >= min_test
-                    && intersection[1] <= max_test
-                {
-                    // println!("inside");
+                let meeting = self.meeting(i, j, axes);
+                *kinds.entry(meeting.name()).or_insert(0) += 1;
+                if meeting.is_ahead() && volume.contains(&meeting, axes) {
                     count += 1;
                 }
             }
         }
+        debug!("{:?} pairs by kind {:?}", axes, kinds);
         count
     }
 }
 impl FromStr for HailCloud {
-    type Err = ();
+    type Err = Error;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let stones = s
-            .lines()
-            .map(|line| line.parse::<InitialCondition>().unwrap())
-            .collect::<Vec<_>>();
+        let stones = parse_lines::<InitialCondition>(s)?;
         Ok(HailCloud::new(stones))
     }
 }
 impl Display for HailCloud {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for (stone, line) in self.stones.iter().zip(self.stone_lines.iter()) {
-            writeln!(f, "{} -> {}", stone, line)?
+        for stone in &self.stones {
+            writeln!(f, "{}", stone)?
         }
         Ok(())
     }
 }
-pub fn run(input: &str, min_test: f64, max_test: f64) -> usize {
-    let hail = input.parse::<HailCloud>().unwrap();
-    // println!("{}", hail);
-    hail.count_intersections_in_test_area(min_test, max_test)
+pub fn run(input: &str, min_test: i64, max_test: i64) -> Result<usize, Error> {
+    let hail = input.parse::<HailCloud>()?;
+    debug!("{}", hail);
+    Ok(hail.count_intersections(&TestVolume::cube(min_test, max_test), Axes::Xy))
 }
 
 #[cfg(test)]
 mod tests {
+    use num::BigRational;
+
+    use super::{Approach, Axes, Crossing, HailCloud, TestVolume};
+
     #[test]
     fn test1() {
         let input = include_str!("example_data.txt");
-        assert_eq!(super::run(input, 7_f64, 27_f64), 2);
+        assert_eq!(super::run(input, 7, 27).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_main() {
+        let input = include_str!("data.txt");
+        let volume = TestVolume::cube(200_000_000_000_000, 400_000_000_000_000);
+        let hail = input.parse::<HailCloud>().unwrap();
+        assert_eq!(hail.count_intersections(&volume, Axes::Xy), 13965);
+    }
+
+    #[test]
+    fn test_crossing() {
+        let input = include_str!("example_data.txt");
+        let hail = input.parse::<HailCloud>().unwrap();
+        let r = |n: i64, d: i64| BigRational::new(n.into(), d.into());
+        assert_eq!(
+            hail.crossing(0, 1, Axes::Xy),
+            Crossing::Intersecting {
+                point: [r(43, 3), r(46, 3), r(0, 1)],
+                times: [r(7, 3), r(11, 3)],
+            }
+        );
+        assert_eq!(hail.crossing(1, 2, Axes::Xy), Crossing::Parallel);
+        assert!(matches!(
+            hail.crossing(0, 4, Axes::Xy),
+            Crossing::PastOnly { .. }
+        ));
+        assert_eq!(hail.crossings(Axes::Xyz).count(), 10);
+
+        let input = "0, 0, 0 @ 1, 0, 0
+5, 0, 0 @ 2, 0, 0
+0, 1, 0 @ 0, 0, 1
+0, 3, 3 @ 0, 0, -1
+2, 0, -2 @ 0, 0, 1";
+        let hail = input.parse::<HailCloud>().unwrap();
+        assert_eq!(hail.crossing(0, 1, Axes::Xyz), Crossing::Coincident);
+        assert_eq!(hail.crossing(0, 2, Axes::Xyz), Crossing::Skew);
+        assert_eq!(hail.crossing(2, 3, Axes::Xyz), Crossing::Parallel);
+        assert_eq!(hail.crossing(2, 3, Axes::Xy), Crossing::Parallel);
+        assert!(matches!(
+            hail.crossing(1, 4, Axes::Xyz),
+            Crossing::PastOnly { .. }
+        ));
+        // straight up and down looks like a point from above
+        let meet = Crossing::Colliding {
+            point: [r(2, 1), r(0, 1), r(0, 1)],
+            time: r(2, 1),
+        };
+        assert_eq!(hail.crossing(0, 4, Axes::Xyz), meet);
+        let meet = Crossing::Intersecting {
+            point: [r(2, 1), r(0, 1), r(0, 1)],
+            times: [r(2, 1), r(0, 1)],
+        };
+        assert_eq!(hail.crossing(0, 4, Axes::Xy), meet);
+        let volume = TestVolume::cube(-5, 5);
+        assert_eq!(hail.count_intersections(&volume, Axes::Xyz), 1);
+        assert_eq!(
+            hail.count_intersections(&TestVolume::cube(3, 5), Axes::Xyz),
+            0
+        );
+
+        let approach = |time, distance_squared| Approach {
+            time,
+            distance_squared,
+        };
+        assert_eq!(
+            hail.closest_approach(0, 2, Axes::Xyz),
+            approach(r(0, 1), r(1, 1))
+        );
+        assert_eq!(
+            hail.closest_approach(0, 4, Axes::Xyz),
+            approach(r(2, 1), r(0, 1))
+        );
+        // the one ahead is faster, so they're closest at the start
+        assert_eq!(
+            hail.closest_approach(0, 1, Axes::Xyz),
+            approach(r(0, 1), r(25, 1))
+        );
+        assert_eq!(
+            hail.closest_approach(2, 3, Axes::Xyz),
+            approach(r(3, 2), r(4, 1))
+        );
+
+        let error = "1, 2, 3 @ 0, 0, 0".parse::<HailCloud>().err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "line 1, column 11: velocity can't be zero"
+        );
+        // too big to classify exactly in i128
+        let input = "0, 0, 0 @ 1000000000, 7, 1\n10, 0, 0 @ 3, 1000000000, 7";
+        let error = input.parse::<HailCloud>().err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "line 1, column 11: velocity can't be more than 4095 either way"
+        );
+        let error = "1, -1125899906842624, 3 @ 1, 1, 1"
+            .parse::<HailCloud>()
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "line 1, column 4: position can't be more than 1125899906842623 either way"
+        );
     }
 }
@@ -1,81 +1,52 @@
-use std::{fmt::Display, str::FromStr, time::Instant};
+use std::{fmt::Display, str::FromStr};
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, Default, Debug, Clone, Copy)]
-struct Position {
-    vec: [i64; 3],
-}
-impl Position {
-    fn new(vec: [i64; 3]) -> Position {
-        Position { vec }
-    }
-    fn sum(&self) -> i64 {
-        self.vec.iter().sum()
-    }
-}
+use itertools::Itertools;
+use num::{BigInt, BigRational, ToPrimitive, Zero};
 
-impl Display for Position {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}, {}, {}", self.vec[0], self.vec[1], self.vec[2])
-    }
-}
-impl FromStr for Position {
-    type Err = ();
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let vec = s
-            .split(", ")
-            .map(|x| x.parse::<i64>().unwrap())
-            .collect::<Vec<_>>();
-        assert_eq!(vec.len(), 3);
-        let vec = [vec[0], vec[1], vec[2]];
-        Ok(Position::new(vec))
-    }
-}
-#[derive(PartialEq, Eq, PartialOrd, Ord, Default, Debug, Clone, Copy)]
-struct Velocity {
-    vec: [i64; 3],
-}
-impl Velocity {
-    fn new(vec: [i64; 3]) -> Velocity {
-        Velocity { vec }
-    }
-}
-impl Display for Velocity {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}, {}, {}", self.vec[0], self.vec[1], self.vec[2])
-    }
-}
-impl FromStr for Velocity {
-    type Err = ();
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let vec = s
-            .split(", ")
-            .map(|x| x.trim().parse::<i64>().unwrap())
-            .collect::<Vec<_>>();
-        assert_eq!(vec.len(), 3);
-        let vec = [vec[0], vec[1], vec[2]];
-        Ok(Velocity::new(vec))
-    }
+use super::simulate::{Hit, Sample};
+use crate::{
+    debug,
+    error::{parse_lines, parse_token, Error},
+    geometry::Point3,
+};
+
+/// Parse three comma separated numbers taken from `line`
+fn parse_triple<T: FromStr>(line: &str, s: &str) -> Result<[T; 3], Error> {
+    s.split(',')
+        .map(|x| parse_token::<T>(line, x.trim()))
+        .collect::<Result<Vec<_>, _>>()?
+        .try_into()
+        .map_err(|v: Vec<T>| {
+            Error::new(format!("expected 3 numbers, found {}", v.len())).at_token(line, s)
+        })
 }
+
 #[derive(PartialEq, Eq, PartialOrd, Ord, Default, Clone, Copy, Debug)]
 struct InitialCondition {
-    position: Position,
-    velocity: Velocity,
+    position: Point3<i64>,
+    velocity: Point3<i64>,
 }
 impl InitialCondition {
-    fn new(position: Position, velocity: Velocity) -> InitialCondition {
+    fn new(position: Point3<i64>, velocity: Point3<i64>) -> InitialCondition {
         InitialCondition { position, velocity }
     }
     fn position_sum(&self) -> i64 {
-        self.position.sum()
+        self.position.x + self.position.y + self.position.z
+    }
+    /// Position `time` after the start, wide enough to go far into the
+    /// future
+    fn at(&self, time: i64) -> Point3<i128> {
+        let wide = |p: Point3<i64>| p.map(|x| x as i128);
+        wide(self.position) + wide(self.velocity) * time as i128
     }
 }
 impl FromStr for InitialCondition {
-    type Err = ();
+    type Err = Error;
     /// Parse things like 19, 13, 30 @ -2,  1, -2
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (pos, vel) = s.split_once(" @ ").unwrap();
-        let pos = pos.parse::<Position>().unwrap();
-        let vel = vel.parse::<Velocity>().unwrap();
+        let (pos, vel) = s.split_once(" @ ").ok_or("expected ` @ `")?;
+        let pos = Point3::from(parse_triple(s, pos)?);
+        let vel = Point3::from(parse_triple(s, vel)?);
         Ok(InitialCondition::new(pos, vel))
     }
 }
@@ -84,214 +55,184 @@ impl Display for InitialCondition {
         write!(f, "{} @ {}", self.position, self.velocity)
     }
 }
-struct HailCloud {
+pub struct HailCloud {
     stones: Vec<InitialCondition>,
-    p: Vec<Vec<f64>>,
-    v: Vec<Vec<f64>>,
 }
 impl HailCloud {
     fn new(stones: Vec<InitialCondition>) -> HailCloud {
-        let p = stones
-            .iter()
-            .map(|s| s.position.vec.iter().map(|x| *x as f64).collect::<Vec<_>>())
-            .collect::<Vec<_>>();
-        let v = stones
-            .iter()
-            .map(|s| s.velocity.vec.iter().map(|x| *x as f64).collect::<Vec<_>>())
-            .collect::<Vec<_>>();
-        HailCloud { stones, p, v }
+        HailCloud { stones }
     }
 
     /// Solve for rock that will pass through all hail positions
     /// and return sum of initial position coords
-    fn run(&self) -> i64 {
-        self.estimate_rock().position_sum()
+    fn run(&self) -> Result<i64, Error> {
+        let rock = self.rock()?;
+        let times = self.hit_times(&rock)?;
+        debug!(
+            "rock {} hits the last stone at {}",
+            rock,
+            times.iter().max().unwrap()
+        );
+        Ok(rock.position_sum())
     }
-    fn estimate_error(&self, rp: &[f64; 3], rv: &[f64; 3], t: &[f64]) -> f64 {
-        let mut error = 0.0;
-        for i in 0..self.stones.len() {
-            let p = &self.stones[i].position.vec;
-            let v = &self.stones[i].velocity.vec;
-            for n in 0..3 {
-                let rock_p = rp[n] + rv[n] * t[i];
-                let stone_p = p[n] as f64 + v[n] as f64 * t[i];
-                error += (stone_p - rock_p).powi(2);
-            }
-        }
-        error
+
+    /// Three equations linear in the rock's position and velocity, in
+    /// that order, that hold if the rock hits stones `a` and `b`
+    ///
+    /// Hitting stone `i` means `(P - p_i) x (V - v_i) = 0`. The rock's own
+    /// `P x V` term is the same for every stone so it cancels out between
+    /// two of them, leaving
+    /// `P x (v_a - v_b) + (p_a - p_b) x V = p_a x v_a - p_b x v_b`.
+    fn equations(a: &InitialCondition, b: &InitialCondition) -> [[i128; 7]; 3] {
+        let wide = |p: Point3<i64>| p.map(|x| x as i128);
+        let (pa, va, pb, vb) = (
+            wide(a.position),
+            wide(a.velocity),
+            wide(b.position),
+            wide(b.velocity),
+        );
+        let dp = pa - pb;
+        let dv = va - vb;
+        let rhs = pa.cross(&va) - pb.cross(&vb);
+        [
+            [0, dv.z, -dv.y, 0, -dp.z, dp.y, rhs.x],
+            [-dv.z, 0, dv.x, dp.z, 0, -dp.x, rhs.y],
+            [dv.y, -dv.x, 0, -dp.y, dp.x, 0, rhs.z],
+        ]
     }
-    /// Make a guess at the rock's initial conditions by observing
-    /// when in time the stones will be close together
-    fn estimate_rock(&self) -> InitialCondition {
-        let mut rp = [0_f64;3];
-        let mut rv = [0_f64;3];
-        let mut t = vec![0_f64;self.stones.len()];
 
-        // use gradient descent to refine solution
-        let mut rock;
-        let mut last_rock = InitialCondition::default();
-        let mut it = 0;
-        let start = Instant::now();
-        let mut debug: bool;
-        loop {
-            it += 1;
-            if it == 500 {
-                dbg!(rp);
-                dbg!(rv);
-                dbg!(t);
-                panic!();
-            }
-            debug = false;
-            if it % 10 == 0 {
-                debug = true;
-                let error = self.estimate_error(&rp, &rv, &t);
-                println!(
-                    "it {}, t: {} error: {:.2e} last_rock: {}",
-                    it,
-                    start.elapsed().as_secs(),
-                    error,
-                    last_rock
-                );
-            }
-            // newton's method of finding local minima
-            // update time
-            let mut max_time_change: f64 = 0.0;
-            for i in 0..self.stones.len() {
-                let p = &self.p[i];
-                let v = &self.v[i];
-                let mut ddt: f64 = 0.0;
-                let mut dt: f64 = 0.0;
-                for n in 0..3 {
-                    ddt += 2.0 * ((rv[n] - v[n]).powi(2));
-                    dt += (-2.0 * rv[n] + 2.0 * v[n]) * (p[n] - rp[n] - rv[n] * t[i] + t[i] * v[n]);
-                }
-                if debug {
-                    max_time_change = max_time_change.max((dt / ddt).abs())
-                }
-                t[i] -= dt / ddt;
-            }
-            if debug {
-                print!("dt: {} ", max_time_change);
-            }
-            // update rp
-            {
-                let mut max_pos_change: f64 = 0.0;
-                let ddp: f64 = 2.0 * self.stones.len() as f64;
-                let mut dp = [0.0_f64; 3];
-                for i in 0..self.stones.len() {
-                    let p = &self.p[i];
-                    let v = &self.v[i];
-                    for n in 0..3 {
-                        dp[n] += -2.0 * p[n] + 2.0 * rp[n] + 2.0 * rv[n] * t[i] - 2.0 * t[i] * v[n];
-                    }
-                }
-                for n in 0..3 {
-                    if debug {
-                        max_pos_change = max_pos_change.max((dp[n] / ddp).abs());
-                    }
-                    rp[n] -= dp[n] / ddp;
-                }
-                if debug {
-                    print!("dp: {} ", max_pos_change);
-                }
-            }
-            // update rv
-            {
-                let mut ddv = [0.0_f64; 3];
-                let mut dv = [0.0_f64; 3];
-                let mut max_v_change: f64 = 0.0;
-                for i in 0..self.stones.len() {
-                    let p = &self.p[i];
-                    let v = &self.v[i];
-                    for n in 0..3 {
-                        ddv[n] += 2.0 * t[i].powi(2);
-                        dv[n] += -2.0 * t[i] * (p[n] - rp[n] - rv[n] * t[i] + t[i] * v[n]);
-                    }
-                }
-                for n in 0..3 {
-                    if debug {
-                        max_v_change = max_v_change.max((dv[n] / ddv[n]).abs());
-                    }
-                    rv[n] -= dv[n] / ddv[n];
-                }
-                if debug {
-                    println!("dv: {} ", max_v_change);
+    /// Rock that hits every stone, from the equations of the first three
+    /// stones that pin it down
+    fn rock(&self) -> Result<InitialCondition, Error> {
+        let solution = self
+            .stones
+            .iter()
+            .tuple_combinations()
+            .find_map(|(a, b, c)| {
+                let rows = HailCloud::equations(a, b)
+                    .into_iter()
+                    .chain(HailCloud::equations(a, c))
+                    .map(|row| {
+                        row.map(|x| BigRational::from_integer(BigInt::from(x)))
+                            .to_vec()
+                    })
+                    .collect();
+                solve_linear(rows)
+            })
+            .ok_or_else(|| Error::new("no three stones pin down the rock"))?;
+        let whole = solution
+            .iter()
+            .map(|x| x.is_integer().then(|| x.to_integer().to_i64()).flatten())
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| Error::new("the rock doesn't start at whole numbers"))?;
+        Ok(InitialCondition::new(
+            Point3::new(whole[0], whole[1], whole[2]),
+            Point3::new(whole[3], whole[4], whole[5]),
+        ))
+    }
+
+    /// Time `rock` hits `stone`, if it does at a whole, non-negative time
+    fn hit_time(rock: &InitialCondition, stone: &InitialCondition) -> Option<i64> {
+        let wide = |p: Point3<i64>| p.map(|x| x as i128).to_array();
+        let (rp, rv) = (wide(rock.position), wide(rock.velocity));
+        let (sp, sv) = (wide(stone.position), wide(stone.velocity));
+        // stone_pos - rock_pos = t * (rock_vel - stone_vel)
+        let t = match (0..3).find(|&n| rv[n] != sv[n]) {
+            Some(n) => {
+                let (dp, dv) = (sp[n] - rp[n], rv[n] - sv[n]);
+                if dp % dv != 0 {
+                    return None;
                 }
+                dp / dv
             }
+            // side by side forever, they only meet if they start together
+            None => 0,
+        };
+        let hit = t >= 0 && (0..3).all(|n| rp[n] + rv[n] * t == sp[n] + sv[n] * t);
+        hit.then(|| t.try_into().ok()).flatten()
+    }
 
-            if !rp.iter().all(|x| x.is_finite()) {
-                dbg!(&rp);
-                panic!("rp not finite");
-            }
-            let rock_position = Position::new([
-                rp[0].round() as i64,
-                rp[1].round() as i64,
-                rp[2].round() as i64,
-            ]);
-            if !rv.iter().all(|x| x.is_finite()) {
-                panic!("rv not finite");
-            }
-            let rock_velocity = Velocity::new([
-                rv[0].round() as i64,
-                rv[1].round() as i64,
-                rv[2].round() as i64,
-            ]);
-            rock = InitialCondition::new(rock_position, rock_velocity);
-            // if rock != last_rock {
-            if self.verify_rock(&rock, it == 499) {
-                println!("Solved after it: {} rock: {}", it, rock);
-                break;
-            }
-            // }
-            last_rock = rock;
+    /// Time `rock` hits each stone, an error for the first one it misses
+    fn hit_times(&self, rock: &InitialCondition) -> Result<Vec<i64>, Error> {
+        self.stones
+            .iter()
+            .enumerate()
+            .map(|(i, stone)| {
+                HailCloud::hit_time(rock, stone).ok_or_else(|| {
+                    Error::new(format!("rock {} misses stone {}", rock, stone)).at_line(i + 1)
+                })
+            })
+            .collect()
+    }
+
+    /// Every stone's position from `t0` to `t1` every `dt`, a time at a
+    /// time in the order of the input
+    pub fn simulate(
+        &self,
+        t0: i64,
+        t1: i64,
+        dt: i64,
+    ) -> Result<impl Iterator<Item = Sample> + '_, Error> {
+        if dt <= 0 {
+            return Err(Error::new(format!("time step {} isn't positive", dt)));
         }
+        let times = (0..)
+            .map(move |n| t0.checked_add(n * dt))
+            .take_while(move |time| time.is_some_and(|time| time <= t1))
+            .flatten();
+        Ok(times.flat_map(move |time| {
+            self.stones
+                .iter()
+                .enumerate()
+                .map(move |(stone, s)| Sample {
+                    time,
+                    stone,
+                    position: s.at(time),
+                })
+        }))
+    }
 
-        rock
+    /// Where and when the rock hits each stone, in the order of the input
+    pub fn hits(&self) -> Result<Vec<Hit>, Error> {
+        let rock = self.rock()?;
+        let times = self.hit_times(&rock)?;
+        Ok(times
+            .into_iter()
+            .enumerate()
+            .map(|(stone, time)| Hit {
+                stone,
+                time,
+                position: rock.at(time),
+            })
+            .collect())
     }
-    /// Return true if rock intersects all stones. Assume stones at i and j are already checked
-    fn verify_rock(&self, rock: &InitialCondition, debug: bool) -> bool {
-        for k in 0..self.stones.len() {
-            // stone_loc = stone_pos + stone_vel * t
-            // rock_loc = rock_pos + rock_vel * t
-            // stone_pos + stone_vel * t = rock_pos + rock_vel * t
-            // stone_pos - rock_pos = t * (rock_vel - stone_vel)
-            // t = (stone_pos - rock_pos) / (rock_vel - stone_vel)
-            let stone = &self.stones[k];
-            let bad_x = rock.velocity.vec[0] as i64 == stone.velocity.vec[0] as i64;
-            let t = if bad_x {
-                (stone.position.vec[1] as i64 - rock.position.vec[1] as i64)
-                    / (rock.velocity.vec[1] as i64 - stone.velocity.vec[1] as i64)
-            } else {
-                (stone.position.vec[0] as i64 - rock.position.vec[0] as i64)
-                    / (rock.velocity.vec[0] as i64 - stone.velocity.vec[0] as i64)
-            };
-            if t < 0 {
-                if debug {
-                    println!("negative time stone: {}", k);
-                }
-                return false;
-            }
-            for n in 0..3 {
-                if rock.position.vec[n] as i64 + rock.velocity.vec[n] as i64 * t
-                    != stone.position.vec[n] as i64 + stone.velocity.vec[n] as i64 * t
-                {
-                    if debug {
-                        println!("non-match at stone {} dim {}", k, n);
-                    }
-                    return false;
-                }
+}
+
+/// Solve the square system with `rows` of coefficients followed by the
+/// right hand side exactly, None if it has no single solution
+fn solve_linear(mut rows: Vec<Vec<BigRational>>) -> Option<Vec<BigRational>> {
+    let n = rows.len();
+    for col in 0..n {
+        let pivot = (col..n).find(|&r| !rows[r][col].is_zero())?;
+        rows.swap(col, pivot);
+        let pivot_row = rows[col].clone();
+        for (r, row) in rows.iter_mut().enumerate() {
+            if r == col || row[col].is_zero() {
+                continue;
             }
+            let factor = &row[col] / &pivot_row[col];
+            row.iter_mut()
+                .zip(&pivot_row)
+                .skip(col)
+                .for_each(|(x, p)| *x -= &factor * p);
         }
-        true
     }
+    Some((0..n).map(|r| &rows[r][n] / &rows[r][r]).collect())
 }
 impl FromStr for HailCloud {
-    type Err = ();
+    type Err = Error;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let stones = s
-            .lines()
-            .map(|line| line.parse::<InitialCondition>().unwrap())
-            .collect::<Vec<_>>();
+        let stones = parse_lines::<InitialCondition>(s)?;
         Ok(HailCloud::new(stones))
     }
 }
@@ -303,21 +244,72 @@ impl Display for HailCloud {
         Ok(())
     }
 }
-pub fn run(input: &str) -> i64 {
-    let hail = input.parse::<HailCloud>().unwrap();
+pub fn run(input: &str) -> Result<i64, Error> {
+    let hail = input.parse::<HailCloud>()?;
     hail.run()
 }
 
 #[cfg(test)]
 mod tests {
+    use super::{HailCloud, InitialCondition};
+    use crate::geometry::Point3;
+
     #[test]
     fn test1() {
         let input = include_str!("example_data.txt");
-        assert_eq!(super::run(input), 47);
+        assert_eq!(super::run(input).unwrap(), 47);
     }
     #[test]
     fn test2() {
         let input = include_str!("data.txt");
-        assert_eq!(super::run(input), 0);
+        assert_eq!(super::run(input).unwrap(), 578177720733043);
+    }
+    #[test]
+    fn test_rock() {
+        let input = include_str!("example_data.txt");
+        let hail = input.parse::<HailCloud>().unwrap();
+        let rock = hail.rock().unwrap();
+        assert_eq!(rock.to_string(), "24, 13, 10 @ -3, 1, 2");
+        assert_eq!(hail.hit_times(&rock).unwrap(), [5, 3, 4, 6, 1]);
+
+        let miss = "25, 13, 10 @ -3, 1, 2".parse::<InitialCondition>().unwrap();
+        let error = hail.hit_times(&miss).unwrap_err();
+        assert!(error.to_string().starts_with("line 1: rock "));
+
+        let error = "19, 13, 30 @ -2, 1, -2\n18, 19, 22 @ -1, -1, -2"
+            .parse::<HailCloud>()
+            .unwrap()
+            .run()
+            .unwrap_err();
+        assert_eq!(error.to_string(), "no three stones pin down the rock");
+    }
+    #[test]
+    fn test_simulate() {
+        let input = include_str!("example_data.txt");
+        let hail = input.parse::<HailCloud>().unwrap();
+        let samples = hail.simulate(0, 4, 2).unwrap().collect::<Vec<_>>();
+        assert_eq!(samples.len(), 15);
+        assert_eq!(
+            (samples[0].time, samples[0].stone, samples[0].position),
+            (0, 0, Point3::new(19, 13, 30))
+        );
+        assert_eq!(
+            (samples[14].time, samples[14].stone, samples[14].position),
+            (4, 4, Point3::new(24, -1, 3))
+        );
+        assert_eq!(hail.simulate(5, 4, 1).unwrap().count(), 0);
+        let error = hail.simulate(0, 4, -1).err().unwrap();
+        assert_eq!(error.to_string(), "time step -1 isn't positive");
+
+        let hits = hail.hits().unwrap();
+        assert_eq!(
+            hits.iter().map(|hit| hit.time).collect::<Vec<_>>(),
+            [5, 3, 4, 6, 1]
+        );
+        // hailstone A is hit at 9, 18, 20
+        assert_eq!(hits[0].position, Point3::new(9, 18, 20));
+        assert!(hits
+            .iter()
+            .all(|hit| hail.stones[hit.stone].at(hit.time) == hit.position));
     }
 }
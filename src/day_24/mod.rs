@@ -1,11 +1,19 @@
-mod a;
-mod b;
+use crate::{
+    error::Result,
+    solution::{Answer, Solution},
+};
 
-pub fn run() {
-    let input = include_str!("data.txt");
-    println!(
-        "day 24 a {}",
-        a::run(input, 200_000_000_000_000_f64, 400_000_000_000_000_f64)
-    );
-    println!("day 24 b {}", b::run(input));
+pub mod a;
+pub mod b;
+pub mod simulate;
+
+pub struct Day24;
+
+impl Solution for Day24 {
+    fn part_a(&self, input: &str) -> Result<Answer> {
+        Ok(a::run(input, 200_000_000_000_000, 400_000_000_000_000)?.into())
+    }
+    fn part_b(&self, input: &str) -> Result<Answer> {
+        Ok(b::run(input)?.into())
+    }
 }
@@ -0,0 +1,75 @@
+//! Hail stepped forward in time and the rock's hits as CSV, for plotting
+//! without parsing the input again
+
+use std::io::{self, Write};
+
+use crate::geometry::Point3;
+
+/// Where stone number `stone` is at `time`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sample {
+    pub time: i64,
+    pub stone: usize,
+    pub position: Point3<i128>,
+}
+
+/// When and where the rock hits stone number `stone`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hit {
+    pub stone: usize,
+    pub time: i64,
+    pub position: Point3<i128>,
+}
+
+/// Write samples as CSV with a header row, one line per stone per time
+pub fn write_samples(
+    out: &mut impl Write,
+    samples: impl IntoIterator<Item = Sample>,
+) -> io::Result<()> {
+    writeln!(out, "time,stone,x,y,z")?;
+    for Sample {
+        time,
+        stone,
+        position,
+    } in samples
+    {
+        let Point3 { x, y, z } = position;
+        writeln!(out, "{},{},{},{},{}", time, stone, x, y, z)?;
+    }
+    Ok(())
+}
+
+/// Write hits as CSV with a header row
+pub fn write_hits(out: &mut impl Write, hits: &[Hit]) -> io::Result<()> {
+    writeln!(out, "stone,time,x,y,z")?;
+    for hit in hits {
+        let Point3 { x, y, z } = hit.position;
+        writeln!(out, "{},{},{},{},{}", hit.stone, hit.time, x, y, z)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{write_hits, write_samples};
+    use crate::day_24::b::HailCloud;
+
+    #[test]
+    fn test_write() {
+        let input = include_str!("example_data.txt");
+        let hail = input.parse::<HailCloud>().unwrap();
+        let mut out = Vec::new();
+        write_samples(&mut out, hail.simulate(0, 1, 1).unwrap()).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+        let lines = csv.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 11);
+        assert_eq!(lines[0], "time,stone,x,y,z");
+        assert_eq!(lines[1], "0,0,19,13,30");
+        assert_eq!(lines[10], "1,4,21,14,12");
+
+        let mut out = Vec::new();
+        write_hits(&mut out, &hail.hits().unwrap()).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+        assert!(csv.starts_with("stone,time,x,y,z\n0,5,9,18,20\n1,3,15,16,16\n"));
+    }
+}
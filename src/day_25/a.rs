@@ -1,82 +1,266 @@
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::HashMap,
     fmt::Display,
+    io::{self, Write},
     str::FromStr,
 };
 
-use petgraph::{data::Build, graph::UnGraph, stable_graph::IndexType, visit::{EdgeRef, NodeRef}, Graph, Undirected};
+use petgraph::graph::{NodeIndex, UnGraph};
 
-struct AocGraph {
-    graph: Graph<String, String, Undirected>,
-    // node_map: HashMap<String, _>,
-    // node_num_to_str: BTreeMap<u32, String>,
+use super::min_cut::{Cut, MinCutStrategy};
+use crate::{
+    debug,
+    error::{Error, Result},
+    partition::Csr,
+};
+
+/// Components as nodes, wires as edges
+pub struct AocGraph {
+    graph: UnGraph<String, ()>,
+    strategy: MinCutStrategy,
 }
 
 impl FromStr for AocGraph {
-    type Err = ();
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        todo!("trying to make a graph like here https://docs.rs/petgraph/latest/petgraph/graph/struct.Graph.html#");
-        // let mut edges = Vec::new();
-        // let mut node_map = HashMap::new();
-        let mut graph = Graph::<String, String>::new();
-
-        for line in s.lines() {
-            let (l_node, r_nodes) = line.split_once(": ").unwrap();
-            graph.add_node(weight)
-            // if !node_map.contains_key(l_node) {
-            //     node_map.insert(l_node.to_string(), graph.add_node(l_node.to_string()));
-            // }
-            for r_node in r_nodes.split(" ") {
-                // if !node_str_to_num.contains_key(r_node) {
-                //     node_str_to_num.insert(r_node.to_string(), node_count);
-                //     node_num_to_str.insert(node_count, r_node.to_string());
-                //     node_count += 1;
-                // }
-                // edges.push((l_node.to_string(), r_node.to_string()));
-                graph.add_edge(l_node.clone(), r_node.clone(), 1);
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
+        let mut graph = UnGraph::new_undirected();
+        let mut nodes = HashMap::new();
+        let mut node = |graph: &mut UnGraph<String, ()>, name: &str| {
+            *nodes
+                .entry(name.to_string())
+                .or_insert_with(|| graph.add_node(name.to_string()))
+        };
+        for (iline, line) in s.lines().enumerate() {
+            let (l_node, r_nodes) = line
+                .split_once(": ")
+                .ok_or_else(|| Error::new("expected `node: nodes`").at_line(iline + 1))?;
+            let l_index = node(&mut graph, l_node);
+            for r_node in r_nodes.split_whitespace() {
+                let r_index = node(&mut graph, r_node);
+                graph.add_edge(l_index, r_index, ());
             }
         }
-        // let graph = UnGraph::<String, String>::from_edges(edges.iter());
+        if graph.node_count() < 2 {
+            return Err(Error::new("need at least two components"));
+        }
         Ok(AocGraph {
             graph,
+            strategy: MinCutStrategy::default(),
         })
     }
 }
 
+/// Write the wiring list back out, each wire once
 impl Display for AocGraph {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for edge in self.graph.edges() {
-            let l_node = edge.source();
-            let l_node = l_node.index() as u32;
-            let l_node = self.node_num_to_str[&l_node];
-            write!(f, "{} ", l_node).unwrap();
-            for r_node in set {
-                write!(f, "{} ", r_node).unwrap();
+        for node in self.graph.node_indices() {
+            let mut later = self
+                .graph
+                .neighbors(node)
+                .filter(|n| *n > node)
+                .collect::<Vec<_>>();
+            if later.is_empty() {
+                continue;
             }
-            writeln!(f, "").unwrap();
+            later.sort();
+            write!(f, "{}:", self.graph[node])?;
+            for neighbor in later {
+                write!(f, " {}", self.graph[neighbor])?;
+            }
+            writeln!(f)?;
         }
         Ok(())
     }
 }
 
+/// Colors of the side of the first node and the other side in DOT output
+const SIDE_COLORS: [&str; 2] = ["lightblue", "lightsalmon"];
+
 impl AocGraph {
+    /// Use `strategy` to find the cut, Stoer–Wagner by default
+    pub fn with_strategy(mut self, strategy: MinCutStrategy) -> AocGraph {
+        self.strategy = strategy;
+        self
+    }
+
+    pub fn min_cut(&self) -> Cut {
+        self.strategy.min_cut(&self.graph)
+    }
+
+    /// Wiring as partitioner input, every wire weighs 1
+    pub fn csr(&self) -> Result<Csr> {
+        Csr::from_graph(&self.graph, |_| 1)
+    }
+
+    /// Names of the components at both ends of each cut wire
+    pub fn cut_wires(&self, cut: &Cut) -> Vec<(&str, &str)> {
+        cut.edges
+            .iter()
+            .map(|&edge| {
+                let (a, b) = self.graph.edge_endpoints(edge).unwrap();
+                (self.graph[a].as_str(), self.graph[b].as_str())
+            })
+            .collect()
+    }
+
+    /// Cut wires and the size of both sides, one per line
+    pub fn cut_report(&self, cut: &Cut) -> String {
+        let mut report = self
+            .cut_wires(cut)
+            .into_iter()
+            .map(|(a, b)| format!("cut {}/{}\n", a, b))
+            .collect::<String>();
+        let [a, b] = cut.sizes(self.graph.node_count());
+        report += &format!("sides {} x {} = {}\n", a, b, a * b);
+        report
+    }
+
+    /// Write the wiring as a Graphviz graph, nodes filled with the color of
+    /// their side and cut wires drawn thick and red
+    pub fn write_dot(&self, out: &mut impl Write, cut: &Cut) -> io::Result<()> {
+        let mut on_side = vec![false; self.graph.node_count()];
+        cut.side.iter().for_each(|n| on_side[n.index()] = true);
+        let name = |node: NodeIndex| format!("\"{}\"", self.graph[node].replace('"', "\\\""));
+        writeln!(out, "graph wiring {{")?;
+        writeln!(out, "    node [style=filled];")?;
+        for node in self.graph.node_indices() {
+            let color = SIDE_COLORS[usize::from(!on_side[node.index()])];
+            writeln!(out, "    {} [fillcolor={}];", name(node), color)?;
+        }
+        for edge in self.graph.edge_indices() {
+            let (a, b) = self.graph.edge_endpoints(edge).unwrap();
+            if cut.edges.contains(&edge) {
+                writeln!(
+                    out,
+                    "    {} -- {} [color=red, penwidth=3];",
+                    name(a),
+                    name(b)
+                )?;
+            } else {
+                writeln!(out, "    {} -- {};", name(a), name(b))?;
+            }
+        }
+        writeln!(out, "}}")
+    }
+
     /// Find partition that has cost 3, return product of sizes
-    fn run(&self) -> usize {
-        todo!();
+    pub fn run(&self) -> Result<usize> {
+        let cut = self.min_cut();
+        for line in self.cut_report(&cut).lines() {
+            debug!("{}", line);
+        }
+        if cut.edges.len() != 3 {
+            return Err(Error::new(format!(
+                "expected to cut 3 wires, found {}",
+                cut.edges.len()
+            )));
+        }
+        let [a, b] = cut.sizes(self.graph.node_count());
+        Ok(a * b)
     }
 }
 
-pub fn run(input: &str) -> usize {
-    let graph = input.parse::<AocGraph>().unwrap();
-    println!("{}", graph);
+/// Graphviz graph of the wiring in `input` with its minimum cut marked
+pub fn dot(input: &str) -> Result<String> {
+    let graph = input.parse::<AocGraph>()?;
+    let mut out = Vec::new();
+    graph.write_dot(&mut out, &graph.min_cut()).unwrap();
+    Ok(String::from_utf8(out).unwrap())
+}
+
+pub fn run(input: &str) -> Result<usize> {
+    let graph = input.parse::<AocGraph>()?;
+    debug!("{}", graph);
     graph.run()
 }
 
 #[cfg(test)]
 mod tests {
+    use super::AocGraph;
+    use crate::{day_25::min_cut::MinCutStrategy, partition::Partition};
+
     #[test]
     fn test1() {
         let input = include_str!("example_data.txt");
-        assert_eq!(super::run(input), 54);
+        assert_eq!(super::run(input).unwrap(), 54);
+    }
+
+    #[test]
+    fn test_strategies() {
+        let input = include_str!("example_data.txt");
+        let graph = input.parse::<AocGraph>().unwrap();
+        let expected = graph.min_cut();
+        for strategy in [
+            MinCutStrategy::KargerStein {
+                seed: 25,
+                trials: 5,
+            },
+            MinCutStrategy::EdmondsKarp,
+        ] {
+            let graph = input.parse::<AocGraph>().unwrap().with_strategy(strategy);
+            assert_eq!(graph.min_cut(), expected, "{:?}", strategy);
+            assert_eq!(graph.run().unwrap(), 54);
+        }
+    }
+
+    #[test]
+    fn test_min_cut() {
+        let graph = "a: b c\nb: c\nc: d\nd: e f\ne: f"
+            .parse::<AocGraph>()
+            .unwrap();
+        let cut = graph.min_cut();
+        assert_eq!((cut.edges.len(), cut.sizes(6)), (1, [3, 3]));
+
+        let error = "a: b\nc d".parse::<AocGraph>().err().unwrap();
+        assert_eq!(error.to_string(), "line 2: expected `node: nodes`");
+    }
+
+    #[test]
+    fn test_display() {
+        let input = "a: b c\nb: c\nc: d";
+        let graph = input.parse::<AocGraph>().unwrap();
+        assert_eq!(graph.to_string(), "a: b c\nb: c\nc: d\n");
+        let input = include_str!("example_data.txt");
+        let graph = input.parse::<AocGraph>().unwrap();
+        let again = graph.to_string().parse::<AocGraph>().unwrap();
+        assert_eq!(again.graph.edge_count(), graph.graph.edge_count());
+    }
+
+    #[test]
+    fn test_dot() {
+        let input = include_str!("example_data.txt");
+        let graph = input.parse::<AocGraph>().unwrap();
+        let cut = graph.min_cut();
+        let mut wires = graph.cut_wires(&cut);
+        wires
+            .iter_mut()
+            .for_each(|w| *w = (w.0.min(w.1), w.0.max(w.1)));
+        wires.sort();
+        assert_eq!(wires, [("bvb", "cmg"), ("hfx", "pzl"), ("jqt", "nvd")]);
+        assert!(graph.cut_report(&cut).ends_with("sides 6 x 9 = 54\n"));
+
+        let dot = super::dot(input).unwrap();
+        assert!(dot.starts_with("graph wiring {\n"));
+        assert_eq!(dot.matches("penwidth=3").count(), 3);
+        assert_eq!(dot.matches("lightblue").count(), 6);
+        assert_eq!(dot.matches("lightsalmon").count(), 9);
+        assert_eq!(dot.matches(" -- ").count(), 33);
+    }
+
+    #[test]
+    fn test_csr() {
+        let input = include_str!("example_data.txt");
+        let graph = input.parse::<AocGraph>().unwrap();
+        let csr = graph.csr().unwrap();
+        assert_eq!((csr.node_count(), csr.edge_count()), (15, 33));
+        let mut assignment = vec![1; csr.node_count()];
+        graph
+            .min_cut()
+            .side
+            .iter()
+            .for_each(|n| assignment[n.index()] = 0);
+        let partition = Partition::new(&csr, 2, assignment).unwrap();
+        assert_eq!(partition.edge_cut, 3);
+        assert_eq!(partition.block_sizes().iter().product::<usize>(), 54);
     }
 }
@@ -0,0 +1,409 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque},
+};
+
+use petgraph::graph::{EdgeIndex, NodeIndex, UnGraph};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// Algorithm used to find a minimum cut, every edge counts as one wire
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MinCutStrategy {
+    /// Deterministic, merges the two most tightly wired nodes n - 1 times
+    #[default]
+    StoerWagner,
+    /// Randomized recursive contraction, the best of `trials` runs seeded
+    /// with `seed`, a run finds a minimum cut with probability about
+    /// 1 / log n
+    KargerStein { seed: u64, trials: usize },
+    /// Max flow between the first node and every other one
+    EdmondsKarp,
+}
+
+/// Edges whose removal splits the graph, with the nodes on the side of the
+/// first node
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cut {
+    pub edges: Vec<EdgeIndex>,
+    pub side: Vec<NodeIndex>,
+}
+
+impl Cut {
+    /// Cut between `side` and the rest of the graph
+    fn from_side<N, E>(graph: &UnGraph<N, E>, side: Vec<NodeIndex>) -> Cut {
+        let mut on_side = vec![false; graph.node_count()];
+        side.iter().for_each(|n| on_side[n.index()] = true);
+        if !on_side[0] {
+            on_side.iter_mut().for_each(|x| *x = !*x);
+        }
+        let side = graph
+            .node_indices()
+            .filter(|n| on_side[n.index()])
+            .collect();
+        let edges = graph
+            .edge_indices()
+            .filter(|&e| {
+                let (a, b) = graph.edge_endpoints(e).unwrap();
+                on_side[a.index()] != on_side[b.index()]
+            })
+            .collect();
+        Cut { edges, side }
+    }
+
+    /// Number of nodes on each side of the cut
+    pub fn sizes(&self, node_count: usize) -> [usize; 2] {
+        [self.side.len(), node_count - self.side.len()]
+    }
+}
+
+impl MinCutStrategy {
+    /// Minimum cut of `graph`, which must have at least two nodes
+    pub fn min_cut<N, E>(&self, graph: &UnGraph<N, E>) -> Cut {
+        match *self {
+            MinCutStrategy::StoerWagner => Cut::from_side(graph, stoer_wagner(graph)),
+            MinCutStrategy::KargerStein { seed, trials } => {
+                Cut::from_side(graph, karger_stein(graph, seed, trials))
+            }
+            MinCutStrategy::EdmondsKarp => edmonds_karp(graph),
+        }
+    }
+}
+
+/// Neighbors of every node with the edge leading to them
+struct Adjacency {
+    neighbors: Vec<Vec<(EdgeIndex, usize)>>,
+}
+
+impl Adjacency {
+    fn new<N, E>(graph: &UnGraph<N, E>) -> Adjacency {
+        let mut neighbors = vec![Vec::new(); graph.node_count()];
+        for edge in graph.edge_indices() {
+            let (a, b) = graph.edge_endpoints(edge).unwrap();
+            neighbors[a.index()].push((edge, b.index()));
+            neighbors[b.index()].push((edge, a.index()));
+        }
+        Adjacency { neighbors }
+    }
+
+    /// Nodes reached from the first one over the edges `usable` allows going
+    /// from a node
+    fn reachable(&self, usable: impl Fn(EdgeIndex, usize) -> bool) -> Vec<NodeIndex> {
+        let mut seen = vec![false; self.neighbors.len()];
+        let mut queue = VecDeque::from([0]);
+        seen[0] = true;
+        while let Some(node) = queue.pop_front() {
+            for &(edge, neighbor) in &self.neighbors[node] {
+                if !seen[neighbor] && usable(edge, node) {
+                    seen[neighbor] = true;
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        (0..seen.len())
+            .filter(|&i| seen[i])
+            .map(NodeIndex::new)
+            .collect()
+    }
+}
+
+/// Stoer–Wagner minimum cut, returns the nodes on one side of it
+///
+/// Every phase orders the merged nodes by how strongly they are wired to the
+/// ones before them, the last one is cut off from the rest with the weight it
+/// was added with, then merged into the one before it.
+fn stoer_wagner<N, E>(graph: &UnGraph<N, E>) -> Vec<NodeIndex> {
+    let n = graph.node_count();
+    let mut members = (0..n).map(|i| vec![NodeIndex::new(i)]).collect::<Vec<_>>();
+    let mut weights = vec![HashMap::<usize, usize>::new(); n];
+    for edge in graph.edge_indices() {
+        let (a, b) = graph.edge_endpoints(edge).unwrap();
+        let (a, b) = (a.index(), b.index());
+        *weights[a].entry(b).or_default() += 1;
+        *weights[b].entry(a).or_default() += 1;
+    }
+    let mut active = (0..n).collect::<Vec<_>>();
+    let mut best = (usize::MAX, Vec::new());
+
+    while active.len() > 1 {
+        let mut added = vec![false; n];
+        let mut connection = vec![0; n];
+        let mut heap = BinaryHeap::from([(0, Reverse(active[0]))]);
+        let mut order = Vec::with_capacity(active.len());
+        while let Some((weight, Reverse(node))) = heap.pop() {
+            if added[node] || weight != connection[node] {
+                continue;
+            }
+            added[node] = true;
+            order.push(node);
+            for (&neighbor, &w) in &weights[node] {
+                if !added[neighbor] {
+                    connection[neighbor] += w;
+                    heap.push((connection[neighbor], Reverse(neighbor)));
+                }
+            }
+        }
+        if order.len() < active.len() {
+            // not connected, what was reached is a cut of nothing
+            return order.iter().flat_map(|&i| members[i].clone()).collect();
+        }
+
+        let (s, t) = (order[order.len() - 2], order[order.len() - 1]);
+        if connection[t] < best.0 {
+            best = (connection[t], members[t].clone());
+        }
+
+        let moved = std::mem::take(&mut members[t]);
+        members[s].extend(moved);
+        for (neighbor, w) in std::mem::take(&mut weights[t]) {
+            weights[neighbor].remove(&t);
+            if neighbor != s {
+                *weights[s].entry(neighbor).or_default() += w;
+                *weights[neighbor].entry(s).or_default() += w;
+            }
+        }
+        active.retain(|&i| i != t);
+    }
+    best.1
+}
+
+/// Graph of `nodes` merged nodes, parallel edges are merged into one
+/// weighted edge
+struct Contracted {
+    nodes: usize,
+    edges: Vec<(usize, usize, usize)>,
+}
+
+impl Contracted {
+    /// Merge the ends of random edges until `target` nodes are left, returns
+    /// the new graph and the node each node was merged into
+    ///
+    /// Edges are picked by weight. Edges that already became loops are drawn
+    /// again, so every merge picks among the edges that are left.
+    fn contract(&self, target: usize, rng: &mut StdRng) -> (Contracted, Vec<usize>) {
+        fn root(parent: &mut [usize], mut node: usize) -> usize {
+            while parent[node] != node {
+                parent[node] = parent[parent[node]];
+                node = parent[node];
+            }
+            node
+        }
+        let cumulative = self
+            .edges
+            .iter()
+            .scan(0, |total, &(_, _, w)| {
+                *total += w;
+                Some(*total)
+            })
+            .collect::<Vec<_>>();
+        let total = *cumulative.last().unwrap();
+        let mut parent = (0..self.nodes).collect::<Vec<_>>();
+        let mut nodes = self.nodes;
+        while nodes > target {
+            let pick = rng.gen_range(0..total);
+            let (a, b, _) = self.edges[cumulative.partition_point(|&c| c <= pick)];
+            let (a, b) = (root(&mut parent, a), root(&mut parent, b));
+            if a != b {
+                parent[a] = b;
+                nodes -= 1;
+            }
+        }
+
+        let mut labels = vec![usize::MAX; self.nodes];
+        let mut next = 0;
+        let merged_into = (0..self.nodes)
+            .map(|node| {
+                let label = &mut labels[root(&mut parent, node)];
+                if *label == usize::MAX {
+                    *label = next;
+                    next += 1;
+                }
+                *label
+            })
+            .collect::<Vec<_>>();
+        let mut edges = self
+            .edges
+            .iter()
+            .map(|&(a, b, w)| (merged_into[a], merged_into[b], w))
+            .filter(|(a, b, _)| a != b)
+            .map(|(a, b, w)| (a.min(b), a.max(b), w))
+            .collect::<Vec<_>>();
+        edges.sort_unstable();
+        edges.dedup_by(|next, kept| {
+            let parallel = (next.0, next.1) == (kept.0, kept.1);
+            if parallel {
+                kept.2 += next.2;
+            }
+            parallel
+        });
+        (Contracted { nodes, edges }, merged_into)
+    }
+
+    /// Karger–Stein, contract to about n / √2 nodes twice and recurse into
+    /// both, keeping the smaller cut
+    ///
+    /// Returns the weight of the cut and which nodes are on the side of the
+    /// first one.
+    fn karger_stein(&self, rng: &mut StdRng) -> (usize, Vec<bool>) {
+        let target = if self.nodes <= 6 {
+            2
+        } else {
+            (1.0 + self.nodes as f64 / std::f64::consts::SQRT_2).ceil() as usize
+        };
+        let trial = |rng: &mut StdRng| {
+            let (contracted, merged_into) = self.contract(target, rng);
+            let (weight, side) = match target {
+                2 => (
+                    contracted.edges.iter().map(|e| e.2).sum(),
+                    vec![true, false],
+                ),
+                _ => contracted.karger_stein(rng),
+            };
+            let first = side[merged_into[0]];
+            let side = merged_into.iter().map(|&i| side[i] == first).collect();
+            (weight, side)
+        };
+        if target == 2 {
+            return trial(rng);
+        }
+        let first = trial(rng);
+        let second = trial(rng);
+        if first.0 <= second.0 {
+            first
+        } else {
+            second
+        }
+    }
+}
+
+/// Nodes on one side of the smallest cut found in `trials` runs of
+/// Karger–Stein
+fn karger_stein<N, E>(graph: &UnGraph<N, E>, seed: u64, trials: usize) -> Vec<NodeIndex> {
+    let reachable = Adjacency::new(graph).reachable(|_, _| true);
+    if reachable.len() < graph.node_count() {
+        // not connected, no need to cut anything
+        return reachable;
+    }
+    let mut rng = StdRng::seed_from_u64(seed);
+    let graph = Contracted {
+        nodes: graph.node_count(),
+        edges: graph
+            .edge_indices()
+            .map(|e| {
+                let (a, b) = graph.edge_endpoints(e).unwrap();
+                (a.index(), b.index(), 1)
+            })
+            .collect(),
+    };
+    let (_, side) = (0..trials.max(1))
+        .map(|_| graph.karger_stein(&mut rng))
+        .min_by_key(|(weight, _)| *weight)
+        .unwrap();
+    (0..side.len())
+        .filter(|&i| side[i])
+        .map(NodeIndex::new)
+        .collect()
+}
+
+/// Minimum cut as the smallest max flow from the first node to any other
+///
+/// Every edge carries one unit either way. Flow is pushed along shortest
+/// augmenting paths, once none is left the nodes reachable in the residual
+/// graph are the source side of the cut.
+fn edmonds_karp<N, E>(graph: &UnGraph<N, E>) -> Cut {
+    let adjacency = Adjacency::new(graph);
+    let n = graph.node_count();
+    let mut best: Option<Cut> = None;
+    for sink in 1..n {
+        // flow along each edge from its first to its second endpoint
+        let mut flow = vec![0i8; graph.edge_count()];
+        let from_first =
+            |edge: EdgeIndex, node: usize| graph.edge_endpoints(edge).unwrap().0.index() == node;
+        let residual = |flow: &[i8], edge: EdgeIndex, node: usize| {
+            let f = flow[edge.index()];
+            1 - if from_first(edge, node) { f } else { -f }
+        };
+        loop {
+            let mut came_from = vec![None; n];
+            let mut queue = VecDeque::from([0]);
+            while let Some(node) = queue.pop_front() {
+                if node == sink {
+                    break;
+                }
+                for &(edge, neighbor) in &adjacency.neighbors[node] {
+                    if neighbor != 0
+                        && came_from[neighbor].is_none()
+                        && residual(&flow, edge, node) > 0
+                    {
+                        came_from[neighbor] = Some((edge, node));
+                        queue.push_back(neighbor);
+                    }
+                }
+            }
+            if came_from[sink].is_none() {
+                break;
+            }
+            let mut node = sink;
+            while let Some((edge, previous)) = came_from[node] {
+                flow[edge.index()] += if from_first(edge, previous) { 1 } else { -1 };
+                node = previous;
+            }
+        }
+
+        let side = adjacency.reachable(|edge, node| residual(&flow, edge, node) > 0);
+        let cut = Cut::from_side(graph, side);
+        if best
+            .as_ref()
+            .is_none_or(|b| cut.edges.len() < b.edges.len())
+        {
+            best = Some(cut);
+        }
+    }
+    best.unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use petgraph::graph::UnGraph;
+
+    use super::MinCutStrategy;
+
+    /// Two triangles wired together by one edge, the second one with its
+    /// edges doubled up
+    fn graph() -> UnGraph<(), ()> {
+        UnGraph::from_edges([
+            (0, 1),
+            (1, 2),
+            (2, 0),
+            (2, 3),
+            (3, 4),
+            (4, 5),
+            (5, 3),
+            (3, 4),
+            (4, 5),
+            (5, 3),
+        ])
+    }
+
+    #[test]
+    fn test_strategies() {
+        let graph = graph();
+        for strategy in [
+            MinCutStrategy::StoerWagner,
+            MinCutStrategy::KargerStein {
+                seed: 1,
+                trials: 10,
+            },
+            MinCutStrategy::EdmondsKarp,
+        ] {
+            let cut = strategy.min_cut(&graph);
+            assert_eq!(cut.edges.len(), 1, "{:?}", strategy);
+            assert_eq!(
+                graph.edge_endpoints(cut.edges[0]).unwrap(),
+                (2.into(), 3.into()),
+                "{:?}",
+                strategy
+            );
+            assert_eq!(cut.sizes(graph.node_count()), [3, 3], "{:?}", strategy);
+        }
+    }
+}
@@ -1,7 +1,17 @@
-pub fn day_3() {
-    let input = include_str!("day_3_data.txt");
-    println!("day 3a {}", day_3a(input));
-    println!("day 3b {}", day_3b(input));
+use crate::{
+    error::{at_char, Error, Result},
+    solution::{Answer, Solution},
+};
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    fn part_a(&self, input: &str) -> Result<Answer> {
+        Ok(day_3a(input)?.into())
+    }
+    fn part_b(&self, input: &str) -> Result<Answer> {
+        Ok(day_3b(input)?.into())
+    }
 }
 
 struct Schematic<'a> {
@@ -9,27 +19,10 @@ struct Schematic<'a> {
     width: usize,
 }
 
+const OPS: &str = "*+#$-/=%&@";
+
 fn is_op(c: char) -> bool {
-    match c {
-        '*' => true,
-        '+' => true,
-        '#' => true,
-        '$' => true,
-        '-' => true,
-        '/' => true,
-        '=' => true,
-        '%' => true,
-        '&' => true,
-        '@' => true,
-        '.' => false,
-        other => {
-            if other.is_numeric() {
-                false
-            } else {
-                panic!("Unexpected char {}", c)
-            }
-        }
-    }
+    OPS.contains(c)
 }
 
 enum State {
@@ -50,16 +43,30 @@ impl NumLocation {
 }
 
 impl<'a> Schematic<'a> {
-    fn new(input: &str) -> Schematic {
+    fn new(input: &str) -> Result<Schematic<'_>> {
         let lines = input
             .split("\n")
             .map(|line| line.trim())
             .collect::<Vec<&str>>();
         let width = lines[0].len();
-        for line in &lines {
-            assert_eq!(width, line.len());
+        for (iline, line) in lines.iter().enumerate() {
+            if line.len() != width {
+                return Err(Error::new(format!(
+                    "expected {} columns, found {}",
+                    width,
+                    line.len()
+                ))
+                .at_line(iline + 1));
+            }
+            if let Some((pos, c)) = line
+                .chars()
+                .enumerate()
+                .find(|(_, c)| !(c.is_ascii_digit() || *c == '.' || is_op(*c)))
+            {
+                return Err(at_char(pos, format!("unexpected char `{}`", c)).at_line(iline + 1));
+            }
         }
-        Schematic { lines, width }
+        Ok(Schematic { lines, width })
     }
 
     fn extract_part(&self, loc: &NumLocation) -> u32 {
@@ -126,11 +133,8 @@ impl<'a> Schematic<'a> {
                     }
                 }
             }
-            match state {
-                State::Found => {
-                    output.push(NumLocation::new(iline, start, line.len()));
-                }
-                _ => (),
+            if let State::Found = state {
+                output.push(NumLocation::new(iline, start, line.len()));
             }
         }
         output
@@ -142,9 +146,9 @@ impl<'a> Schematic<'a> {
             .collect::<Vec<NumLocation>>()
     }
 
-    fn get_gear_ratio(&self, iline: usize, pos: usize, parts: &Vec<NumLocation>) -> Option<u32> {
+    fn get_gear_ratio(&self, iline: usize, pos: usize, parts: &[NumLocation]) -> Option<u32> {
         let parts = parts
-            .into_iter()
+            .iter()
             .filter(|loc| loc.iline == iline || loc.iline == iline - 1 || loc.iline == iline + 1)
             .filter(|loc| pos <= loc.end && pos as i64 >= loc.start as i64 - 1)
             .collect::<Vec<&NumLocation>>();
@@ -167,11 +171,8 @@ impl<'a> Schematic<'a> {
         for (iline, line) in self.lines.iter().enumerate() {
             for (pos, c) in line.chars().enumerate() {
                 if c == '*' {
-                    match self.get_gear_ratio(iline, pos, &parts) {
-                        Some(ratio) => {
-                            sum += ratio;
-                        }
-                        None => (),
+                    if let Some(ratio) = self.get_gear_ratio(iline, pos, &parts) {
+                        sum += ratio;
                     }
                 }
             }
@@ -180,14 +181,14 @@ impl<'a> Schematic<'a> {
     }
 }
 
-fn day_3a(input: &str) -> u32 {
-    let schematic = Schematic::new(input);
+fn day_3a(input: &str) -> Result<u32> {
+    let schematic = Schematic::new(input)?;
     let parts = schematic.find_parts();
-    parts.iter().map(|loc| schematic.extract_part(loc)).sum()
+    Ok(parts.iter().map(|loc| schematic.extract_part(loc)).sum())
 }
 
-fn day_3b(input: &str) -> u32 {
-    Schematic::new(input).sum_gear_ratios()
+fn day_3b(input: &str) -> Result<u32> {
+    Ok(Schematic::new(input)?.sum_gear_ratios())
 }
 
 #[cfg(test)]
@@ -204,7 +205,13 @@ mod tests {
 ......755.
 ...$.*....
 .664.598.."#;
-        assert_eq!(super::day_3a(input), 4361);
-        assert_eq!(super::day_3b(input), 467835);
+        assert_eq!(super::day_3a(input).unwrap(), 4361);
+        assert_eq!(super::day_3b(input).unwrap(), 467835);
+    }
+
+    #[test]
+    fn test_bad_char() {
+        let error = super::day_3a("467..\n..!..").unwrap_err();
+        assert_eq!(error.to_string(), "line 2, column 3: unexpected char `!`");
     }
 }
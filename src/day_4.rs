@@ -1,13 +1,23 @@
-pub fn day_4() {
-    let input = include_str!("day_4_data.txt");
-    println!("day 4a {}", day_4a(input));
-    println!("day 4b {}", day_4b(input));
+use crate::{
+    error::Result,
+    solution::{Answer, Solution},
+};
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    fn part_a(&self, input: &str) -> Result<Answer> {
+        Ok(day_4a(input)?.into())
+    }
+    fn part_b(&self, input: &str) -> Result<Answer> {
+        Ok(day_4b(input)?.into())
+    }
 }
 
-fn process_4a_line(line: &str) -> u32 {
-    let (winners, nums) = line.split_once(" | ").unwrap();
+fn process_4a_line(line: &str) -> Result<u32> {
+    let (winners, nums) = line.split_once(" | ").ok_or("expected ` | `")?;
     let mut set = std::collections::HashSet::new();
-    let (_, winners) = winners.split_once(": ").unwrap();
+    let (_, winners) = winners.split_once(": ").ok_or("expected `: `")?;
     for winner in winners.split_whitespace() {
         let winner = winner.trim();
         set.insert(winner);
@@ -23,17 +33,21 @@ fn process_4a_line(line: &str) -> u32 {
             }
         }
     }
-    score
+    Ok(score)
 }
 
-fn day_4a(input: &str) -> u32 {
-    input.split("\n").map(|line| process_4a_line(line)).sum()
+fn day_4a(input: &str) -> Result<u32> {
+    input
+        .split("\n")
+        .enumerate()
+        .map(|(iline, line)| process_4a_line(line).map_err(|e| e.at_line(iline + 1)))
+        .sum()
 }
 
-fn process_4b_line(line: &str) -> u32 {
-    let (winners, nums) = line.split_once(" | ").unwrap();
+fn process_4b_line(line: &str) -> Result<u32> {
+    let (winners, nums) = line.split_once(" | ").ok_or("expected ` | `")?;
     let mut set = std::collections::HashSet::new();
-    let (_, winners) = winners.split_once(": ").unwrap();
+    let (_, winners) = winners.split_once(": ").ok_or("expected `: `")?;
     for winner in winners.split_whitespace() {
         let winner = winner.trim();
         set.insert(winner);
@@ -45,22 +59,22 @@ fn process_4b_line(line: &str) -> u32 {
             matches += 1;
         }
     }
-    matches
+    Ok(matches)
 }
 
-fn day_4b(input: &str) -> u32 {
+fn day_4b(input: &str) -> Result<u32> {
     let mut card_count = counter::Counter::new();
     let mut sum = 0_u32;
     for (iline, line) in input.lines().enumerate() {
         card_count[&iline] += 1;
-        let matches = process_4b_line(line);
+        let matches = process_4b_line(line).map_err(|e| e.at_line(iline + 1))?;
         let multiplier: u32 = card_count[&iline];
         for i in 1..matches + 1 {
             card_count[&(iline + i as usize)] += multiplier;
         }
         sum += card_count[&iline];
     }
-    sum
+    Ok(sum)
 }
 
 #[cfg(test)]
@@ -73,7 +87,13 @@ Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
 Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
 Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
 Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11"#;
-        assert_eq!(super::day_4a(input), 13);
-        assert_eq!(super::day_4b(input), 30);
+        assert_eq!(super::day_4a(input).unwrap(), 13);
+        assert_eq!(super::day_4b(input).unwrap(), 30);
+        assert_eq!(
+            super::day_4a("Card 1: 41 | 41\nCard 2 41 | 41")
+                .unwrap_err()
+                .to_string(),
+            "line 2: expected `: `"
+        );
     }
 }
@@ -1,20 +1,33 @@
 use std::collections::{BTreeSet, HashMap};
 
-pub fn day_5() {
-    let input = include_str!("day_5_data.txt");
-    println!("day 5a {}", day_5a(input));
-    println!("day 5b {}", day_5b(input));
+use crate::{
+    error::{parse_token, Error, Result},
+    solution::{Answer, Solution},
+};
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    fn part_a(&self, input: &str) -> Result<Answer> {
+        Ok(day_5a(input)?.into())
+    }
+    fn part_b(&self, input: &str) -> Result<Answer> {
+        Ok(day_5b(input)?.into())
+    }
 }
 
-fn find_maps(input: &str) -> Vec<Vec<&str>> {
+/// Split the input into blocks of lines separated by blank lines, each with
+/// the number of lines before it
+fn find_maps(input: &str) -> Vec<(usize, Vec<&str>)> {
     let mut out = Vec::new();
     let mut map = Vec::new();
+    let mut start = 0;
     let mut in_map = false;
-    for line in input.lines() {
+    for (iline, line) in input.lines().enumerate() {
         match in_map {
             true => {
                 if line.trim().is_empty() {
-                    out.push(map.clone());
+                    out.push((start, map.clone()));
                     map.clear();
                     in_map = false;
                 } else {
@@ -24,39 +37,51 @@ fn find_maps(input: &str) -> Vec<Vec<&str>> {
             false => {
                 if !line.trim().is_empty() {
                     in_map = true;
+                    start = iline;
                     map.push(line);
                 }
             }
         }
     }
     if in_map {
-        out.push(map.clone());
+        out.push((start, map.clone()));
     }
     out
 }
 
+/// Parse the numbers after the `:` of a `seeds: 1 2 3` line
+fn parse_seed_line(line: &str) -> Result<Vec<u64>> {
+    let (_, nums) = line
+        .split_once(": ")
+        .ok_or_else(|| Error::new("expected `seeds: `").at_line(1))?;
+    let nums = nums
+        .split_whitespace()
+        .map(|num| parse_token::<u64>(line, num).map_err(|e| e.at_line(1)))
+        .collect::<Result<Vec<_>>>()?;
+    if nums.is_empty() {
+        return Err(Error::new("expected at least one seed").at_line(1));
+    }
+    Ok(nums)
+}
+
 #[derive(Debug)]
 struct SeedRange {
     start: u64,
     range: u64,
 }
 impl SeedRange {
-    fn make_vec(map: &Vec<&str>) -> Vec<SeedRange> {
-        assert_eq!(map.len(), 1);
-        let line = map[0];
-        let (_, line) = line.split_once(": ").unwrap();
-        let nums = line
-            .split_whitespace()
-            .map(|s| s.parse::<u64>().unwrap())
-            .collect::<Vec<u64>>();
-        assert_eq!(nums.len() % 2, 0);
+    fn make_vec(map: &[&str]) -> Result<Vec<SeedRange>> {
+        let nums = parse_seed_line(map[0])?;
+        if nums.len() % 2 != 0 {
+            return Err(Error::new("expected pairs of seed start and range").at_line(1));
+        }
         let mut seeds = Vec::new();
         for i in 0..nums.len() / 2 {
             let start = nums[i * 2];
             let range = nums[i * 2 + 1];
             seeds.push(SeedRange { start, range });
         }
-        seeds
+        Ok(seeds)
     }
 }
 
@@ -66,15 +91,9 @@ struct Seeds {
 }
 
 impl Seeds {
-    fn new(map: &Vec<&str>) -> Seeds {
-        assert_eq!(map.len(), 1);
-        let line = map[0];
-        let (_, line) = line.split_once(": ").unwrap();
-        let mut seeds = Vec::new();
-        for num in line.split_whitespace() {
-            seeds.push(num.parse::<u64>().unwrap());
-        }
-        Seeds { seeds }
+    fn new(map: &[&str]) -> Result<Seeds> {
+        let seeds = parse_seed_line(map[0])?;
+        Ok(Seeds { seeds })
     }
 }
 
@@ -86,32 +105,37 @@ struct RangeMap {
 }
 
 impl RangeMap {
-    fn new(line: &str) -> RangeMap {
+    fn new(line: &str) -> Result<RangeMap> {
         let nums = line.split_whitespace().collect::<Vec<&str>>();
-        assert_eq!(nums.len(), 3);
-        let dest_start = nums[0].parse::<u64>().unwrap();
-        let source_start = nums[1].parse::<u64>().unwrap();
-        let range = nums[2].parse::<u64>().unwrap();
-        RangeMap {
+        if nums.len() != 3 {
+            return Err(Error::new(format!(
+                "expected 3 numbers, found {}",
+                nums.len()
+            )));
+        }
+        let dest_start = parse_token::<u64>(line, nums[0])?;
+        let source_start = parse_token::<u64>(line, nums[1])?;
+        let range = parse_token::<u64>(line, nums[2])?;
+        Ok(RangeMap {
             dest_start,
             source_start,
             range,
-        }
+        })
     }
     fn get_offset(&self) -> i64 {
-        return self.dest_start as i64 - self.source_start as i64;
+        self.dest_start as i64 - self.source_start as i64
     }
     fn contains_key(&self, key: &u64) -> bool {
         if key < &self.source_start {
             return false;
         }
-        key - self.source_start <= self.range - 1
+        key - self.source_start < self.range
     }
     fn contains_dest(&self, dest: &u64) -> bool {
         if dest < &self.dest_start {
             return false;
         }
-        dest - self.dest_start <= self.range - 1
+        dest - self.dest_start < self.range
     }
     fn lookup(&self, key: &u64) -> u64 {
         assert!(self.contains_key(key));
@@ -134,19 +158,23 @@ struct Map {
 }
 
 impl Map {
-    fn new(map: &Vec<&str>) -> Map {
-        assert!(map.len() > 1);
-        let (line, _) = map[0].split_once(" map").unwrap();
-        let (input, output) = line.split_once("-to-").unwrap();
+    fn new(map: &[&str]) -> Result<Map> {
+        let (line, _) = map[0]
+            .split_once(" map")
+            .ok_or_else(|| Error::new("expected `A-to-B map:`").at_line(1))?;
+        let (input, output) = line
+            .split_once("-to-")
+            .ok_or_else(|| Error::new("expected `A-to-B map:`").at_line(1))?;
         let maps = map[1..]
             .iter()
-            .map(|line| RangeMap::new(line))
-            .collect::<Vec<RangeMap>>();
-        Map {
+            .enumerate()
+            .map(|(iline, line)| RangeMap::new(line).map_err(|e| e.at_line(iline + 2)))
+            .collect::<Result<Vec<RangeMap>>>()?;
+        Ok(Map {
             input: input.to_string(),
             output: output.to_string(),
-            maps: maps,
-        }
+            maps,
+        })
     }
 
     fn lookup(&self, key: &u64) -> u64 {
@@ -198,19 +226,12 @@ struct Plan {
 }
 
 impl Plan {
-    fn new(input: &str) -> Plan {
+    fn new(input: &str) -> Result<Plan> {
         let maps = find_maps(input);
-        assert!(is_seeds(&maps[0]));
-        let seeds = Seeds::new(&maps[0]);
-        let maps_vec = maps[1..]
-            .iter()
-            .map(|map| Map::new(map))
-            .collect::<Vec<Map>>();
-        let mut maps = HashMap::new();
-        for map in maps_vec {
-            maps.insert(map.input.clone(), map.clone());
-        }
-        Plan { seeds, maps }
+        let (offset, seeds) = first_seeds(&maps)?;
+        let seeds = Seeds::new(seeds).map_err(|e| e.offset_lines(offset))?;
+        let maps = index_maps(&maps[1..], "seed", "location")?;
+        Ok(Plan { seeds, maps })
     }
 
     fn map_seeds(&self, dest: &String) -> Vec<u64> {
@@ -272,7 +293,7 @@ fn find_last_2_keys(maps: &HashMap<String, Map>, start: &str, dest: &str) -> (St
 }
 
 fn pop_last_2_maps(maps: &mut HashMap<String, Map>, start: &str, dest: &str) -> (Map, Map) {
-    let (key_n, key_n_minus_one) = find_last_2_keys(&maps, start, dest);
+    let (key_n, key_n_minus_one) = find_last_2_keys(maps, start, dest);
     let map_n_minus_one = maps.remove(&key_n_minus_one).unwrap();
     let map_n = maps.remove(&key_n).unwrap();
     (map_n_minus_one, map_n)
@@ -293,21 +314,14 @@ struct PlanB {
 }
 
 impl PlanB {
-    fn new(input: &str, source: &str, dest: &str) -> PlanB {
+    fn new(input: &str, source: &str, dest: &str) -> Result<PlanB> {
         let maps = find_maps(input);
-        assert!(is_seeds(&maps[0]));
-        let seeds = SeedRange::make_vec(&maps[0]);
-        let maps_vec = maps[1..]
-            .iter()
-            .map(|map| Map::new(map))
-            .collect::<Vec<Map>>();
-        let mut maps = HashMap::new();
-        for map in maps_vec {
-            maps.insert(map.input.clone(), map.clone());
-        }
+        let (offset, seeds) = first_seeds(&maps)?;
+        let seeds = SeedRange::make_vec(seeds).map_err(|e| e.offset_lines(offset))?;
+        let mut maps = index_maps(&maps[1..], source, dest)?;
         flatten_map(&mut maps, source, dest);
         let map = maps.remove(source).unwrap();
-        PlanB { seeds, map }
+        Ok(PlanB { seeds, map })
     }
 
     fn find_min_location_from_seed_range(&self, seed_range: &SeedRange) -> u64 {
@@ -333,24 +347,69 @@ impl PlanB {
             .iter()
             .map(|r| self.find_min_location_from_seed_range(r))
             .min()
-            .unwrap()
+            .expect("seeds are checked when parsing")
     }
 }
 
-fn is_seeds(map: &Vec<&str>) -> bool {
-    return map.len() == 1;
+fn is_seeds(map: &[&str]) -> bool {
+    map.len() == 1
+}
+
+/// The seeds block that must come first, with its line offset
+fn first_seeds<'a, 'b>(maps: &'a [(usize, Vec<&'b str>)]) -> Result<(usize, &'a Vec<&'b str>)> {
+    match maps.first() {
+        Some((offset, map)) if is_seeds(map) => Ok((*offset, map)),
+        Some((offset, _)) => Err(Error::new("expected a single seeds line").at_line(offset + 1)),
+        None => Err(Error::new("empty input")),
+    }
+}
+
+fn parse_maps(maps: &[(usize, Vec<&str>)]) -> Result<Vec<Map>> {
+    maps.iter()
+        .map(|(offset, map)| Map::new(map).map_err(|e| e.offset_lines(*offset)))
+        .collect()
+}
+
+/// Parse the map blocks keyed by their input, keeping only those leading
+/// from `source` to `dest` so following them can't fail
+fn index_maps(
+    blocks: &[(usize, Vec<&str>)],
+    source: &str,
+    dest: &str,
+) -> Result<HashMap<String, Map>> {
+    let mut maps = HashMap::new();
+    // header line of the map giving each output, to point errors at
+    let mut lines = HashMap::new();
+    for (map, (offset, _)) in parse_maps(blocks)?.into_iter().zip(blocks) {
+        lines.insert(map.output.clone(), offset + 1);
+        maps.insert(map.input.clone(), map);
+    }
+    let mut chain = HashMap::new();
+    let mut key = source.to_string();
+    while key != dest {
+        let Some(map) = maps.remove(&key) else {
+            let error = Error::new(format!("expected a map from `{}`", key));
+            return Err(match lines.get(&key) {
+                Some(line) => error.at_line(*line),
+                None => error,
+            });
+        };
+        key = map.output.clone();
+        chain.insert(map.input.clone(), map);
+    }
+    Ok(chain)
 }
 
-fn day_5a(input: &str) -> u64 {
-    *Plan::new(input)
+fn day_5a(input: &str) -> Result<u64> {
+    Ok(*Plan::new(input)?
         .map_seeds(&"location".to_string())
         .iter()
         .min()
-        .unwrap()
+        .expect("seeds are checked when parsing"))
 }
 
-fn day_5b(input: &str) -> u64 {
-    PlanB::new(input, "seed", "location").find_min_location()
+fn day_5b(input: &str) -> Result<u64> {
+    Ok(PlanB::new(input, "seed", "location")?.find_min_location())
 }
 
 #[cfg(test)]
@@ -390,7 +449,23 @@ temperature-to-humidity map:
 humidity-to-location map:
 60 56 37
 56 93 4"#;
-        assert_eq!(super::day_5a(input), 35);
-        assert_eq!(super::day_5b(input), 46);
+        assert_eq!(super::day_5a(input).unwrap(), 35);
+        assert_eq!(super::day_5b(input).unwrap(), 46);
+        let input = input.replace("52 50 48", "52 50");
+        assert_eq!(
+            super::day_5a(&input).unwrap_err().to_string(),
+            "line 5: expected 3 numbers, found 2"
+        );
+        let input = input.replace("52 50", "52 50 48");
+        let error = input.replace("water-to-light", "watery-to-light");
+        assert_eq!(
+            super::day_5a(&error).unwrap_err().to_string(),
+            "line 12: expected a map from `water`"
+        );
+        let error = input.replace("79 14 55 13", "");
+        assert_eq!(
+            super::day_5b(&error).unwrap_err().to_string(),
+            "line 1: expected at least one seed"
+        );
     }
 }
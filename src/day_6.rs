@@ -1,9 +1,19 @@
 use std::str::FromStr;
 
-pub fn day_6() {
-    let input = include_str!("day_6_data.txt");
-    println!("day 6 a {}", day_6_a(input));
-    println!("day 6 b {}", day_6_b(input));
+use crate::{
+    error::{parse_token, Error, Result},
+    solution::{Answer, Solution},
+};
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    fn part_a(&self, input: &str) -> Result<Answer> {
+        Ok(day_6_a(input)?.into())
+    }
+    fn part_b(&self, input: &str) -> Result<Answer> {
+        Ok(day_6_b(input)?.into())
+    }
 }
 
 #[derive(Debug)]
@@ -38,29 +48,48 @@ struct RaceHistory {
     races: Vec<Race>,
 }
 
-#[derive(Debug, PartialEq, Eq)]
-struct ParseRaceHistoryError;
-
-impl FromStr for RaceHistory {
-    type Err = ParseRaceHistoryError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let lines = s.lines().collect::<Vec<&str>>();
-        assert_eq!(lines.len(), 2);
-
-        let (_, times) = lines[0].split_once("Time:").unwrap();
-        let times = times
-            .split_whitespace()
-            .map(|s| s.parse::<u64>().unwrap())
-            .collect::<Vec<u64>>();
+/// Split the two input lines and strip their `Time:` and `Distance:` labels
+fn split_labels(s: &str) -> Result<[(&str, &str); 2]> {
+    let lines = s.lines().collect::<Vec<&str>>();
+    if lines.len() != 2 {
+        return Err(Error::new(format!(
+            "expected 2 lines, found {}",
+            lines.len()
+        )));
+    }
+    let mut out = [("", ""); 2];
+    for (iline, label) in ["Time:", "Distance:"].iter().enumerate() {
+        let line = lines[iline];
+        let (_, rest) = line
+            .split_once(label)
+            .ok_or_else(|| Error::new(format!("expected `{}`", label)).at_line(iline + 1))?;
+        out[iline] = (line, rest);
+    }
+    Ok(out)
+}
 
-        let (_, distances) = lines[1].split_once("Distance:").unwrap();
-        let distances = distances
-            .split_whitespace()
-            .map(|s| s.parse::<u64>().unwrap())
-            .collect::<Vec<u64>>();
+/// Parse the numbers on one line of the input, counting lines from 0
+fn parse_numbers(iline: usize, (line, nums): (&str, &str)) -> Result<Vec<u64>> {
+    nums.split_whitespace()
+        .map(|s| parse_token::<u64>(line, s).map_err(|e| e.at_line(iline + 1)))
+        .collect()
+}
 
-        assert_eq!(times.len(), distances.len());
+impl FromStr for RaceHistory {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let [times, distances] = split_labels(s)?;
+        let times = parse_numbers(0, times)?;
+        let distances = parse_numbers(1, distances)?;
+
+        if times.len() != distances.len() {
+            return Err(Error::new(format!(
+                "found {} times but {} distances",
+                times.len(),
+                distances.len()
+            )));
+        }
 
         let races = times
             .iter()
@@ -89,31 +118,21 @@ struct RaceHistoryB {
     race: Race,
 }
 
-#[derive(Debug, PartialEq, Eq)]
-struct ParseRaceHistoryBError;
-
 impl FromStr for RaceHistoryB {
-    type Err = ParseRaceHistoryBError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let lines = s.lines().collect::<Vec<&str>>();
-        assert_eq!(lines.len(), 2);
-
-        let (_, time) = lines[0].split_once("Time:").unwrap();
-        let time = time
-            .chars()
-            .filter(|c| c.is_numeric())
-            .collect::<String>()
-            .parse::<u64>()
-            .unwrap();
-
-        let (_, distance) = lines[1].split_once("Distance:").unwrap();
-        let distance = distance
-            .chars()
-            .filter(|c| c.is_numeric())
-            .collect::<String>()
-            .parse::<u64>()
-            .unwrap();
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let [time, distance] = split_labels(s)?;
+        // the spaces between numbers are bad kerning, join them into one
+        let joined = [time, distance]
+            .iter()
+            .enumerate()
+            .map(|(iline, line)| {
+                let digits = line.1.split_whitespace().collect::<String>();
+                parse_token::<u64>(&digits, &digits).map_err(|e| e.at_line(iline + 1))
+            })
+            .collect::<Result<Vec<u64>>>()?;
+        let (time, distance) = (joined[0], joined[1]);
 
         let race = Race { time, distance };
         Ok(RaceHistoryB { race })
@@ -126,15 +145,12 @@ impl RaceHistoryB {
     }
 }
 
-fn day_6_a(input: &str) -> u64 {
-    input
-        .parse::<RaceHistory>()
-        .expect("Failed to parse RaceHistory")
-        .num_ways_to_win()
+fn day_6_a(input: &str) -> Result<u64> {
+    Ok(input.parse::<RaceHistory>()?.num_ways_to_win())
 }
 
-fn day_6_b(input: &str) -> u64 {
-    input.parse::<RaceHistoryB>().unwrap().num_ways_to_win()
+fn day_6_b(input: &str) -> Result<u64> {
+    Ok(input.parse::<RaceHistoryB>()?.num_ways_to_win())
 }
 
 #[cfg(test)]
@@ -143,7 +159,13 @@ mod tests {
     fn test1() {
         let input = r#"Time:      7  15   30
 Distance:  9  40  200"#;
-        assert_eq!(super::day_6_a(input), 288);
-        assert_eq!(super::day_6_b(input), 71503);
+        assert_eq!(super::day_6_a(input).unwrap(), 288);
+        assert_eq!(super::day_6_b(input).unwrap(), 71503);
+        assert_eq!(
+            super::day_6_a("Time: 7 15\nDistance: 9 x")
+                .unwrap_err()
+                .to_string(),
+            "line 2, column 13: could not parse `x`"
+        );
     }
 }
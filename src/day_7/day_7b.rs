@@ -3,6 +3,8 @@ use std::{convert::TryFrom, str::FromStr};
 
 use counter::Counter;
 
+use crate::error::{parse_chars, parse_token, Error, Result};
+
 #[derive(Hash, Debug, Eq, PartialEq, PartialOrd, Ord)]
 enum Card {
     J,
@@ -20,13 +22,10 @@ enum Card {
     A,
 }
 
-#[derive(Debug, PartialEq, Eq)]
-struct ParseCardError;
-
 impl TryFrom<char> for Card {
-    type Error = ParseCardError;
+    type Error = Error;
 
-    fn try_from(c: char) -> Result<Self, Self::Error> {
+    fn try_from(c: char) -> Result<Self> {
         match c {
             'A' => Ok(Card::A),
             'K' => Ok(Card::K),
@@ -41,7 +40,7 @@ impl TryFrom<char> for Card {
             '4' => Ok(Card::C4),
             '3' => Ok(Card::C3),
             '2' => Ok(Card::C2),
-            _ => Err(ParseCardError),
+            _ => Err(Error::new(format!("bad card `{}`", c))),
         }
     }
 }
@@ -57,13 +56,10 @@ enum HandType {
     FiveOAK,
 }
 
-#[derive(Debug, PartialEq, Eq)]
-struct ParseHandTypeError;
-
 impl TryFrom<Hand> for HandType {
-    type Error = ParseHandTypeError;
+    type Error = Error;
 
-    fn try_from(hand: Hand) -> Result<Self, Self::Error> {
+    fn try_from(hand: Hand) -> Result<Self> {
         hand.determine_hand_type()
     }
 }
@@ -74,9 +70,9 @@ struct Hand {
 }
 
 impl Hand {
-    fn determine_hand_type(&self) -> Result<HandType, ParseHandTypeError> {
+    fn determine_hand_type(&self) -> Result<HandType> {
         if self.cards.len() != 5 {
-            return Err(ParseHandTypeError);
+            return Err(Error::new("expected 5 cards"));
         }
         let non_j_cards = self
             .cards
@@ -92,7 +88,7 @@ impl Hand {
             .map(|(_card, count)| count)
             .collect::<Vec<_>>();
         let num_j = 5 - non_j_cards.len();
-        if signature.len() == 0 {
+        if signature.is_empty() {
             assert_eq!(num_j, 5);
             signature.push(num_j);
         } else {
@@ -104,40 +100,49 @@ impl Hand {
             2 => match signature.iter().collect_tuple().unwrap() {
                 (4, 1) => Ok(HandType::FourOAK),
                 (3, 2) => Ok(HandType::FullHouse),
-                _ => Err(ParseHandTypeError),
+                _ => Err(Error::new(format!(
+                    "could not determine hand type for {:?}",
+                    self
+                ))),
             },
             3 => match signature.iter().collect_tuple().unwrap() {
                 (3, 1, 1) => Ok(HandType::ThreeOAK),
                 (2, 2, 1) => Ok(HandType::TwoPair),
-                _ => Err(ParseHandTypeError),
+                _ => Err(Error::new(format!(
+                    "could not determine hand type for {:?}",
+                    self
+                ))),
             },
             4 => match signature.iter().collect_tuple().unwrap() {
                 (2, 1, 1, 1) => Ok(HandType::OnePair),
-                _ => Err(ParseHandTypeError),
+                _ => Err(Error::new(format!(
+                    "could not determine hand type for {:?}",
+                    self
+                ))),
             },
             5 => match signature.iter().collect_tuple().unwrap() {
                 (1, 1, 1, 1, 1) => Ok(HandType::HighCard),
-                _ => Err(ParseHandTypeError),
+                _ => Err(Error::new(format!(
+                    "could not determine hand type for {:?}",
+                    self
+                ))),
             },
-            _ => Err(ParseHandTypeError),
+            _ => Err(Error::new(format!(
+                "could not determine hand type for {:?}",
+                self
+            ))),
         }
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
-struct ParseHandError;
-
 impl FromStr for Hand {
-    type Err = ParseHandError;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
         if s.len() != 5 {
-            return Err(ParseHandError);
+            return Err(Error::new(format!("expected 5 cards, found `{}`", s)));
         }
         Ok(Hand {
-            cards: s
-                .chars()
-                .map(|c| c.try_into().unwrap())
-                .collect::<Vec<Card>>(),
+            cards: parse_chars::<Card>(s)?,
         })
     }
 }
@@ -152,29 +157,23 @@ struct Game {
     hands: Vec<(HandType, Hand, Bid)>,
 }
 
-#[derive(Debug, PartialEq, Eq)]
-struct ParseGameError;
-
 impl FromStr for Game {
-    type Err = ParseGameError;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
         let mut hands = s
             .lines()
-            .map(|line| {
-                let (hand, bid) = line.split_once(" ").unwrap();
-                let hand = hand.parse::<Hand>().unwrap();
-                let hand_type = hand
-                    .determine_hand_type()
-                    .expect(format!("failed to determine hand type for {:?}", hand).as_str());
-                (
-                    hand_type,
-                    hand,
-                    Bid {
-                        amount: bid.parse::<u64>().unwrap(),
-                    },
-                )
+            .enumerate()
+            .map(|(iline, line)| {
+                let parse_line = || {
+                    let (hand, bid) = line.split_once(' ').ok_or("expected a hand and a bid")?;
+                    let hand = hand.parse::<Hand>()?;
+                    let hand_type = hand.determine_hand_type()?;
+                    let amount = parse_token::<u64>(line, bid)?;
+                    Ok((hand_type, hand, Bid { amount }))
+                };
+                parse_line().map_err(|e: Error| e.at_line(iline + 1))
             })
-            .collect::<Vec<_>>();
+            .collect::<Result<Vec<_>>>()?;
         hands.sort();
         Ok(Game { hands })
     }
@@ -190,9 +189,9 @@ impl Game {
     }
 }
 
-pub fn day_7_b(input: &str) -> u64 {
-    let game = input.parse::<Game>().unwrap();
-    game.total_winnings()
+pub fn day_7_b(input: &str) -> Result<u64> {
+    let game = input.parse::<Game>()?;
+    Ok(game.total_winnings())
 }
 
 #[cfg(test)]
@@ -206,7 +205,7 @@ T55J5 684
 KK677 28
 KTJJT 220
 QQQJA 483"#;
-        assert_eq!(super::day_7_b(input), 5905);
+        assert_eq!(super::day_7_b(input).unwrap(), 5905);
     }
 
     #[test]
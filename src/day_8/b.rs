@@ -1,5 +1,10 @@
 use std::{collections::HashMap, str::FromStr};
 
+use crate::{
+    debug,
+    error::{parse_chars, Error, Result},
+};
+
 #[derive(Debug)]
 enum Direction {
     L,
@@ -7,12 +12,12 @@ enum Direction {
 }
 
 impl TryFrom<char> for Direction {
-    type Error = &'static str;
-    fn try_from(c: char) -> Result<Self, <Self as TryFrom<char>>::Error> {
+    type Error = Error;
+    fn try_from(c: char) -> Result<Self> {
         match c {
             'L' => Ok(Direction::L),
             'R' => Ok(Direction::R),
-            _ => Err("Got bad direction char"),
+            _ => Err(Error::new(format!("bad direction `{}`", c))),
         }
     }
 }
@@ -57,7 +62,7 @@ impl<'a> History<'a> {
         let latest_node = *self.nodes.last().unwrap();
         let ret = old_node == latest_node;
         if ret {
-            println!(
+            debug!(
                 "history match on {} and {} comparing last to pos {}",
                 old_node, latest_node, pos
             );
@@ -95,10 +100,10 @@ impl Historian {
 }
 
 impl<'a> TryFrom<History<'a>> for Historian {
-    type Error = &'static str;
-    fn try_from(hist: History<'a>) -> Result<Self, <Self as TryFrom<History<'a>>>::Error> {
+    type Error = Error;
+    fn try_from(hist: History<'a>) -> Result<Self> {
         if !hist.check() {
-            Err("Incomplete history")
+            Err(Error::new("Incomplete history"))
         } else {
             Ok(Historian::new(hist))
         }
@@ -169,30 +174,32 @@ impl Map {
 }
 
 impl FromStr for Map {
-    type Err = &'static str;
+    type Err = Error;
 
-    fn from_str(val: &str) -> Result<Map, <Self as FromStr>::Err> {
-        let instructions = val
-            .lines()
-            .next()
-            .unwrap()
-            .chars()
-            .map(|c| Direction::try_from(c).unwrap())
-            .collect::<Vec<_>>();
+    fn from_str(val: &str) -> Result<Map> {
+        let instructions = val.lines().next().ok_or("empty input")?;
+        let instructions = parse_chars::<Direction>(instructions).map_err(|e| e.at_line(1))?;
 
         let nodes = val
             .lines()
+            .enumerate()
             .skip(2)
-            .map(|line| {
-                let (input, output) = line.split_once(" = ").unwrap();
-                let output = &output[1..output.len() - 1];
-                let (left_output, right_output) = output.split_once(", ").unwrap();
-                (
-                    input.to_string(),
-                    (left_output.to_string(), right_output.to_string()),
-                )
+            .map(|(iline, line)| {
+                let parse_line = || {
+                    let (input, output) = line.split_once(" = ").ok_or("expected ` = `")?;
+                    let (left_output, right_output) = output
+                        .strip_prefix('(')
+                        .and_then(|output| output.strip_suffix(')'))
+                        .and_then(|output| output.split_once(", "))
+                        .ok_or("expected `(LEFT, RIGHT)`")?;
+                    Ok((
+                        input.to_string(),
+                        (left_output.to_string(), right_output.to_string()),
+                    ))
+                };
+                parse_line().map_err(|e: Error| e.at_line(iline + 1))
             })
-            .collect::<HashMap<_, _>>();
+            .collect::<Result<HashMap<_, _>>>()?;
 
         Ok(Map {
             instructions,
@@ -201,8 +208,8 @@ impl FromStr for Map {
     }
 }
 
-pub fn run(input: &str) -> u64 {
-    input.parse::<Map>().unwrap().count_steps_2()
+pub fn run(input: &str) -> Result<u64> {
+    Ok(input.parse::<Map>()?.count_steps_2())
 }
 
 #[cfg(test)]
@@ -210,7 +217,7 @@ mod tests {
     #[test]
     fn test1() {
         let input = include_str!("example_b_data.txt");
-        assert_eq!(super::run(input), 6);
+        assert_eq!(super::run(input).unwrap(), 6);
     }
 }
 // 8b anser: 15299095336639
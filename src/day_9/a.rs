@@ -1,20 +1,41 @@
 use std::str::FromStr;
 
+use crate::error::{parse_lines, parse_token, Error, Result};
+
 #[derive(Debug)]
 struct Sequence {
     numbers: Vec<i64>,
 }
 
 impl FromStr for Sequence {
-    type Err = &'static str;
-    fn from_str(line: &str) -> Result<Self, <Self as FromStr>::Err> {
+    type Err = Error;
+    fn from_str(line: &str) -> Result<Self> {
         let numbers = line
             .split_whitespace()
-            .map(|s| s.parse::<i64>().unwrap())
-            .collect::<Vec<_>>();
+            .map(|s| parse_token::<i64>(line, s))
+            .collect::<Result<Vec<_>>>()?;
+        check_differences(&numbers)?;
         Ok(Sequence { numbers })
     }
 }
+/// Check that taking differences of `numbers` reaches a row of zeros
+/// before running out of numbers, there's nothing to extrapolate otherwise
+pub(super) fn check_differences(numbers: &[i64]) -> Result<()> {
+    let mut row = numbers.to_vec();
+    while row.iter().any(|x| *x != 0) {
+        if row.len() < 2 {
+            return Err(Error::new(
+                "expected differences that reach all zeros, need more numbers",
+            ));
+        }
+        row = row.windows(2).map(|x| x[1] - x[0]).collect();
+    }
+    if row.is_empty() {
+        return Err(Error::new("expected numbers"));
+    }
+    Ok(())
+}
+
 impl Sequence {
     fn predict_next_2(&self) -> i64 {
         let mut vecs = Vec::new();
@@ -32,11 +53,11 @@ impl Sequence {
     }
 }
 
-pub fn run(input: &str) -> i64 {
-    input
-        .lines()
-        .map(|line| line.parse::<Sequence>().unwrap().predict_next_2())
-        .sum::<i64>()
+pub fn run(input: &str) -> Result<i64> {
+    Ok(parse_lines::<Sequence>(input)?
+        .iter()
+        .map(|sequence| sequence.predict_next_2())
+        .sum::<i64>())
 }
 
 #[cfg(test)]
@@ -44,6 +65,11 @@ mod tests {
     #[test]
     fn test1() {
         let input = include_str!("example_data.txt");
-        assert_eq!(super::run(input), 114);
+        assert_eq!(super::run(input).unwrap(), 114);
+        assert_eq!(super::run("0 0\n3 3").unwrap(), 3);
+        assert_eq!(
+            super::run("0 0\n1 2").unwrap_err().to_string(),
+            "line 2: expected differences that reach all zeros, need more numbers"
+        );
     }
 }
@@ -1,17 +1,21 @@
 use std::str::FromStr;
 
+use super::a::check_differences;
+use crate::error::{parse_lines, parse_token, Error, Result};
+
 #[derive(Debug)]
 struct Sequence {
     numbers: Vec<i64>,
 }
 
 impl FromStr for Sequence {
-    type Err = &'static str;
-    fn from_str(line: &str) -> Result<Self, <Self as FromStr>::Err> {
+    type Err = Error;
+    fn from_str(line: &str) -> Result<Self> {
         let numbers = line
             .split_whitespace()
-            .map(|s| s.parse::<i64>().unwrap())
-            .collect::<Vec<_>>();
+            .map(|s| parse_token::<i64>(line, s))
+            .collect::<Result<Vec<_>>>()?;
+        check_differences(&numbers)?;
         Ok(Sequence { numbers })
     }
 }
@@ -36,11 +40,11 @@ impl Sequence {
     }
 }
 
-pub fn run(input: &str) -> i64 {
-    input
-        .lines()
-        .map(|line| line.parse::<Sequence>().unwrap().predict_prev())
-        .sum::<i64>()
+pub fn run(input: &str) -> Result<i64> {
+    Ok(parse_lines::<Sequence>(input)?
+        .iter()
+        .map(|sequence| sequence.predict_prev())
+        .sum::<i64>())
 }
 
 #[cfg(test)]
@@ -48,6 +52,6 @@ mod tests {
     #[test]
     fn test1() {
         let input = include_str!("example_data.txt");
-        assert_eq!(super::run(input), 2);
+        assert_eq!(super::run(input).unwrap(), 2);
     }
 }
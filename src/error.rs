@@ -0,0 +1,254 @@
+use std::{fmt::Display, str::FromStr};
+
+/// Error from parsing or solving a day's puzzle
+///
+/// Parsers say what went wrong and where, counting lines and columns from 1.
+/// The runner fills in the day before the error is reported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    pub day: Option<u8>,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    pub fn new(message: impl Into<String>) -> Error {
+        Error {
+            day: None,
+            line: None,
+            column: None,
+            message: message.into(),
+        }
+    }
+
+    pub fn for_day(mut self, day: u8) -> Error {
+        self.day.get_or_insert(day);
+        self
+    }
+
+    /// Set the line unless a more specific parser already did
+    pub fn at_line(mut self, line: usize) -> Error {
+        self.line.get_or_insert(line);
+        self
+    }
+
+    pub fn at_column(mut self, column: usize) -> Error {
+        self.column.get_or_insert(column);
+        self
+    }
+
+    /// Set the column to where `token` starts in `line`, if it is a slice of
+    /// it
+    pub fn at_token(self, line: &str, token: &str) -> Error {
+        match column_of(line, token) {
+            Some(column) => self.at_column(column),
+            None => self,
+        }
+    }
+
+    /// Move a line number that is relative to a block of the input so it is
+    /// relative to the whole input, `offset` is the number of lines before
+    /// the block
+    pub fn offset_lines(mut self, offset: usize) -> Error {
+        if let Some(line) = self.line.as_mut() {
+            *line += offset;
+        }
+        self
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let location = [
+            self.day.map(|day| format!("day {}", day)),
+            self.line.map(|line| format!("line {}", line)),
+            self.column.map(|column| format!("column {}", column)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+        if location.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", location.join(", "), self.message)
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<&str> for Error {
+    fn from(message: &str) -> Self {
+        Error::new(message)
+    }
+}
+
+impl From<String> for Error {
+    fn from(message: String) -> Self {
+        Error::new(message)
+    }
+}
+
+/// Column of `token` within `line`, if it is a slice of it
+fn column_of(line: &str, token: &str) -> Option<usize> {
+    let start = line.as_ptr() as usize;
+    let offset = (token.as_ptr() as usize).checked_sub(start)?;
+    if offset > line.len() {
+        return None;
+    }
+    Some(line[..offset].chars().count() + 1)
+}
+
+/// Parse a token taken from `line`, reporting its column on failure
+pub fn parse_token<T: FromStr>(line: &str, token: &str) -> Result<T> {
+    token
+        .parse::<T>()
+        .map_err(|_| Error::new(format!("could not parse `{}`", token)).at_token(line, token))
+}
+
+/// Error for an unexpected character at a 0 based index into a line
+pub fn at_char(index: usize, message: impl Into<String>) -> Error {
+    Error::new(message).at_column(index + 1)
+}
+
+/// Convert every char of `line`, reporting the column on failure
+pub fn parse_chars<T>(line: &str) -> Result<Vec<T>>
+where
+    T: TryFrom<char, Error = Error>,
+{
+    line.chars()
+        .enumerate()
+        .map(|(i, c)| T::try_from(c).map_err(|e| e.at_column(i + 1)))
+        .collect()
+}
+
+/// Convert every char of `input` into rows of cells, reporting the line and
+/// column on failure
+///
+/// The grid must have at least one row and every row must be as long as the
+/// first.
+pub fn parse_grid<T>(input: &str) -> Result<Vec<Vec<T>>>
+where
+    T: TryFrom<char, Error = Error>,
+{
+    parse_grid_with(input, T::try_from)
+}
+
+/// Like [parse_grid] for cells that can't implement `TryFrom<char>`
+pub fn parse_grid_with<T>(input: &str, convert: impl Fn(char) -> Result<T>) -> Result<Vec<Vec<T>>> {
+    let rows = input
+        .lines()
+        .enumerate()
+        .map(|(iline, line)| {
+            line.chars()
+                .enumerate()
+                .map(|(i, c)| convert(c).map_err(|e| e.at_column(i + 1).at_line(iline + 1)))
+                .collect::<Result<Vec<T>>>()
+        })
+        .collect::<Result<Vec<Vec<T>>>>()?;
+    let ncols = rows.first().ok_or("empty grid")?.len();
+    if let Some((i, row)) = rows.iter().enumerate().find(|(_, row)| row.len() != ncols) {
+        return Err(
+            Error::new(format!("expected {} columns, found {}", ncols, row.len())).at_line(i + 1),
+        );
+    }
+    Ok(rows)
+}
+
+/// Convert a char to the digit it shows
+pub fn digit(c: char) -> Result<u8> {
+    c.to_digit(10)
+        .map(|d| d as u8)
+        .ok_or_else(|| Error::new(format!("expected a digit, found `{}`", c)))
+}
+
+/// Parse every line of `input`, reporting the line number on failure
+pub fn parse_lines<T>(input: &str) -> Result<Vec<T>>
+where
+    T: FromStr<Err = Error>,
+{
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| line.parse::<T>().map_err(|e| e.at_line(i + 1)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{digit, parse_grid, parse_grid_with, parse_lines, parse_token, Error};
+
+    #[test]
+    fn test_display() {
+        let error = Error::new("bad card");
+        assert_eq!(error.to_string(), "bad card");
+        let error = error.at_column(4).at_line(2);
+        assert_eq!(error.to_string(), "line 2, column 4: bad card");
+        let error = error.for_day(7).offset_lines(10).at_line(1);
+        assert_eq!(error.to_string(), "day 7, line 12, column 4: bad card");
+        assert_eq!(Error::new("empty").for_day(3).to_string(), "day 3: empty");
+    }
+
+    #[test]
+    fn test_parse() {
+        let line = "12 x4 5";
+        let token = line.split(' ').nth(1).unwrap();
+        let error = parse_token::<u32>(line, token).unwrap_err();
+        assert_eq!(error.to_string(), "column 4: could not parse `x4`");
+        assert_eq!(parse_token::<u32>(line, "5"), Ok(5));
+
+        let numbers = parse_lines::<Number>("1\n2\nthree").unwrap_err();
+        assert_eq!(
+            numbers.to_string(),
+            "line 3, column 1: could not parse `three`"
+        );
+        let numbers = parse_lines::<Number>("1\n2").unwrap();
+        assert_eq!(numbers.iter().map(|n| n.0).sum::<u32>(), 3);
+    }
+
+    #[test]
+    fn test_grid() {
+        let grid = parse_grid::<Bit>("01\n10").unwrap();
+        assert_eq!(
+            grid[1].iter().map(|b| b.0).collect::<Vec<_>>(),
+            [true, false]
+        );
+        let error = parse_grid::<Bit>("01\n12").unwrap_err();
+        assert_eq!(error.to_string(), "line 2, column 2: bad bit `2`");
+        let error = parse_grid::<Bit>("01\n1").unwrap_err();
+        assert_eq!(error.to_string(), "line 2: expected 2 columns, found 1");
+        assert!(parse_grid::<Bit>("").is_err());
+        let error = parse_grid_with("12\n3a", digit).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 2: expected a digit, found `a`"
+        );
+    }
+
+    #[derive(Debug)]
+    struct Number(u32);
+
+    #[derive(Debug)]
+    struct Bit(bool);
+
+    impl TryFrom<char> for Bit {
+        type Error = Error;
+        fn try_from(c: char) -> Result<Self, Self::Error> {
+            match c {
+                '0' => Ok(Bit(false)),
+                '1' => Ok(Bit(true)),
+                _ => Err(Error::new(format!("bad bit `{}`", c))),
+            }
+        }
+    }
+
+    impl std::str::FromStr for Number {
+        type Err = Error;
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            parse_token(s, s).map(Number)
+        }
+    }
+}
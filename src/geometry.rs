@@ -0,0 +1,435 @@
+use std::{
+    fmt::Display,
+    ops::{Add, Mul, Sub},
+};
+
+use num::PrimInt;
+
+/// One of the four compass directions on a map drawn with north at the top
+///
+/// Rows grow going south and columns grow going east.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction {
+    #[default]
+    N,
+    E,
+    S,
+    W,
+}
+
+impl Direction {
+    /// Clockwise from north
+    pub const ALL: [Direction; 4] = [Direction::N, Direction::E, Direction::S, Direction::W];
+
+    /// Direction after a quarter turn counterclockwise
+    pub fn left(&self) -> Direction {
+        match self {
+            Direction::N => Direction::W,
+            Direction::E => Direction::N,
+            Direction::S => Direction::E,
+            Direction::W => Direction::S,
+        }
+    }
+
+    /// Direction after a quarter turn clockwise
+    pub fn right(&self) -> Direction {
+        match self {
+            Direction::N => Direction::E,
+            Direction::E => Direction::S,
+            Direction::S => Direction::W,
+            Direction::W => Direction::N,
+        }
+    }
+
+    pub fn opposite(&self) -> Direction {
+        match self {
+            Direction::N => Direction::S,
+            Direction::E => Direction::W,
+            Direction::S => Direction::N,
+            Direction::W => Direction::E,
+        }
+    }
+
+    /// Row and column offset of one step
+    pub fn offset(&self) -> [i64; 2] {
+        match self {
+            Direction::N => [-1, 0],
+            Direction::E => [0, 1],
+            Direction::S => [1, 0],
+            Direction::W => [0, -1],
+        }
+    }
+}
+
+impl Display for Direction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Direction::N => write!(f, "N"),
+            Direction::E => write!(f, "E"),
+            Direction::S => write!(f, "S"),
+            Direction::W => write!(f, "W"),
+        }
+    }
+}
+
+/// Index of the direction in [Direction::ALL], for tables with a layer per
+/// direction
+impl From<Direction> for usize {
+    fn from(value: Direction) -> Self {
+        match value {
+            Direction::N => 0,
+            Direction::E => 1,
+            Direction::S => 2,
+            Direction::W => 3,
+        }
+    }
+}
+
+/// One of the four compass directions or one of the diagonals between them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction8 {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+}
+
+impl Direction8 {
+    /// Clockwise from north
+    pub const ALL: [Direction8; 8] = [
+        Direction8::N,
+        Direction8::NE,
+        Direction8::E,
+        Direction8::SE,
+        Direction8::S,
+        Direction8::SW,
+        Direction8::W,
+        Direction8::NW,
+    ];
+
+    fn turn(&self, eighths: usize) -> Direction8 {
+        let index = Direction8::ALL.iter().position(|d| d == self).unwrap();
+        Direction8::ALL[(index + eighths) % 8]
+    }
+
+    /// Direction after an eighth of a turn counterclockwise
+    pub fn left(&self) -> Direction8 {
+        self.turn(7)
+    }
+
+    /// Direction after an eighth of a turn clockwise
+    pub fn right(&self) -> Direction8 {
+        self.turn(1)
+    }
+
+    pub fn opposite(&self) -> Direction8 {
+        self.turn(4)
+    }
+
+    pub fn is_diagonal(&self) -> bool {
+        matches!(
+            self,
+            Direction8::NE | Direction8::SE | Direction8::SW | Direction8::NW
+        )
+    }
+
+    /// Row and column offset of one step
+    pub fn offset(&self) -> [i64; 2] {
+        match self {
+            Direction8::N => [-1, 0],
+            Direction8::NE => [-1, 1],
+            Direction8::E => [0, 1],
+            Direction8::SE => [1, 1],
+            Direction8::S => [1, 0],
+            Direction8::SW => [1, -1],
+            Direction8::W => [0, -1],
+            Direction8::NW => [-1, -1],
+        }
+    }
+}
+
+impl From<Direction> for Direction8 {
+    fn from(value: Direction) -> Self {
+        match value {
+            Direction::N => Direction8::N,
+            Direction::E => Direction8::E,
+            Direction::S => Direction8::S,
+            Direction::W => Direction8::W,
+        }
+    }
+}
+
+/// Distance between two numbers without going below zero for unsigned types
+fn abs_diff<T: PrimInt>(a: T, b: T) -> T {
+    if a > b {
+        a - b
+    } else {
+        b - a
+    }
+}
+
+/// Move `value` by `distance` in the direction of the sign of `delta`
+fn shift<T: PrimInt>(value: T, delta: i64, distance: T) -> Option<T> {
+    match delta.signum() {
+        1 => value.checked_add(&distance),
+        -1 => value.checked_sub(&distance),
+        _ => Some(value),
+    }
+}
+
+/// Point on a map, the row grows going south and the column going east
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point2<T> {
+    pub row: T,
+    pub col: T,
+}
+
+impl<T> Point2<T> {
+    pub fn new(row: T, col: T) -> Point2<T> {
+        Point2 { row, col }
+    }
+}
+
+impl<T: PrimInt> Point2<T> {
+    pub fn manhattan(&self, other: &Point2<T>) -> T {
+        abs_diff(self.row, other.row) + abs_diff(self.col, other.col)
+    }
+
+    /// Point `distance` steps towards `direction`, if it can be represented,
+    /// so an unsigned point can't move north of row 0
+    pub fn moved(&self, direction: Direction, distance: T) -> Option<Point2<T>> {
+        self.moved8(direction.into(), distance)
+    }
+
+    /// Like [Point2::moved] but diagonals move `distance` along both axes
+    pub fn moved8(&self, direction: Direction8, distance: T) -> Option<Point2<T>> {
+        let [drow, dcol] = direction.offset();
+        Some(Point2::new(
+            shift(self.row, drow, distance)?,
+            shift(self.col, dcol, distance)?,
+        ))
+    }
+
+    /// Neighbor one step towards `direction`
+    pub fn step(&self, direction: Direction) -> Option<Point2<T>> {
+        self.moved(direction, T::one())
+    }
+
+    /// Whether the point is in a map with `nrows` rows and `ncols` columns
+    pub fn within(&self, nrows: T, ncols: T) -> bool {
+        self.row >= T::zero() && self.row < nrows && self.col >= T::zero() && self.col < ncols
+    }
+
+    /// Neighbors north, east, south and west that can be represented
+    pub fn neighbors4(&self) -> impl Iterator<Item = Point2<T>> {
+        let point = *self;
+        Direction::ALL
+            .into_iter()
+            .filter_map(move |d| point.step(d))
+    }
+
+    /// Neighbors in all eight directions that can be represented
+    pub fn neighbors8(&self) -> impl Iterator<Item = Point2<T>> {
+        let point = *self;
+        Direction8::ALL
+            .into_iter()
+            .filter_map(move |d| point.moved8(d, T::one()))
+    }
+
+    /// Smallest row and smallest column of the two, unlike [Ord::min] which
+    /// compares rows first
+    pub fn min_each(&self, other: &Point2<T>) -> Point2<T> {
+        Point2::new(self.row.min(other.row), self.col.min(other.col))
+    }
+
+    /// Largest row and largest column of the two
+    pub fn max_each(&self, other: &Point2<T>) -> Point2<T> {
+        Point2::new(self.row.max(other.row), self.col.max(other.col))
+    }
+}
+
+impl Point2<usize> {
+    /// Index into a [Grid](crate::grid::Grid) or an `Array2`
+    pub fn index(&self) -> [usize; 2] {
+        [self.row, self.col]
+    }
+}
+
+impl<T> From<[T; 2]> for Point2<T> {
+    fn from([row, col]: [T; 2]) -> Self {
+        Point2 { row, col }
+    }
+}
+
+impl<T> From<Point2<T>> for [T; 2] {
+    fn from(value: Point2<T>) -> Self {
+        [value.row, value.col]
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point2<T> {
+    type Output = Point2<T>;
+    fn add(self, rhs: Self) -> Self::Output {
+        Point2::new(self.row + rhs.row, self.col + rhs.col)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point2<T> {
+    type Output = Point2<T>;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Point2::new(self.row - rhs.row, self.col - rhs.col)
+    }
+}
+
+impl<T: Display> Display for Point2<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.row, self.col)
+    }
+}
+
+/// Point or vector in space
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Point3<T> {
+    pub fn new(x: T, y: T, z: T) -> Point3<T> {
+        Point3 { x, y, z }
+    }
+}
+
+impl<T: Copy> Point3<T> {
+    pub fn to_array(&self) -> [T; 3] {
+        [self.x, self.y, self.z]
+    }
+
+    pub fn map<U>(&self, f: impl Fn(T) -> U) -> Point3<U> {
+        Point3::new(f(self.x), f(self.y), f(self.z))
+    }
+}
+
+impl<T: PrimInt> Point3<T> {
+    pub fn manhattan(&self, other: &Point3<T>) -> T {
+        abs_diff(self.x, other.x) + abs_diff(self.y, other.y) + abs_diff(self.z, other.z)
+    }
+
+    pub fn dot(&self, other: &Point3<T>) -> T {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    /// Vector at right angles to both, zero when they are parallel
+    pub fn cross(&self, other: &Point3<T>) -> Point3<T> {
+        Point3::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+}
+
+impl<T> From<[T; 3]> for Point3<T> {
+    fn from([x, y, z]: [T; 3]) -> Self {
+        Point3 { x, y, z }
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point3<T> {
+    type Output = Point3<T>;
+    fn add(self, rhs: Self) -> Self::Output {
+        Point3::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point3<T> {
+    type Output = Point3<T>;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Point3::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Point3<T> {
+    type Output = Point3<T>;
+    fn mul(self, rhs: T) -> Self::Output {
+        Point3::new(self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+impl<T: Display> Display for Point3<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}, {}, {}", self.x, self.y, self.z)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Direction, Direction8, Point2, Point3};
+
+    #[test]
+    fn test_turns() {
+        for d in Direction::ALL {
+            assert_eq!(d.left().right(), d);
+            assert_eq!(d.right().right(), d.opposite());
+            assert_eq!(d.opposite().opposite(), d);
+            let [drow, dcol] = d.offset();
+            assert_eq!(d.opposite().offset(), [-drow, -dcol]);
+            assert_eq!(Direction::ALL[usize::from(d)], d);
+        }
+        assert_eq!(Direction::N.left(), Direction::W);
+        assert_eq!(Direction::N.right(), Direction::E);
+
+        for d in Direction8::ALL {
+            assert_eq!(d.left().right(), d);
+            assert_eq!(d.right().is_diagonal(), !d.is_diagonal());
+            assert_eq!(d.right().right().right().right(), d.opposite());
+        }
+        assert_eq!(Direction8::N.left(), Direction8::NW);
+        assert_eq!(Direction8::from(Direction::W).right(), Direction8::NW);
+    }
+
+    #[test]
+    fn test_moves() {
+        let p = Point2::new(0_usize, 2);
+        assert_eq!(p.step(Direction::N), None);
+        assert_eq!(p.step(Direction::W), Some(Point2::new(0, 1)));
+        assert_eq!(p.moved(Direction::W, 3), None);
+        assert_eq!(p.moved(Direction::S, 3), Some(Point2::new(3, 2)));
+        assert_eq!(p.moved8(Direction8::SW, 2), Some(Point2::new(2, 0)));
+        assert_eq!(p.neighbors4().count(), 3);
+        assert_eq!(p.neighbors8().count(), 5);
+        assert!(p.within(1, 3));
+        assert!(!p.within(1, 2));
+        assert_eq!(p.index(), [0, 2]);
+
+        let p = Point2::new(0_i64, 0);
+        assert_eq!(p.moved(Direction::N, 5), Some(Point2::new(-5, 0)));
+        assert!(!Point2::new(-1_i64, 0).within(3, 3));
+        assert_eq!(p.neighbors8().count(), 8);
+        assert_eq!(Point2::new(i64::MAX, 0).step(Direction::S), None);
+    }
+
+    #[test]
+    fn test_distance() {
+        assert_eq!(Point2::new(1_usize, 5).manhattan(&Point2::new(4, 2)), 6);
+        assert_eq!(Point2::new(-1_i64, 5).manhattan(&Point2::new(4, -2)), 12);
+        let (a, b) = (Point2::new(1, 5), Point2::new(2, -3));
+        assert_eq!(a.min_each(&b), Point2::new(1, -3));
+        assert_eq!(a.max_each(&b), Point2::new(2, 5));
+        let a = Point3::new(1_i64, -2, 3);
+        let b = Point3::from([4, 2, 3]);
+        assert_eq!(a.manhattan(&b), 7);
+        assert_eq!(b - a, Point3::new(3, 4, 0));
+        assert_eq!(a + b * 2, Point3::new(9, 2, 9));
+        assert_eq!(a.dot(&b), 9);
+        assert_eq!(a.cross(&b), Point3::new(-12, 9, 10));
+        assert_eq!(a.cross(&b).dot(&a), 0);
+        assert_eq!(a.to_string(), "1, -2, 3");
+        assert_eq!(a.map(|v| v as f64 / 2.0).to_array(), [0.5, -1.0, 1.5]);
+    }
+}
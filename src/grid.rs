@@ -0,0 +1,299 @@
+use std::{
+    fmt::Display,
+    ops::{Index, IndexMut},
+    str::FromStr,
+};
+
+use ndarray::{s, Array2};
+
+use crate::{
+    error::{parse_grid, parse_grid_with, Error, Result},
+    geometry::{Direction, Direction8},
+};
+
+/// Rectangular grid of cells indexed by `[row, col]`
+///
+/// Puzzles that come as a picture are parsed into one of these, one cell per
+/// char. The cells are kept in an [Array2] for the days that need to work on
+/// whole rows or columns.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    cells: Array2<T>,
+}
+
+impl<T> Grid<T> {
+    /// Grid from rows that must all be as long as the first
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Grid<T>> {
+        let ncols = rows.first().ok_or("empty grid")?.len();
+        if let Some((i, row)) = rows.iter().enumerate().find(|(_, row)| row.len() != ncols) {
+            return Err(
+                Error::new(format!("expected {} columns, found {}", ncols, row.len()))
+                    .at_line(i + 1),
+            );
+        }
+        let nrows = rows.len();
+        let cells = rows.into_iter().flatten().collect::<Vec<_>>();
+        Ok(Grid {
+            cells: Array2::from_shape_vec((nrows, ncols), cells).unwrap(),
+        })
+    }
+
+    /// Parse `input` with `convert` for cells that can't implement
+    /// `TryFrom<char>`
+    pub fn parse_with(input: &str, convert: impl Fn(char) -> Result<T>) -> Result<Grid<T>> {
+        Grid::from_rows(parse_grid_with(input, convert)?)
+    }
+
+    pub fn nrows(&self) -> usize {
+        self.cells.nrows()
+    }
+
+    pub fn ncols(&self) -> usize {
+        self.cells.ncols()
+    }
+
+    pub fn get(&self, [row, col]: [usize; 2]) -> Option<&T> {
+        self.cells.get([row, col])
+    }
+
+    pub fn get_mut(&mut self, [row, col]: [usize; 2]) -> Option<&mut T> {
+        self.cells.get_mut([row, col])
+    }
+
+    /// Index into the grid as if it were tiled forever in every direction
+    pub fn wrap(&self, [row, col]: [i64; 2]) -> [usize; 2] {
+        [
+            row.rem_euclid(self.nrows() as i64) as usize,
+            col.rem_euclid(self.ncols() as i64) as usize,
+        ]
+    }
+
+    /// Cell of the grid tiled forever in every direction
+    pub fn get_wrapped(&self, position: [i64; 2]) -> &T {
+        &self[self.wrap(position)]
+    }
+
+    /// Position `offset` away from `position`, if it is inside the grid
+    pub fn offset(&self, [row, col]: [usize; 2], [drow, dcol]: [i64; 2]) -> Option<[usize; 2]> {
+        let row = row.checked_add_signed(drow as isize)?;
+        let col = col.checked_add_signed(dcol as isize)?;
+        (row < self.nrows() && col < self.ncols()).then_some([row, col])
+    }
+
+    pub fn on_edge(&self, [row, col]: [usize; 2]) -> bool {
+        row == 0 || col == 0 || row + 1 == self.nrows() || col + 1 == self.ncols()
+    }
+
+    /// Positions north, east, south and west of `position` that are inside
+    /// the grid
+    pub fn neighbors4(&self, position: [usize; 2]) -> impl Iterator<Item = [usize; 2]> + '_ {
+        Direction::ALL
+            .iter()
+            .filter_map(move |d| self.offset(position, d.offset()))
+    }
+
+    /// Positions around `position`, diagonals included, that are inside the
+    /// grid
+    pub fn neighbors8(&self, position: [usize; 2]) -> impl Iterator<Item = [usize; 2]> + '_ {
+        Direction8::ALL
+            .iter()
+            .filter_map(move |d| self.offset(position, d.offset()))
+    }
+
+    /// Cells in row major order
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.cells.iter_mut()
+    }
+
+    /// Cells in row major order with their positions
+    pub fn indexed_iter(&self) -> impl Iterator<Item = ([usize; 2], &T)> {
+        self.cells
+            .indexed_iter()
+            .map(|((row, col), cell)| ([row, col], cell))
+    }
+
+    /// Positions of the cells matching `predicate`, in row major order
+    pub fn positions<'a>(
+        &'a self,
+        predicate: impl Fn(&T) -> bool + 'a,
+    ) -> impl Iterator<Item = [usize; 2]> + 'a {
+        self.indexed_iter()
+            .filter(move |(_, cell)| predicate(cell))
+            .map(|(position, _)| position)
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.map(f),
+        }
+    }
+
+    pub fn as_array(&self) -> &Array2<T> {
+        &self.cells
+    }
+
+    pub fn as_array_mut(&mut self) -> &mut Array2<T> {
+        &mut self.cells
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn from_elem(nrows: usize, ncols: usize, elem: T) -> Grid<T> {
+        Grid {
+            cells: Array2::from_elem((nrows, ncols), elem),
+        }
+    }
+
+    /// Swap rows and columns
+    pub fn transpose(&self) -> Grid<T> {
+        Grid {
+            cells: self.cells.t().to_owned(),
+        }
+    }
+
+    /// Rotate a quarter turn clockwise, the first column becomes the first
+    /// row read bottom to top
+    pub fn rotate_cw(&self) -> Grid<T> {
+        Grid {
+            cells: self.cells.t().slice(s![.., ..;-1]).to_owned(),
+        }
+    }
+
+    /// Rotate a quarter turn counterclockwise, the first row becomes the
+    /// first column read bottom to top
+    pub fn rotate_ccw(&self) -> Grid<T> {
+        Grid {
+            cells: self.cells.t().slice(s![..;-1, ..]).to_owned(),
+        }
+    }
+}
+
+impl<T> Index<[usize; 2]> for Grid<T> {
+    type Output = T;
+    fn index(&self, index: [usize; 2]) -> &Self::Output {
+        &self.cells[index]
+    }
+}
+
+impl<T> IndexMut<[usize; 2]> for Grid<T> {
+    fn index_mut(&mut self, index: [usize; 2]) -> &mut Self::Output {
+        &mut self.cells[index]
+    }
+}
+
+impl<T> FromStr for Grid<T>
+where
+    T: TryFrom<char, Error = Error>,
+{
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
+        Grid::from_rows(parse_grid(s)?)
+    }
+}
+
+/// Write the grid back out as text, each cell should write a single char
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.cells.rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grid;
+    use crate::error::{digit, Error};
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Tile {
+        Rock,
+        Sand,
+    }
+
+    impl TryFrom<char> for Tile {
+        type Error = Error;
+        fn try_from(c: char) -> Result<Self, Self::Error> {
+            match c {
+                '#' => Ok(Tile::Rock),
+                '.' => Ok(Tile::Sand),
+                _ => Err(Error::new(format!("bad tile `{}`", c))),
+            }
+        }
+    }
+
+    impl std::fmt::Display for Tile {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                Tile::Rock => write!(f, "#"),
+                Tile::Sand => write!(f, "."),
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = "#..\n.#.".parse::<Grid<Tile>>().unwrap();
+        assert_eq!((grid.nrows(), grid.ncols()), (2, 3));
+        assert_eq!(grid[[1, 1]], Tile::Rock);
+        assert_eq!(grid.get([1, 3]), None);
+        assert_eq!(grid.to_string(), "#..\n.#.\n");
+        assert_eq!(
+            grid.positions(|t| *t == Tile::Rock).collect::<Vec<_>>(),
+            [[0, 0], [1, 1]]
+        );
+
+        let error = "#.\n.x".parse::<Grid<Tile>>().unwrap_err();
+        assert_eq!(error.to_string(), "line 2, column 2: bad tile `x`");
+        let error = Grid::from_rows(vec![vec![1, 2], vec![3]]).unwrap_err();
+        assert_eq!(error.to_string(), "line 2: expected 2 columns, found 1");
+
+        let digits = Grid::parse_with("12\n34", digit).unwrap();
+        assert_eq!(digits[[1, 0]], 3);
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid = Grid::from_elem(3, 4, 0);
+        assert_eq!(
+            grid.neighbors4([0, 0]).collect::<Vec<_>>(),
+            [[0, 1], [1, 0]]
+        );
+        assert_eq!(grid.neighbors4([1, 1]).count(), 4);
+        assert_eq!(grid.neighbors8([1, 1]).count(), 8);
+        assert_eq!(
+            grid.neighbors8([2, 3]).collect::<Vec<_>>(),
+            [[1, 3], [2, 2], [1, 2]]
+        );
+        assert_eq!(grid.offset([2, 3], [0, 1]), None);
+        assert_eq!(grid.offset([2, 3], [-2, -3]), Some([0, 0]));
+        assert!(grid.on_edge([2, 1]));
+        assert!(!grid.on_edge([1, 1]));
+    }
+
+    #[test]
+    fn test_wrap() {
+        let grid = Grid::from_rows(vec![vec![1, 2, 3], vec![4, 5, 6]]).unwrap();
+        assert_eq!(grid.wrap([-1, -1]), [1, 2]);
+        assert_eq!(grid.wrap([5, 7]), [1, 1]);
+        assert_eq!(*grid.get_wrapped([2, -3]), 1);
+    }
+
+    #[test]
+    fn test_transform() {
+        let grid = Grid::from_rows(vec![vec![1, 2, 3], vec![4, 5, 6]]).unwrap();
+        assert_eq!(grid.transpose().to_string(), "14\n25\n36\n");
+        assert_eq!(grid.rotate_cw().to_string(), "41\n52\n63\n");
+        assert_eq!(grid.rotate_ccw().to_string(), "36\n25\n14\n");
+        assert_eq!(grid.rotate_cw().rotate_ccw(), grid);
+        assert_eq!(grid.map(|x| x * 2)[[1, 2]], 12);
+    }
+}
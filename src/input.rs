@@ -0,0 +1,170 @@
+use std::{
+    fmt::Display,
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+use crate::solution::Part;
+
+/// Where the puzzle input for a day is read from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    /// The data file that lives next to the day's solution
+    Default,
+    File(PathBuf),
+    Stdin,
+    /// One of the worked examples from the puzzle text, counting from 1
+    Example(usize),
+}
+
+impl InputSource {
+    /// Interpret a `--input` argument, `-` meaning stdin
+    pub fn from_arg(arg: &str) -> InputSource {
+        if arg == "-" {
+            InputSource::Stdin
+        } else {
+            InputSource::File(PathBuf::from(arg))
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct InputError {
+    path: Option<PathBuf>,
+    source: io::Error,
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.path {
+            Some(path) => write!(
+                f,
+                "could not read input file {}: {}",
+                path.display(),
+                self.source
+            ),
+            None => write!(f, "could not read input from stdin: {}", self.source),
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+/// Directory holding the source and data files for a day
+fn day_dir(day: u8) -> PathBuf {
+    let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+    if day <= 6 {
+        src
+    } else {
+        src.join(format!("day_{}", day))
+    }
+}
+
+/// Path of the data file checked in for a day
+pub fn default_path(day: u8) -> PathBuf {
+    match day {
+        1..=6 => day_dir(day).join(format!("day_{}_data.txt", day)),
+        7 => day_dir(day).join("day_7_data.txt"),
+        _ => day_dir(day).join("data.txt"),
+    }
+}
+
+fn read_file(path: &Path) -> Result<String, InputError> {
+    fs::read_to_string(path).map_err(|source| InputError {
+        path: Some(path.to_path_buf()),
+        source,
+    })
+}
+
+/// Path of an example file for a day
+///
+/// Examples live next to the data as `example_data.txt`,
+/// `example_data_2.txt` and so on. A day whose second part has its own
+/// example also ships `example_b_data.txt`, which is preferred for part b.
+pub fn example_path(day: u8, part: Part, n: usize) -> PathBuf {
+    let dir = day_dir(day);
+    if part == Part::B {
+        let path = if n == 1 {
+            dir.join("example_b_data.txt")
+        } else {
+            dir.join(format!("example_b_data_{}.txt", n))
+        };
+        if path.exists() {
+            return path;
+        }
+    }
+    if n == 1 {
+        dir.join("example_data.txt")
+    } else {
+        dir.join(format!("example_data_{}.txt", n))
+    }
+}
+
+/// Read the puzzle input for one part of a day
+///
+/// Only examples can differ between parts, every other source gives the
+/// same text as [read].
+pub fn read_part(day: u8, part: Part, source: &InputSource) -> Result<String, InputError> {
+    match source {
+        InputSource::Example(n) => Ok(trim(read_file(&example_path(day, part, *n))?)),
+        _ => read(day, source),
+    }
+}
+
+fn trim(text: String) -> String {
+    text.trim_end_matches(['\n', '\r']).to_string()
+}
+
+/// Read the puzzle input for a day
+///
+/// Trailing newlines are dropped so that files saved by an editor parse the
+/// same as the checked in data. Examples are read for part a.
+pub fn read(day: u8, source: &InputSource) -> Result<String, InputError> {
+    let text = match source {
+        InputSource::Default => read_file(&default_path(day))?,
+        InputSource::File(path) => read_file(path)?,
+        InputSource::Example(n) => read_file(&example_path(day, Part::A, *n))?,
+        InputSource::Stdin => {
+            let mut text = String::new();
+            io::stdin()
+                .read_to_string(&mut text)
+                .map_err(|source| InputError { path: None, source })?;
+            text
+        }
+    };
+    Ok(trim(text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{default_path, example_path, read, read_part, InputSource};
+    use crate::solution::Part;
+
+    #[test]
+    fn test_default() {
+        let input = read(8, &InputSource::Default).unwrap();
+        assert_eq!(input, include_str!("day_8/data.txt"));
+        assert!(default_path(3).ends_with("src/day_3_data.txt"));
+    }
+
+    #[test]
+    fn test_example() {
+        let source = InputSource::Example(1);
+        let a = read_part(8, Part::A, &source).unwrap();
+        let b = read_part(8, Part::B, &source).unwrap();
+        assert_eq!(a, include_str!("day_8/example_data.txt"));
+        assert_eq!(b, include_str!("day_8/example_b_data.txt"));
+        let b = read_part(9, Part::B, &source).unwrap();
+        assert_eq!(b, include_str!("day_9/example_data.txt"));
+        assert!(example_path(20, Part::A, 2).ends_with("day_20/example_data_2.txt"));
+        assert!(read_part(2, Part::A, &source).is_err());
+    }
+
+    #[test]
+    fn test_missing() {
+        let source = InputSource::from_arg("no/such/file.txt");
+        let err = read(8, &source).unwrap_err();
+        assert!(err.to_string().contains("no/such/file.txt"));
+    }
+}
@@ -0,0 +1,155 @@
+use std::{
+    fmt::{Arguments, Display},
+    sync::atomic::{AtomicU32, AtomicU8, Ordering},
+};
+
+/// How much detail a log message carries
+///
+/// Warnings are always shown, each `-v` on the command line shows one more
+/// level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Display for Level {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Level::Warn => "warn",
+            Level::Info => "info",
+            Level::Debug => "debug",
+            Level::Trace => "trace",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+static VERBOSITY: AtomicU8 = AtomicU8::new(0);
+/// Bit `n` is set when day `n` may log, no bits set means every day may
+static DAYS: AtomicU32 = AtomicU32::new(0);
+
+/// Show messages up to `verbosity` levels above [Level::Warn]
+pub fn set_verbosity(verbosity: u8) {
+    VERBOSITY.store(verbosity, Ordering::Relaxed);
+}
+
+/// Only show messages logged from these days, or from every day when empty
+///
+/// Messages from outside a day module, like the runner, are always shown.
+pub fn set_days(days: &[u8]) {
+    let mask = days.iter().fold(0, |mask, day| mask | 1 << day);
+    DAYS.store(mask, Ordering::Relaxed);
+}
+
+/// Day of a module path like `aoc23::day_17::a`
+fn day_of(module: &str) -> Option<u8> {
+    module
+        .split("::")
+        .find_map(|segment| segment.strip_prefix("day_"))
+        .and_then(|day| day.parse::<u8>().ok())
+}
+
+fn allowed(verbosity: u8, days: u32, level: Level, module: &str) -> bool {
+    if level as u8 > verbosity {
+        return false;
+    }
+    match day_of(module) {
+        Some(day) => days == 0 || days & 1 << day != 0,
+        None => true,
+    }
+}
+
+/// Whether a message at `level` from `module` would be shown, use it to skip
+/// work that only feeds the log
+pub fn enabled(level: Level, module: &str) -> bool {
+    allowed(
+        VERBOSITY.load(Ordering::Relaxed),
+        DAYS.load(Ordering::Relaxed),
+        level,
+        module,
+    )
+}
+
+/// Write a message to stderr so it never mixes with the answers on stdout
+pub fn write(level: Level, module: &str, args: Arguments) {
+    let module = module.split_once("::").map_or(module, |(_, rest)| rest);
+    eprintln!("[{} {}] {}", level, module, args);
+}
+
+/// Log a message at a [Level](crate::log::Level), the arguments are only
+/// evaluated when it will be shown
+#[macro_export]
+macro_rules! log {
+    ($level:expr, $($arg:tt)+) => {
+        if $crate::log::enabled($level, module_path!()) {
+            $crate::log::write($level, module_path!(), format_args!($($arg)+));
+        }
+    };
+}
+
+/// Whether messages at a [Level](crate::log::Level) from the calling module
+/// would be shown
+#[macro_export]
+macro_rules! log_enabled {
+    ($level:expr) => {
+        $crate::log::enabled($level, module_path!())
+    };
+}
+
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)+) => {
+        $crate::log!($crate::log::Level::Warn, $($arg)+)
+    };
+}
+
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)+) => {
+        $crate::log!($crate::log::Level::Info, $($arg)+)
+    };
+}
+
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)+) => {
+        $crate::log!($crate::log::Level::Debug, $($arg)+)
+    };
+}
+
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)+) => {
+        $crate::log!($crate::log::Level::Trace, $($arg)+)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{allowed, day_of, Level};
+
+    #[test]
+    fn test_day_of() {
+        assert_eq!(day_of("aoc23::day_17::a"), Some(17));
+        assert_eq!(day_of("aoc23::day_7::day_7a"), Some(7));
+        assert_eq!(day_of("aoc23::day_1"), Some(1));
+        assert_eq!(day_of("aoc23::runner"), None);
+    }
+
+    #[test]
+    fn test_allowed() {
+        assert!(allowed(0, 0, Level::Warn, "aoc23::day_12::b"));
+        assert!(!allowed(0, 0, Level::Info, "aoc23::day_12::b"));
+        assert!(allowed(2, 0, Level::Debug, "aoc23::day_12::b"));
+        assert!(!allowed(2, 0, Level::Trace, "aoc23::day_12::b"));
+
+        let days = 1 << 17 | 1 << 24;
+        assert!(allowed(1, days, Level::Info, "aoc23::day_17::a"));
+        assert!(!allowed(1, days, Level::Info, "aoc23::day_12::b"));
+        assert!(!allowed(1, days, Level::Warn, "aoc23::day_12::b"));
+        assert!(allowed(1, days, Level::Info, "aoc23::runner"));
+    }
+}
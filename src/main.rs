@@ -1,140 +1,782 @@
-mod day_1;
-mod day_10;
-mod day_11;
-mod day_12;
-mod day_13;
-mod day_14;
-mod day_15;
-mod day_16;
-mod day_17;
-mod day_18;
-mod day_19;
-mod day_2;
-mod day_20;
-mod day_21;
-mod day_22;
-mod day_23;
-mod day_24;
-mod day_25;
-mod day_3;
-mod day_4;
-mod day_5;
-mod day_6;
-mod day_7;
-mod day_8;
-mod day_9;
-
-fn parse_error_message(args: &Vec<String>) {
-    println!("Usage: {} NUM [-d/--debug]", args[0]);
+use std::{
+    io::{BufWriter, Write},
+    iter::Peekable,
+    path::PathBuf,
+    slice::Iter,
+    time::{Duration, Instant},
+};
+
+use aoc23::{
+    bench::{self, Baseline},
+    day_17::{
+        crucible::Crucible,
+        path::{self, PathFormat},
+    },
+    day_20::{self, trace::Trace},
+    day_24::{self, b::HailCloud},
+    day_25,
+    input::{self, InputSource},
+    log,
+    report::{self, Format},
+    runner::{self, PartRun},
+    solution::{self, Part},
+    verify::{self, Answers},
+};
+
+fn parse_error_message(args: &[String]) {
+    println!(
+        "Usage: {} NUM [-v/-vv/-vvv] [-d/--debug] [-i/--input PATH|-] [-e/--example [N]] [--part a|b] [-f/--format text|json|csv]",
+        args[0]
+    );
+    println!(
+        "       {} all [-p/--parallel] [-f/--format text|json|csv] [-v/-vv/-vvv] [--log-day DAY]",
+        args[0]
+    );
+    println!(
+        "       {} verify [DAY...] [-p/--parallel] [-a/--answers PATH] [--all-parts] [-v/-vv/-vvv] [--log-day DAY]",
+        args[0]
+    );
+    println!(
+        "       {} bench DAY [--part a|b] [-n/--runs N] [-w/--warmup N] [-b/--baseline PATH] [-t/--threshold PERCENT] [--save/--update-baseline|--no-save]",
+        args[0]
+    );
+    println!(
+        "       {} dot [-i/--input PATH|-] [-e/--example [N]] [-o/--output PATH]",
+        args[0]
+    );
+    println!(
+        "       {} path [-i/--input PATH|-] [-e/--example [N]] [--part a|b] [-f/--format ascii|json|ppm] [-o/--output PATH]",
+        args[0]
+    );
+    println!(
+        "       {} hail [-i/--input PATH|-] [-e/--example [N]] [--from T0] [--to T1] [--step DT] [--hits] [-o/--output PATH]",
+        args[0]
+    );
+    println!(
+        "       {} trace [-i/--input PATH|-] [-e/--example [N]] [-n/--presses N] [-o/--output PATH]",
+        args[0]
+    );
+    println!(
+        "       {} replay TRACE [-i/--input PATH|-] [-e/--example [N]] [--press N]",
+        args[0]
+    );
 }
-fn main() {
-    let args = std::env::args().collect::<Vec<_>>();
-    if args.len() != 2 && args.len() != 3 {
-        parse_error_message(&args);
-        return;
+
+/// How many levels of logging a flag like `-vv` turns on
+fn verbosity_flag(arg: &str) -> Option<u8> {
+    match arg {
+        "--verbose" => Some(1),
+        // shorthand for the debug log level
+        "-d" | "--debug" => Some(2),
+        _ => arg
+            .strip_prefix('-')
+            .filter(|v| !v.is_empty() && v.chars().all(|c| c == 'v'))
+            .map(|v| v.len() as u8),
     }
-    let num = args[1].parse::<u8>().unwrap();
-    let debug = match args.len() {
-        2 => false,
-        3 => {
-            if args[2] == "-d" || args[2] == "--debug" {
-                true
-            } else {
-                parse_error_message(&args);
+}
+
+fn run_day(args: &[String]) {
+    let (num, solution) = match args[1]
+        .parse::<u8>()
+        .ok()
+        .and_then(|num| solution::get(num).map(|solution| (num, solution)))
+    {
+        Some(found) => found,
+        None => {
+            parse_error_message(args);
+            return;
+        }
+    };
+    let mut verbosity = 0_u8;
+    let mut source = InputSource::Default;
+    let mut format = Format::default();
+    let mut parts = Part::BOTH.to_vec();
+    let mut rest = args[2..].iter().peekable();
+    while let Some(arg) = rest.next() {
+        if let Some(v) = verbosity_flag(arg) {
+            verbosity = verbosity.saturating_add(v);
+            continue;
+        }
+        match arg.as_str() {
+            "-f" | "--format" => match rest.next().map(|f| f.parse::<Format>()) {
+                Some(Ok(f)) => {
+                    format = f;
+                }
+                _ => {
+                    parse_error_message(args);
+                    return;
+                }
+            },
+            "-i" | "--input" | "-e" | "--example" => match input_flag(arg, &mut rest) {
+                Some(s) => source = s,
+                None => {
+                    parse_error_message(args);
+                    return;
+                }
+            },
+            "--part" => match rest.next().map(|p| p.parse::<Part>()) {
+                Some(Ok(p)) => {
+                    parts = vec![p];
+                }
+                _ => {
+                    parse_error_message(args);
+                    return;
+                }
+            },
+            _ => {
+                parse_error_message(args);
                 return;
             }
         }
-        _ => {
-            parse_error_message(&args);
-            return;
+    }
+    log::set_verbosity(verbosity);
+    // Only examples can differ between parts, anything else is read once so
+    // that stdin isn't consumed twice
+    let read = |part: Part| match input::read_part(num, part, &source) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
         }
     };
-    match num {
-        1 => {
-            day_1::day_1();
+    let shared = match source {
+        InputSource::Example(_) => None,
+        _ => Some(read(Part::A)),
+    };
+    // a part failing doesn't hide the answers of the others
+    let runs = parts
+        .iter()
+        .map(|part| {
+            let input = match &shared {
+                Some(input) => input.clone(),
+                None => read(*part),
+            };
+            PartRun {
+                day: num,
+                part: *part,
+                result: runner::run_part(num, solution, *part, &input),
+            }
+        })
+        .collect::<Vec<_>>();
+    write_runs(format, &runs, None);
+}
+
+/// Write `runs` and exit with 1 if any part failed, the table shows each
+/// error in its row while the other formats leave them to stderr
+fn write_runs(format: Format, runs: &[PartRun], wall: Option<Duration>) {
+    report::write(&mut std::io::stdout(), format, runs, wall).unwrap();
+    let mut failed = false;
+    for run in runs {
+        if let Err(e) = &run.result {
+            if format != Format::Text {
+                eprintln!("error: part {}: {}", run.part, e);
+            }
+            failed = true;
         }
-        2 => {
-            day_2::day_2();
+    }
+    if failed {
+        std::process::exit(1);
+    }
+}
+
+/// Parse `-i/--input PATH|-` or `-e/--example [N]` into where the input
+/// is read from, taking the flag's value from `rest`
+fn input_flag(flag: &str, rest: &mut Peekable<Iter<String>>) -> Option<InputSource> {
+    match flag {
+        "-i" | "--input" => rest.next().map(|path| InputSource::from_arg(path)),
+        "-e" | "--example" => {
+            let n = match rest.peek().map(|n| n.parse::<usize>()) {
+                Some(Ok(n)) if n > 0 => {
+                    rest.next();
+                    n
+                }
+                _ => 1,
+            };
+            Some(InputSource::Example(n))
         }
-        3 => {
-            day_3::day_3();
+        _ => None,
+    }
+}
+
+/// Parse `--log-day DAY` into the days whose logs are shown
+fn log_day_flag(arg: Option<&String>, log_days: &mut Vec<u8>) -> bool {
+    match arg.map(|day| day.parse::<u8>()) {
+        Some(Ok(day)) if solution::get(day).is_some() => {
+            log_days.push(day);
+            true
         }
-        4 => {
-            day_4::day_4();
+        _ => false,
+    }
+}
+
+fn run_all(args: &[String]) {
+    let mut parallel = false;
+    let mut verbosity = 0_u8;
+    let mut log_days = Vec::new();
+    let mut format = Format::default();
+    let mut rest = args[2..].iter();
+    while let Some(arg) = rest.next() {
+        if let Some(v) = verbosity_flag(arg) {
+            verbosity = verbosity.saturating_add(v);
+            continue;
         }
-        5 => {
-            day_5::day_5();
+        match arg.as_str() {
+            "-p" | "--parallel" => {
+                parallel = true;
+            }
+            "--log-day" => {
+                if !log_day_flag(rest.next(), &mut log_days) {
+                    parse_error_message(args);
+                    return;
+                }
+            }
+            "-f" | "--format" => match rest.next().map(|f| f.parse::<Format>()) {
+                Some(Ok(f)) => {
+                    format = f;
+                }
+                _ => {
+                    parse_error_message(args);
+                    return;
+                }
+            },
+            _ => {
+                parse_error_message(args);
+                return;
+            }
         }
-        6 => {
-            day_6::day_6();
+    }
+    log::set_verbosity(verbosity);
+    log::set_days(&log_days);
+    let now = Instant::now();
+    let runs = runner::run_all(parallel);
+    let wall = now.elapsed();
+    write_runs(format, &runs, Some(wall));
+}
+
+fn run_verify(args: &[String]) {
+    let mut parallel = false;
+    let mut all_parts = false;
+    let mut answers_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("answers.toml");
+    let mut days = Vec::new();
+    let mut verbosity = 0_u8;
+    let mut log_days = Vec::new();
+    let mut rest = args[2..].iter();
+    while let Some(arg) = rest.next() {
+        if let Some(v) = verbosity_flag(arg) {
+            verbosity = verbosity.saturating_add(v);
+            continue;
         }
-        7 => {
-            day_7::day_7();
+        match arg.as_str() {
+            "-p" | "--parallel" => {
+                parallel = true;
+            }
+            "--log-day" => {
+                if !log_day_flag(rest.next(), &mut log_days) {
+                    parse_error_message(args);
+                    return;
+                }
+            }
+            "-a" | "--answers" => {
+                let Some(path) = rest.next() else {
+                    parse_error_message(args);
+                    return;
+                };
+                answers_path = PathBuf::from(path);
+            }
+            "--all-parts" => {
+                all_parts = true;
+            }
+            day => match day.parse::<u8>() {
+                Ok(day) if solution::get(day).is_some() => {
+                    days.push(day);
+                }
+                _ => {
+                    parse_error_message(args);
+                    return;
+                }
+            },
         }
-        8 => {
-            day_8::run();
+    }
+    if days.is_empty() {
+        days = solution::days().map(|(day, _)| day).collect::<Vec<_>>();
+    }
+    log::set_verbosity(verbosity);
+    log::set_days(&log_days);
+    let answers = match Answers::load(&answers_path) {
+        Ok(answers) => answers,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
         }
-        9 => {
-            day_9::run();
+    };
+    // parts without an answer have nothing to check, some are also too slow
+    // to solve on every run
+    let wanted = |day, part| all_parts || answers.get(day, part).is_some();
+    let runs = runner::run_parts(&days, parallel, wanted);
+    let checks = verify::check(&days, runs, &answers);
+    verify::write_report(&mut std::io::stdout(), &checks).unwrap();
+    if !verify::all_passed(&checks) {
+        std::process::exit(1);
+    }
+}
+
+fn run_bench(args: &[String]) {
+    let Some((day, solution)) = args
+        .get(2)
+        .and_then(|day| day.parse::<u8>().ok())
+        .and_then(|day| solution::get(day).map(|solution| (day, solution)))
+    else {
+        parse_error_message(args);
+        return;
+    };
+    let mut parts = Part::BOTH.to_vec();
+    let mut runs = 10;
+    let mut warmup = 2;
+    let mut baseline_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("bench_baseline.csv");
+    let mut threshold = 10.0;
+    // None saves the new timings unless they regressed
+    let mut save = None;
+    let mut rest = args[3..].iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--part" => match rest.next().map(|p| p.parse::<Part>()) {
+                Some(Ok(p)) => {
+                    parts = vec![p];
+                }
+                _ => {
+                    parse_error_message(args);
+                    return;
+                }
+            },
+            "-n" | "--runs" => match rest.next().map(|n| n.parse::<usize>()) {
+                Some(Ok(n)) if n > 0 => {
+                    runs = n;
+                }
+                _ => {
+                    parse_error_message(args);
+                    return;
+                }
+            },
+            "-w" | "--warmup" => match rest.next().map(|n| n.parse::<usize>()) {
+                Some(Ok(n)) => {
+                    warmup = n;
+                }
+                _ => {
+                    parse_error_message(args);
+                    return;
+                }
+            },
+            "-b" | "--baseline" => {
+                let Some(path) = rest.next() else {
+                    parse_error_message(args);
+                    return;
+                };
+                baseline_path = PathBuf::from(path);
+            }
+            "-t" | "--threshold" => match rest.next().map(|t| t.parse::<f64>()) {
+                Some(Ok(t)) if t >= 0.0 => {
+                    threshold = t;
+                }
+                _ => {
+                    parse_error_message(args);
+                    return;
+                }
+            },
+            "--save" | "--update-baseline" => {
+                save = Some(true);
+            }
+            "--no-save" => {
+                save = Some(false);
+            }
+            _ => {
+                parse_error_message(args);
+                return;
+            }
         }
-        10 => {
-            day_10::run();
+    }
+    let mut baseline = match Baseline::load(&baseline_path) {
+        Ok(baseline) => baseline,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
         }
-        11 => {
-            day_11::run();
+    };
+    let input = match input::read(day, &InputSource::Default) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
         }
-        12 => {
-            if debug {
-                day_12::run(day_12::b::RunMode::Time);
-            } else {
-                day_12::run(day_12::b::RunMode::Fast);
-            }
+    };
+    let results = parts
+        .iter()
+        .map(|part| bench::bench_part(day, solution, *part, &input, warmup, runs))
+        .collect::<Result<Vec<_>, _>>();
+    let results = match results {
+        Ok(results) => results,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
         }
-        13 => {
-            day_13::run();
+    };
+    let threshold = threshold / 100.0;
+    bench::write_report(&mut std::io::stdout(), &results, &baseline, threshold).unwrap();
+    let regressed = results
+        .iter()
+        .any(|result| bench::is_regression(bench::compare(result, &baseline), threshold));
+    if regressed && save.is_none() {
+        eprintln!(
+            "not saving {} after a regression, pass --update-baseline to save anyway",
+            baseline_path.display()
+        );
+    }
+    if save.unwrap_or(!regressed) {
+        results.iter().for_each(|result| baseline.update(result));
+        if let Err(e) = baseline.save(&baseline_path) {
+            eprintln!("error: could not save {}: {}", baseline_path.display(), e);
+            std::process::exit(1);
         }
-        14 => {
-            day_14::run();
+    }
+    if regressed {
+        std::process::exit(1);
+    }
+}
+
+/// Write day 25's wiring with its minimum cut as a Graphviz graph
+fn run_dot(args: &[String]) {
+    let mut source = InputSource::Default;
+    let mut output = None;
+    let mut rest = args[2..].iter().peekable();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "-i" | "--input" | "-e" | "--example" => match input_flag(arg, &mut rest) {
+                Some(s) => source = s,
+                None => {
+                    parse_error_message(args);
+                    return;
+                }
+            },
+            "-o" | "--output" => {
+                let Some(path) = rest.next() else {
+                    parse_error_message(args);
+                    return;
+                };
+                output = Some(PathBuf::from(path));
+            }
+            _ => {
+                parse_error_message(args);
+                return;
+            }
         }
-        15 => {
-            day_15::run();
+    }
+    let dot = match input::read(25, &source)
+        .map_err(|e| e.to_string())
+        .and_then(|input| day_25::a::dot(&input).map_err(|e| e.for_day(25).to_string()))
+    {
+        Ok(dot) => dot,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
         }
-        16 => {
-            day_16::run();
+    };
+    match output {
+        Some(path) => {
+            if let Err(e) = std::fs::write(&path, dot) {
+                eprintln!("error: could not write {}: {}", path.display(), e);
+                std::process::exit(1);
+            }
         }
-        17 => {
-            if debug {
-                day_17::a::random(6);
-            } else {
-                day_17::run();
+        None => print!("{}", dot),
+    }
+}
+
+/// Write day 17's crucible path losing the least heat
+fn run_path(args: &[String]) {
+    let mut source = InputSource::Default;
+    let mut crucible = Crucible::NORMAL;
+    let mut format = PathFormat::default();
+    let mut output = None;
+    let mut rest = args[2..].iter().peekable();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "-i" | "--input" | "-e" | "--example" => match input_flag(arg, &mut rest) {
+                Some(s) => source = s,
+                None => {
+                    parse_error_message(args);
+                    return;
+                }
+            },
+            "--part" => match rest.next().map(|p| p.parse::<Part>()) {
+                Some(Ok(Part::A)) => crucible = Crucible::NORMAL,
+                Some(Ok(Part::B)) => crucible = Crucible::ULTRA,
+                _ => {
+                    parse_error_message(args);
+                    return;
+                }
+            },
+            "-f" | "--format" => match rest.next().map(|f| f.parse::<PathFormat>()) {
+                Some(Ok(f)) => format = f,
+                _ => {
+                    parse_error_message(args);
+                    return;
+                }
+            },
+            "-o" | "--output" => {
+                let Some(path) = rest.next() else {
+                    parse_error_message(args);
+                    return;
+                };
+                output = Some(PathBuf::from(path));
+            }
+            _ => {
+                parse_error_message(args);
+                return;
             }
         }
-        18 => {
-            day_18::run();
+    }
+    let rendered = match input::read(17, &source)
+        .map_err(|e| e.to_string())
+        .and_then(|input| {
+            path::render(&input, crucible, format).map_err(|e| e.for_day(17).to_string())
+        }) {
+        Ok(rendered) => rendered,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
         }
-        19 => {
-            day_19::run();
+    };
+    let written = match &output {
+        Some(path) => std::fs::write(path, rendered),
+        None => std::io::stdout().write_all(&rendered),
+    };
+    if let Err(e) = written {
+        let target = output.map_or("stdout".to_string(), |p| p.display().to_string());
+        eprintln!("error: could not write {}: {}", target, e);
+        std::process::exit(1);
+    }
+}
+
+/// Write day 24's hail stepped forward in time, or when the rock hits each
+/// stone, as CSV
+fn run_hail(args: &[String]) {
+    let mut source = InputSource::Default;
+    // the span the stones cross the test area of part a in
+    let (mut t0, mut t1, mut dt) = (0, 1_200_000_000_000, 12_000_000_000);
+    let mut hits = false;
+    let mut output = None;
+    let mut rest = args[2..].iter().peekable();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "-i" | "--input" | "-e" | "--example" => match input_flag(arg, &mut rest) {
+                Some(s) => source = s,
+                None => {
+                    parse_error_message(args);
+                    return;
+                }
+            },
+            "--from" | "--to" | "--step" => match rest.next().map(|t| t.parse::<i64>()) {
+                Some(Ok(t)) => match arg.as_str() {
+                    "--from" => t0 = t,
+                    "--to" => t1 = t,
+                    _ => dt = t,
+                },
+                _ => {
+                    parse_error_message(args);
+                    return;
+                }
+            },
+            "--hits" => {
+                hits = true;
+            }
+            "-o" | "--output" => {
+                let Some(path) = rest.next() else {
+                    parse_error_message(args);
+                    return;
+                };
+                output = Some(PathBuf::from(path));
+            }
+            _ => {
+                parse_error_message(args);
+                return;
+            }
         }
-        20 => {
-            day_20::run();
+    }
+    let hail = match input::read(24, &source)
+        .map_err(|e| e.to_string())
+        .and_then(|input| {
+            input
+                .parse::<HailCloud>()
+                .map_err(|e| e.for_day(24).to_string())
+        }) {
+        Ok(hail) => hail,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
         }
-        21 => {
-            day_21::run();
+    };
+    let mut out: BufWriter<Box<dyn Write>> = match &output {
+        Some(path) => match std::fs::File::create(path) {
+            Ok(file) => BufWriter::new(Box::new(file)),
+            Err(e) => {
+                eprintln!("error: could not write {}: {}", path.display(), e);
+                std::process::exit(1);
+            }
+        },
+        None => BufWriter::new(Box::new(std::io::stdout().lock())),
+    };
+    let written = if hits {
+        hail.hits()
+            .map(|hits| day_24::simulate::write_hits(&mut out, &hits))
+    } else {
+        hail.simulate(t0, t1, dt)
+            .map(|samples| day_24::simulate::write_samples(&mut out, samples))
+    };
+    let written = match written {
+        Ok(written) => written.and_then(|_| out.flush()),
+        Err(e) => {
+            eprintln!("error: {}", e.for_day(24));
+            std::process::exit(1);
         }
-        22 => {
-            day_22::run();
+    };
+    if let Err(e) = written {
+        let target = output.map_or("stdout".to_string(), |p| p.display().to_string());
+        eprintln!("error: could not write {}: {}", target, e);
+        std::process::exit(1);
+    }
+}
+
+/// Write every pulse day 20's modules deliver as JSON lines
+fn run_trace(args: &[String]) {
+    let mut source = InputSource::Default;
+    let mut presses = 1000;
+    let mut output = None;
+    let mut rest = args[2..].iter().peekable();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "-i" | "--input" | "-e" | "--example" => match input_flag(arg, &mut rest) {
+                Some(s) => source = s,
+                None => {
+                    parse_error_message(args);
+                    return;
+                }
+            },
+            "-n" | "--presses" => match rest.next().map(|n| n.parse::<usize>()) {
+                Some(Ok(n)) => presses = n,
+                _ => {
+                    parse_error_message(args);
+                    return;
+                }
+            },
+            "-o" | "--output" => {
+                let Some(path) = rest.next() else {
+                    parse_error_message(args);
+                    return;
+                };
+                output = Some(PathBuf::from(path));
+            }
+            _ => {
+                parse_error_message(args);
+                return;
+            }
         }
-        23 => {
-            day_23::run();
+    }
+    let trace = match input::read(20, &source)
+        .map_err(|e| e.to_string())
+        .and_then(|input| day_20::a::trace(&input, presses).map_err(|e| e.for_day(20).to_string()))
+    {
+        Ok(trace) => trace,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
         }
-        24 => {
-            day_24::run();
+    };
+    let mut jsonl = Vec::new();
+    trace.write_jsonl(&mut jsonl).unwrap();
+    let written = match &output {
+        Some(path) => std::fs::write(path, jsonl),
+        None => std::io::stdout().write_all(&jsonl),
+    };
+    if let Err(e) = written {
+        let target = output.map_or("stdout".to_string(), |p| p.display().to_string());
+        eprintln!("error: could not write {}: {}", target, e);
+        std::process::exit(1);
+    }
+}
+
+/// Print the state of day 20's modules at a press of a recorded trace
+fn run_replay(args: &[String]) {
+    let Some(trace_path) = args.get(2) else {
+        parse_error_message(args);
+        return;
+    };
+    let mut source = InputSource::Default;
+    let mut press = None;
+    let mut rest = args[3..].iter().peekable();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "-i" | "--input" | "-e" | "--example" => match input_flag(arg, &mut rest) {
+                Some(s) => source = s,
+                None => {
+                    parse_error_message(args);
+                    return;
+                }
+            },
+            "--press" => match rest.next().map(|n| n.parse::<usize>()) {
+                Some(Ok(n)) => press = Some(n),
+                _ => {
+                    parse_error_message(args);
+                    return;
+                }
+            },
+            _ => {
+                parse_error_message(args);
+                return;
+            }
         }
-        25 => {
-            day_25::run();
+    }
+    let trace = match std::fs::read_to_string(trace_path)
+        .map_err(|e| format!("could not read {}: {}", trace_path, e))
+        .and_then(|trace| {
+            trace
+                .parse::<Trace>()
+                .map_err(|e| format!("{}: {}", trace_path, e))
+        }) {
+        Ok(trace) => trace,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
         }
-        _ => {
-            panic!();
+    };
+    let press = press.unwrap_or(trace.presses());
+    let states = match input::read(20, &source)
+        .map_err(|e| e.to_string())
+        .and_then(|input| {
+            day_20::a::replay(&input, &trace, press).map_err(|e| e.for_day(20).to_string())
+        }) {
+        Ok(states) => states,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
         }
+    };
+    states.iter().for_each(|state| println!("{}", state));
+}
+
+fn main() {
+    let args = std::env::args().collect::<Vec<_>>();
+    if args.len() < 2 {
+        parse_error_message(&args);
+        return;
+    }
+    match args[1].as_str() {
+        "all" => run_all(&args),
+        "verify" => run_verify(&args),
+        "bench" => run_bench(&args),
+        "dot" => run_dot(&args),
+        "path" => run_path(&args),
+        "hail" => run_hail(&args),
+        "trace" => run_trace(&args),
+        "replay" => run_replay(&args),
+        _ => run_day(&args),
     }
 }
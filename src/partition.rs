@@ -0,0 +1,273 @@
+use std::collections::BTreeMap;
+
+use petgraph::graph::{NodeIndex, UnGraph};
+
+use crate::error::{Error, Result};
+
+/// Undirected graph in compressed sparse row form, the input KaMinPar
+/// partitions
+///
+/// The neighbors of node `i` are `targets[offsets[i]..offsets[i + 1]]`, every
+/// edge shows up once from each end. Parallel edges are merged into one
+/// edge with their weights added up and loops are dropped, a cut never
+/// crosses them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Csr {
+    pub offsets: Vec<u64>,
+    pub targets: Vec<u32>,
+    pub weights: Vec<i64>,
+}
+
+impl Csr {
+    /// Csr of `graph` with each edge weighing `weight`
+    pub fn from_graph<N, E>(graph: &UnGraph<N, E>, weight: impl Fn(&E) -> i64) -> Result<Csr> {
+        let edges = graph.edge_indices().map(|e| {
+            let (a, b) = graph.edge_endpoints(e).unwrap();
+            (a.index(), b.index(), weight(&graph[e]))
+        });
+        Csr::from_edges(graph.node_count(), edges)
+    }
+
+    /// Csr of `node_count` nodes joined by `(a, b, weight)` edges
+    pub fn from_edges(
+        node_count: usize,
+        edges: impl IntoIterator<Item = (usize, usize, i64)>,
+    ) -> Result<Csr> {
+        u32::try_from(node_count).map_err(|_| Error::new("too many nodes to partition"))?;
+        let mut neighbors = vec![BTreeMap::<u32, i64>::new(); node_count];
+        for (a, b, weight) in edges {
+            if a.max(b) >= node_count {
+                return Err(Error::new(format!(
+                    "edge {} - {} leaves the {} nodes",
+                    a, b, node_count
+                )));
+            }
+            if a != b {
+                *neighbors[a].entry(b as u32).or_default() += weight;
+                *neighbors[b].entry(a as u32).or_default() += weight;
+            }
+        }
+        let mut csr = Csr {
+            offsets: vec![0],
+            targets: Vec::new(),
+            weights: Vec::new(),
+        };
+        for node in neighbors {
+            csr.targets.extend(node.keys());
+            csr.weights.extend(node.values());
+            csr.offsets.push(csr.targets.len() as u64);
+        }
+        Ok(csr)
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    /// Number of undirected edges
+    pub fn edge_count(&self) -> usize {
+        self.targets.len() / 2
+    }
+
+    /// Neighbors of `node` with the weight of the edge to them
+    pub fn neighbors(&self, node: usize) -> impl Iterator<Item = (usize, i64)> + '_ {
+        let range = self.offsets[node] as usize..self.offsets[node + 1] as usize;
+        self.targets[range.clone()]
+            .iter()
+            .zip(&self.weights[range])
+            .map(|(&target, &weight)| (target as usize, weight))
+    }
+
+    /// Total weight of the edges between different blocks of `assignment`
+    pub fn edge_cut(&self, assignment: &[u32]) -> i64 {
+        (0..self.node_count())
+            .flat_map(|node| {
+                self.neighbors(node)
+                    .filter(move |&(target, _)| node < target)
+                    .map(move |(target, weight)| (node, target, weight))
+            })
+            .filter(|&(a, b, _)| assignment[a] != assignment[b])
+            .map(|(_, _, weight)| weight)
+            .sum()
+    }
+
+    /// Simple weighted graph with the same edges, what the kaminpar crate
+    /// builds its own Csr from
+    pub fn to_graph(&self) -> UnGraph<(), i64> {
+        let mut graph = UnGraph::with_capacity(self.node_count(), self.edge_count());
+        (0..self.node_count()).for_each(|_| {
+            graph.add_node(());
+        });
+        for node in 0..self.node_count() {
+            for (target, weight) in self.neighbors(node).filter(|&(t, _)| node < t) {
+                graph.add_edge(NodeIndex::new(node), NodeIndex::new(target), weight);
+            }
+        }
+        graph
+    }
+}
+
+/// Assignment of every node to one of `k` blocks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Partition {
+    pub k: u32,
+    pub assignment: Vec<u32>,
+    /// Total weight of the edges between blocks
+    pub edge_cut: i64,
+}
+
+impl Partition {
+    pub fn new(csr: &Csr, k: u32, assignment: Vec<u32>) -> Result<Partition> {
+        if assignment.len() != csr.node_count() {
+            return Err(Error::new(format!(
+                "{} blocks assigned for {} nodes",
+                assignment.len(),
+                csr.node_count()
+            )));
+        }
+        if let Some(block) = assignment.iter().find(|&&b| b >= k) {
+            return Err(Error::new(format!("block {} out of {}", block, k)));
+        }
+        Ok(Partition {
+            k,
+            edge_cut: csr.edge_cut(&assignment),
+            assignment,
+        })
+    }
+
+    /// Number of nodes in each block
+    pub fn block_sizes(&self) -> Vec<usize> {
+        let mut sizes = vec![0; self.k as usize];
+        self.assignment.iter().for_each(|&b| sizes[b as usize] += 1);
+        sizes
+    }
+
+    /// How much bigger the largest block is than a perfectly balanced one,
+    /// 0.03 for 3%
+    pub fn imbalance(&self) -> f64 {
+        let largest = self.block_sizes().into_iter().max().unwrap_or(0);
+        let perfect = self.assignment.len() as f64 / self.k as f64;
+        largest as f64 / perfect.ceil() - 1.0
+    }
+}
+
+/// Balanced k-way partitioning with KaMinPar, needs the `kaminpar` feature
+#[cfg(feature = "kaminpar")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Partitioner {
+    k: u32,
+    imbalance: f64,
+    seed: u64,
+}
+
+#[cfg(feature = "kaminpar")]
+impl Partitioner {
+    /// Partition into `k` blocks that may be 3% bigger than a perfectly
+    /// balanced one
+    pub fn new(k: u32) -> Partitioner {
+        Partitioner {
+            k,
+            imbalance: 0.03,
+            seed: 1,
+        }
+    }
+
+    /// Allowed size of a block over a perfectly balanced one, 0.03 for 3%
+    pub fn imbalance(mut self, imbalance: f64) -> Partitioner {
+        self.imbalance = imbalance;
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Partitioner {
+        self.seed = seed;
+        self
+    }
+
+    /// Partition `csr` into balanced blocks with as few edges between them
+    /// as KaMinPar can find
+    pub fn partition(&self, csr: &Csr) -> Result<Partition> {
+        if self.k == 0 || self.k as usize > csr.node_count() {
+            return Err(Error::new(format!(
+                "can't split {} nodes into {} blocks",
+                csr.node_count(),
+                self.k
+            )));
+        }
+        if self.imbalance.is_nan() || self.imbalance < 0.0 {
+            return Err(Error::new(format!("bad imbalance {}", self.imbalance)));
+        }
+        let assignment = kaminpar::PartitionerBuilder::with_epsilon(self.imbalance)
+            .seed(self.seed)
+            .partition_edge_weighted(&csr.to_graph(), self.k)
+            .map_err(|e| Error::new(format!("kaminpar: {}", e)))?;
+        Partition::new(csr, self.k, assignment)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use petgraph::graph::UnGraph;
+
+    use super::{Csr, Partition};
+
+    /// Two squares joined by two edges, one of them doubled
+    fn csr() -> Csr {
+        let graph = UnGraph::<(), i64>::from_edges([
+            (0, 1, 1),
+            (1, 2, 1),
+            (2, 3, 1),
+            (3, 0, 1),
+            (4, 5, 1),
+            (5, 6, 1),
+            (6, 7, 1),
+            (7, 4, 1),
+            (1, 4, 1),
+            (2, 7, 1),
+            (7, 2, 1),
+            (3, 3, 1),
+        ]);
+        Csr::from_graph(&graph, |w| *w).unwrap()
+    }
+
+    #[test]
+    fn test_csr() {
+        let csr = csr();
+        assert_eq!((csr.node_count(), csr.edge_count()), (8, 10));
+        assert_eq!(csr.offsets[..4], [0, 2, 5, 8]);
+        assert_eq!(
+            csr.neighbors(2).collect::<Vec<_>>(),
+            [(1, 1), (3, 1), (7, 2)]
+        );
+        assert_eq!(csr.to_graph().edge_count(), 10);
+        assert_eq!(
+            Csr::from_edges(2, [(0, 2, 1)]).unwrap_err().to_string(),
+            "edge 0 - 2 leaves the 2 nodes"
+        );
+    }
+
+    #[test]
+    fn test_partition() {
+        let csr = csr();
+        let partition = Partition::new(&csr, 2, vec![0, 0, 0, 0, 1, 1, 1, 1]).unwrap();
+        assert_eq!(partition.edge_cut, 3);
+        assert_eq!(partition.block_sizes(), [4, 4]);
+        assert_eq!(partition.imbalance(), 0.0);
+        let partition = Partition::new(&csr, 3, vec![0, 0, 0, 0, 0, 1, 1, 2]).unwrap();
+        assert_eq!(partition.edge_cut, 5);
+        assert!((partition.imbalance() - 2.0 / 3.0).abs() < 1e-9);
+        assert!(Partition::new(&csr, 2, vec![0, 1, 2, 0, 0, 0, 0, 0]).is_err());
+    }
+
+    #[cfg(feature = "kaminpar")]
+    #[test]
+    fn test_kaminpar() {
+        use super::Partitioner;
+
+        let csr = csr();
+        assert!(Partitioner::new(9).partition(&csr).is_err());
+        assert!(Partitioner::new(2).imbalance(-1.0).partition(&csr).is_err());
+        let partition = Partitioner::new(2).seed(7).partition(&csr).unwrap();
+        assert_eq!(partition.block_sizes(), [4, 4]);
+        assert_eq!(partition.edge_cut, 3);
+    }
+}
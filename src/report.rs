@@ -0,0 +1,247 @@
+use std::{
+    io::{self, Write},
+    str::FromStr,
+    time::Duration,
+};
+
+use crate::runner::{PartResult, PartRun};
+
+/// How results are written by the runner
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    #[default]
+    Text,
+    Json,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err("Unknown format, expected json, csv or text"),
+        }
+    }
+}
+
+pub(crate) fn format_duration(duration: &Duration) -> String {
+    format!("{:.3} ms", duration.as_secs_f64() * 1000.0)
+}
+
+/// Write results as an aligned table followed by a total
+///
+/// A part that failed gets a row with its error, the total only counts the
+/// parts that were solved. `wall` is the elapsed time of the whole run,
+/// which is less than the sum of the parts when they ran in parallel.
+pub fn write_table(
+    out: &mut impl Write,
+    runs: &[PartRun],
+    wall: Option<Duration>,
+) -> io::Result<()> {
+    let answers = runs
+        .iter()
+        .map(|run| match &run.result {
+            Ok(result) => result.answer.to_string(),
+            Err(_) => "error".to_string(),
+        })
+        .collect::<Vec<_>>();
+    let width = answers
+        .iter()
+        .map(|a| a.len())
+        .chain(std::iter::once("answer".len()))
+        .max()
+        .unwrap();
+    writeln!(out, "day part {:>width$} {:>12}", "answer", "time")?;
+    for (run, answer) in runs.iter().zip(answers.iter()) {
+        match &run.result {
+            Ok(result) => writeln!(
+                out,
+                "{:>3} {:>4} {:>width$} {:>12}",
+                run.day,
+                run.part,
+                answer,
+                format_duration(&result.duration)
+            )?,
+            Err(e) => writeln!(
+                out,
+                "{:>3} {:>4} {:>width$}  {}",
+                run.day, run.part, answer, e
+            )?,
+        }
+    }
+    let total = runs
+        .iter()
+        .filter_map(|run| run.result.as_ref().ok())
+        .map(|r| r.duration)
+        .sum::<Duration>();
+    writeln!(
+        out,
+        "{:>8} {:>width$} {:>12}",
+        "total",
+        "",
+        format_duration(&total)
+    )?;
+    if let Some(wall) = wall {
+        writeln!(
+            out,
+            "{:>8} {:>width$} {:>12}",
+            "wall",
+            "",
+            format_duration(&wall)
+        )?;
+    }
+    Ok(())
+}
+
+/// Write results as a JSON array with one object per part
+///
+/// Answers are strings so that every record has the same shape whatever
+/// the day's answer type is.
+pub fn write_json(out: &mut impl Write, results: &[PartResult]) -> io::Result<()> {
+    writeln!(out, "[")?;
+    for (i, result) in results.iter().enumerate() {
+        let separator = if i + 1 == results.len() { "" } else { "," };
+        writeln!(
+            out,
+            "  {{\"day\": {}, \"part\": \"{}\", \"answer\": \"{}\", \"duration_ns\": {}, \"input_hash\": \"{:016x}\"}}{}",
+            result.day,
+            result.part,
+            result.answer,
+            result.duration.as_nanos(),
+            result.input_hash,
+            separator
+        )?;
+    }
+    writeln!(out, "]")
+}
+
+/// Write results as CSV with a header row
+pub fn write_csv(out: &mut impl Write, results: &[PartResult]) -> io::Result<()> {
+    writeln!(out, "day,part,answer,duration_ns,input_hash")?;
+    for result in results {
+        writeln!(
+            out,
+            "{},{},{},{},{:016x}",
+            result.day,
+            result.part,
+            result.answer,
+            result.duration.as_nanos(),
+            result.input_hash
+        )?;
+    }
+    Ok(())
+}
+
+/// Write results in the given format
+///
+/// Only the table has room for parts that failed, JSON and CSV leave them
+/// out for the caller to report.
+pub fn write(
+    out: &mut impl Write,
+    format: Format,
+    runs: &[PartRun],
+    wall: Option<Duration>,
+) -> io::Result<()> {
+    let solved = || {
+        runs.iter()
+            .filter_map(|run| run.result.clone().ok())
+            .collect::<Vec<_>>()
+    };
+    match format {
+        Format::Text => write_table(out, runs, wall),
+        Format::Json => write_json(out, &solved()),
+        Format::Csv => write_csv(out, &solved()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{write, write_table, Format};
+    use crate::{
+        error::Error,
+        runner::{PartResult, PartRun},
+        solution::{Answer, Part},
+    };
+
+    fn results() -> Vec<PartRun> {
+        [
+            (Part::A, Answer::Unsigned(54644), 2),
+            (Part::B, Answer::Signed(-5), 3),
+        ]
+        .into_iter()
+        .map(|(part, answer, millis)| PartRun {
+            day: 1,
+            part,
+            result: Ok(PartResult {
+                day: 1,
+                part,
+                answer,
+                duration: Duration::from_millis(millis),
+                input_hash: 0xff,
+            }),
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_table() {
+        let mut out = Vec::new();
+        write_table(&mut out, &results(), None).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let lines = out.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], "day part answer         time");
+        assert_eq!(lines[1], "  1    a  54644     2.000 ms");
+        assert_eq!(lines[2], "  1    b     -5     3.000 ms");
+        assert_eq!(lines[3], "   total            5.000 ms");
+
+        // a part failing doesn't hide the others or the total
+        let mut runs = results();
+        runs.insert(
+            1,
+            PartRun {
+                day: 2,
+                part: Part::A,
+                result: Err(Error::new("no start").at_line(1).for_day(2)),
+            },
+        );
+        let mut out = Vec::new();
+        write_table(&mut out, &runs, None).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let lines = out.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[1], "  1    a  54644     2.000 ms");
+        assert_eq!(lines[2], "  2    a  error  day 2, line 1: no start");
+        assert_eq!(lines[3], "  1    b     -5     3.000 ms");
+        assert_eq!(lines[4], "   total            5.000 ms");
+    }
+
+    #[test]
+    fn test_json() {
+        let mut out = Vec::new();
+        write(&mut out, Format::Json, &results(), None).unwrap();
+        let expected = r#"[
+  {"day": 1, "part": "a", "answer": "54644", "duration_ns": 2000000, "input_hash": "00000000000000ff"},
+  {"day": 1, "part": "b", "answer": "-5", "duration_ns": 3000000, "input_hash": "00000000000000ff"}
+]
+"#;
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_csv() {
+        let mut out = Vec::new();
+        write(&mut out, "csv".parse().unwrap(), &results(), None).unwrap();
+        let expected = "day,part,answer,duration_ns,input_hash
+1,a,54644,2000000,00000000000000ff
+1,b,-5,3000000,00000000000000ff
+";
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+        assert!("xml".parse::<Format>().is_err());
+    }
+}
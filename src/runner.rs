@@ -0,0 +1,150 @@
+use rayon::prelude::*;
+use std::time::{Duration, Instant};
+
+use crate::{
+    error::{Error, Result},
+    input::{self, InputSource},
+    solution::{self, Answer, Part, Solution},
+};
+
+/// Answer and wall time for one part of one day
+#[derive(Debug, Clone)]
+pub struct PartResult {
+    pub day: u8,
+    pub part: Part,
+    pub answer: Answer,
+    pub duration: Duration,
+    /// FNV-1a hash of the input, to tell apart runs on different data
+    pub input_hash: u64,
+}
+
+/// One part of a day that was run, with its result or why it failed
+#[derive(Debug)]
+pub struct PartRun {
+    pub day: u8,
+    pub part: Part,
+    pub result: Result<PartResult>,
+}
+
+/// 64 bit FNV-1a, stable across builds unlike `DefaultHasher`
+pub fn hash_input(input: &str) -> u64 {
+    input.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Time `solve` on `input` and record it as the result for a day and part
+///
+/// An error from `solve` is tagged with the day.
+pub fn time_part(
+    day: u8,
+    part: Part,
+    input: &str,
+    solve: impl FnOnce(&str) -> Result<Answer>,
+) -> Result<PartResult> {
+    let now = Instant::now();
+    let answer = solve(input).map_err(|e| e.for_day(day))?;
+    Ok(PartResult {
+        day,
+        part,
+        answer,
+        duration: now.elapsed(),
+        input_hash: hash_input(input),
+    })
+}
+
+/// Solve one part and time it
+pub fn run_part(day: u8, solution: &dyn Solution, part: Part, input: &str) -> Result<PartResult> {
+    time_part(day, part, input, |input| solution.solve(part, input))
+}
+
+/// Solve every part of the given days on their checked in data
+///
+/// All inputs are read before any solver starts so that file access isn't
+/// counted in the timings. With `parallel` the parts are spread over the
+/// rayon thread pool, results are still returned in the order of `days`.
+/// Days without a registered solution are skipped. An error, reading a
+/// day's input or solving a part, only fails the parts it belongs to.
+pub fn run_days(days: &[u8], parallel: bool) -> Vec<PartRun> {
+    run_parts(days, parallel, |_, _| true)
+}
+
+/// Like [run_days] but only solve the parts `wanted` picks
+///
+/// Days with no wanted part don't have their input read.
+pub fn run_parts(days: &[u8], parallel: bool, wanted: impl Fn(u8, Part) -> bool) -> Vec<PartRun> {
+    let days = days
+        .iter()
+        .filter_map(|day| solution::get(*day).map(|solution| (*day, solution)))
+        .map(|(day, solution)| {
+            let parts = Part::BOTH
+                .into_iter()
+                .filter(|part| wanted(day, *part))
+                .collect::<Vec<_>>();
+            (day, solution, parts)
+        })
+        .filter(|(_, _, parts)| !parts.is_empty())
+        .collect::<Vec<_>>();
+    let inputs = days
+        .iter()
+        .map(|(day, _, _)| {
+            input::read(*day, &InputSource::Default)
+                .map_err(|e| Error::new(e.to_string()).for_day(*day))
+        })
+        .collect::<Vec<_>>();
+    let jobs = days
+        .iter()
+        .zip(inputs.iter())
+        .flat_map(|((day, solution, parts), input)| {
+            parts
+                .iter()
+                .map(move |part| (*day, *solution, *part, input))
+        })
+        .collect::<Vec<_>>();
+    let run = |&(day, solution, part, input): &(u8, &dyn Solution, Part, &Result<String>)| {
+        let result = match input {
+            Ok(input) => run_part(day, solution, part, input),
+            Err(e) => Err(e.clone()),
+        };
+        PartRun { day, part, result }
+    };
+    if parallel {
+        jobs.par_iter().map(run).collect::<Vec<_>>()
+    } else {
+        jobs.iter().map(run).collect::<Vec<_>>()
+    }
+}
+
+/// Solve every part of every day on its checked in data
+pub fn run_all(parallel: bool) -> Vec<PartRun> {
+    let days = solution::days().map(|(day, _)| day).collect::<Vec<_>>();
+    run_days(&days, parallel)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{hash_input, run_part};
+    use crate::solution::{self, Answer, Part};
+
+    #[test]
+    fn test_run_part() {
+        let input = include_str!("day_9/example_data.txt");
+        let result = run_part(9, solution::get(9).unwrap(), Part::A, input).unwrap();
+        assert_eq!(result.day, 9);
+        assert_eq!(result.part, Part::A);
+        assert_eq!(result.answer, Answer::Signed(114));
+        assert_eq!(result.input_hash, hash_input(input));
+        let error = run_part(9, solution::get(9).unwrap(), Part::A, "0 3 six").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "day 9, line 1, column 5: could not parse `six`"
+        );
+    }
+
+    #[test]
+    fn test_hash_input() {
+        assert_eq!(hash_input(""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(hash_input("a"), 0xaf63_dc4c_8601_ec8c);
+        assert_ne!(hash_input("ab"), hash_input("ba"));
+    }
+}
@@ -0,0 +1,396 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::{BuildHasherDefault, Hash, Hasher};
+
+/// Shortest path problem for [a_star] and [dijkstra]
+pub trait Problem {
+    type State: Copy + Eq + Hash;
+
+    /// States one move away from `state`, each with the cost of moving
+    /// there
+    fn successors(&self, state: &Self::State) -> impl Iterator<Item = (Self::State, u64)>;
+
+    /// Lower bound of the cost from `state` to a goal, A* only finds the
+    /// shortest path when it never overestimates and never drops by more
+    /// than the cost of a move
+    fn heuristic(&self, _state: &Self::State) -> u64 {
+        0
+    }
+
+    fn is_goal(&self, state: &Self::State) -> bool;
+}
+
+/// Problem numbering every one of its states below [Numbered::state_count],
+/// letting [dense_a_star] and [dense_dijkstra] keep their bookkeeping in
+/// arrays instead of a hash map
+pub trait Numbered: Problem {
+    fn state_count(&self) -> usize;
+
+    /// Number of `state`, below the state count
+    fn index(&self, state: &Self::State) -> usize;
+
+    /// State numbered `index`, the inverse of [Numbered::index]
+    fn state(&self, index: usize) -> Self::State;
+}
+
+/// Shortest path to a goal and its cost
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Found<S> {
+    /// From the start the path left from to the goal
+    pub path: Vec<S>,
+    pub cost: u64,
+}
+
+/// Outcome of a search, `expanded` counts the states whose successors were
+/// looked at to compare heuristics by
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Search<S> {
+    pub found: Option<Found<S>>,
+    pub expanded: usize,
+}
+
+/// Shortest path from any of `starts`, each with the cost of getting
+/// there, to a goal, trying states in order of cost plus heuristic
+pub fn a_star<P: Problem>(
+    problem: &P,
+    starts: impl IntoIterator<Item = (P::State, u64)>,
+) -> Search<P::State> {
+    search(problem, starts, |state| problem.heuristic(state))
+}
+
+/// Shortest path like [a_star] with the heuristic ignored
+pub fn dijkstra<P: Problem>(
+    problem: &P,
+    starts: impl IntoIterator<Item = (P::State, u64)>,
+) -> Search<P::State> {
+    search(problem, starts, |_| 0)
+}
+
+/// Shortest path like [a_star] through numbered states
+pub fn dense_a_star<P: Numbered>(
+    problem: &P,
+    starts: impl IntoIterator<Item = (P::State, u64)>,
+) -> Search<P::State> {
+    search_with(problem, Dense::new(problem), starts, |state| {
+        problem.heuristic(state)
+    })
+}
+
+/// Shortest path like [dijkstra] through numbered states
+pub fn dense_dijkstra<P: Numbered>(
+    problem: &P,
+    starts: impl IntoIterator<Item = (P::State, u64)>,
+) -> Search<P::State> {
+    search_with(problem, Dense::new(problem), starts, |_| 0)
+}
+
+/// Best known cost of each state and the state it was reached from, by
+/// state number so the heap only holds numbers
+trait Book<S> {
+    /// Lower the cost of `state` to `cost` if that is better, returning its
+    /// number if it was
+    fn relax(&mut self, state: S, cost: u64, previous: Option<usize>) -> Option<usize>;
+
+    fn cost(&self, id: usize) -> u64;
+
+    fn state(&self, id: usize) -> S;
+
+    fn previous(&self, id: usize) -> Option<usize>;
+
+    /// Path from a start to state number `id`
+    fn path(&self, mut id: usize) -> Vec<S> {
+        let mut path = vec![self.state(id)];
+        while let Some(previous) = self.previous(id) {
+            path.push(self.state(previous));
+            id = previous;
+        }
+        path.reverse();
+        path
+    }
+}
+
+/// Best known cost of a state and the state it was reached from
+struct Entry<S> {
+    state: S,
+    cost: u64,
+    previous: Option<usize>,
+}
+
+/// Multiply and rotate hash of whole words at a time, much quicker than
+/// the default for the small states searches go through by the million
+#[derive(Default)]
+struct StateHasher(u64);
+
+impl Hasher for StateHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        bytes.iter().for_each(|&b| self.write_u64(b as u64));
+    }
+
+    fn write_u64(&mut self, word: u64) {
+        self.0 = (self.0.rotate_left(5) ^ word).wrapping_mul(0x51_7c_c1_b7_27_22_0a_95);
+    }
+
+    fn write_u8(&mut self, word: u8) {
+        self.write_u64(word as u64);
+    }
+
+    fn write_u32(&mut self, word: u32) {
+        self.write_u64(word as u64);
+    }
+
+    fn write_usize(&mut self, word: usize) {
+        self.write_u64(word as u64);
+    }
+
+    fn write_isize(&mut self, word: isize) {
+        self.write_u64(word as u64);
+    }
+}
+
+/// States numbered in the order they are found, for problems that can't
+/// number them
+struct Entries<S> {
+    ids: HashMap<S, usize, BuildHasherDefault<StateHasher>>,
+    entries: Vec<Entry<S>>,
+}
+
+impl<S: Copy + Eq + Hash> Book<S> for Entries<S> {
+    fn relax(&mut self, state: S, cost: u64, previous: Option<usize>) -> Option<usize> {
+        let entries = &mut self.entries;
+        let id = *self.ids.entry(state).or_insert_with(|| {
+            assert!(u32::try_from(entries.len()).is_ok(), "too many states");
+            entries.push(Entry {
+                state,
+                cost: u64::MAX,
+                previous: None,
+            });
+            entries.len() - 1
+        });
+        let entry = &mut entries[id];
+        if cost >= entry.cost {
+            return None;
+        }
+        entry.cost = cost;
+        entry.previous = previous;
+        Some(id)
+    }
+
+    fn cost(&self, id: usize) -> u64 {
+        self.entries[id].cost
+    }
+
+    fn state(&self, id: usize) -> S {
+        self.entries[id].state
+    }
+
+    fn previous(&self, id: usize) -> Option<usize> {
+        self.entries[id].previous
+    }
+}
+
+/// Costs and previous states in arrays by [Numbered::index]
+struct Dense<'a, P> {
+    problem: &'a P,
+    costs: Vec<u64>,
+    /// Index of the previous state, MAX for a start
+    previous: Vec<u32>,
+}
+
+impl<'a, P: Numbered> Dense<'a, P> {
+    fn new(problem: &'a P) -> Dense<'a, P> {
+        let count = problem.state_count();
+        assert!(u32::try_from(count).is_ok(), "too many states");
+        Dense {
+            problem,
+            costs: vec![u64::MAX; count],
+            previous: vec![u32::MAX; count],
+        }
+    }
+}
+
+impl<P: Numbered> Book<P::State> for Dense<'_, P> {
+    fn relax(&mut self, state: P::State, cost: u64, previous: Option<usize>) -> Option<usize> {
+        let id = self.problem.index(&state);
+        if cost >= self.costs[id] {
+            return None;
+        }
+        self.costs[id] = cost;
+        self.previous[id] = previous.map_or(u32::MAX, |previous| previous as u32);
+        Some(id)
+    }
+
+    fn cost(&self, id: usize) -> u64 {
+        self.costs[id]
+    }
+
+    fn state(&self, id: usize) -> P::State {
+        self.problem.state(id)
+    }
+
+    fn previous(&self, id: usize) -> Option<usize> {
+        let previous = self.previous[id];
+        (previous != u32::MAX).then_some(previous as usize)
+    }
+}
+
+fn search<P: Problem>(
+    problem: &P,
+    starts: impl IntoIterator<Item = (P::State, u64)>,
+    heuristic: impl Fn(&P::State) -> u64,
+) -> Search<P::State> {
+    let entries = Entries {
+        ids: HashMap::default(),
+        entries: Vec::new(),
+    };
+    search_with(problem, entries, starts, heuristic)
+}
+
+/// Bits of a heap key below the estimated total cost
+const TIE_BITS: u32 = 16;
+
+/// Heap key ordering states by estimated total cost `cost + estimate`,
+/// and among equal totals the one estimated closest to a goal, which is
+/// the furthest from the start
+///
+/// Packing both into one word keeps heap entries small, which matters
+/// more than anything else once states are numbered densely.
+fn heap_key(cost: u64, estimate: u64) -> u64 {
+    let total = cost + estimate;
+    assert!(total < 1 << (64 - TIE_BITS), "cost {} too large", total);
+    total << TIE_BITS | estimate.min((1 << TIE_BITS) - 1)
+}
+
+fn search_with<P: Problem>(
+    problem: &P,
+    mut book: impl Book<P::State>,
+    starts: impl IntoIterator<Item = (P::State, u64)>,
+    heuristic: impl Fn(&P::State) -> u64,
+) -> Search<P::State> {
+    let mut heap = BinaryHeap::new();
+    for (state, cost) in starts {
+        if let Some(id) = book.relax(state, cost, None) {
+            heap.push(Reverse((heap_key(cost, heuristic(&state)), id as u32)));
+        }
+    }
+    let mut expanded = 0;
+    while let Some(Reverse((key, id))) = heap.pop() {
+        let id = id as usize;
+        let (state, cost) = (book.state(id), book.cost(id));
+        // a cheaper way there was found after this entry was pushed
+        if key > heap_key(cost, heuristic(&state)) {
+            continue;
+        }
+        if problem.is_goal(&state) {
+            return Search {
+                found: Some(Found {
+                    path: book.path(id),
+                    cost,
+                }),
+                expanded,
+            };
+        }
+        expanded += 1;
+        for (next, step_cost) in problem.successors(&state) {
+            let next_cost = cost + step_cost;
+            if let Some(next_id) = book.relax(next, next_cost, Some(id)) {
+                let key = heap_key(next_cost, heuristic(&next));
+                heap.push(Reverse((key, next_id as u32)));
+            }
+        }
+    }
+    Search {
+        found: None,
+        expanded,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{a_star, dense_a_star, dense_dijkstra, dijkstra, Found, Numbered, Problem};
+    use crate::geometry::{Direction, Point2};
+
+    /// Walls on a grid, every step costs 1
+    struct Maze {
+        walls: Vec<&'static str>,
+        end: Point2<usize>,
+    }
+
+    impl Problem for Maze {
+        type State = Point2<usize>;
+
+        fn successors(&self, state: &Point2<usize>) -> impl Iterator<Item = (Point2<usize>, u64)> {
+            Direction::ALL
+                .into_iter()
+                .filter_map(|d| state.step(d))
+                .filter(|p| p.within(self.walls.len(), self.walls[0].len()))
+                .filter(|p| self.walls[p.row].as_bytes()[p.col] != b'#')
+                .map(|p| (p, 1))
+        }
+
+        fn heuristic(&self, state: &Point2<usize>) -> u64 {
+            state.manhattan(&self.end) as u64
+        }
+
+        fn is_goal(&self, state: &Point2<usize>) -> bool {
+            *state == self.end
+        }
+    }
+
+    /// Cells numbered row by row
+    impl Numbered for Maze {
+        fn state_count(&self) -> usize {
+            self.walls.len() * self.walls[0].len()
+        }
+
+        fn index(&self, state: &Point2<usize>) -> usize {
+            state.row * self.walls[0].len() + state.col
+        }
+
+        fn state(&self, index: usize) -> Point2<usize> {
+            let ncols = self.walls[0].len();
+            Point2::new(index / ncols, index % ncols)
+        }
+    }
+
+    #[test]
+    fn test_search() {
+        let maze = Maze {
+            walls: vec!["....#...", ".##.#.#.", ".#..#.#.", ".#.##.#.", "......#."],
+            end: Point2::new(0, 7),
+        };
+        let start = [(Point2::new(4, 0), 0)];
+        let guided = a_star(&maze, start);
+        let blind = dijkstra(&maze, start);
+        let Some(Found { path, cost }) = guided.found.clone() else {
+            panic!("no path");
+        };
+        assert_eq!(cost, 11);
+        assert_eq!(path.len(), 12);
+        assert_eq!((path[0], path[11]), (Point2::new(4, 0), Point2::new(0, 7)));
+        assert!(path.windows(2).all(|w| w[0].manhattan(&w[1]) == 1));
+        assert_eq!(blind.found.map(|f| f.cost), Some(11));
+        assert_eq!(dense_a_star(&maze, start), guided);
+        assert_eq!(dense_dijkstra(&maze, start).found.map(|f| f.cost), Some(11));
+
+        let open = Maze {
+            walls: vec!["......"; 6],
+            end: Point2::new(5, 5),
+        };
+        let start = [(Point2::new(0, 0), 0)];
+        let (guided, blind) = (a_star(&open, start), dijkstra(&open, start));
+        assert_eq!(guided.found.map(|f| f.cost), Some(10));
+        assert_eq!(blind.found.map(|f| f.cost), Some(10));
+        assert!(guided.expanded < blind.expanded);
+
+        let walled = Maze {
+            walls: vec![".#.", "##."],
+            end: Point2::new(1, 2),
+        };
+        let search = a_star(&walled, start.map(|_| (Point2::new(0, 0), 0)));
+        assert_eq!((search.found, search.expanded), (None, 1));
+    }
+}
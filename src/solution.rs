@@ -0,0 +1,164 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::{
+    day_1, day_10, day_11, day_12, day_13, day_14, day_15, day_16, day_17, day_18, day_19, day_2,
+    day_20, day_21, day_22, day_23, day_24, day_25, day_3, day_4, day_5, day_6, day_7, day_8,
+    day_9, error::Result,
+};
+
+/// Answer to one part of a day's puzzle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Answer {
+    Signed(i64),
+    Unsigned(u64),
+}
+
+impl From<u32> for Answer {
+    fn from(value: u32) -> Self {
+        Answer::Unsigned(value as u64)
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(value: u64) -> Self {
+        Answer::Unsigned(value)
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(value: usize) -> Self {
+        Answer::Unsigned(value as u64)
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(value: i64) -> Self {
+        Answer::Signed(value)
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Signed(x) => x.fmt(f),
+            Answer::Unsigned(x) => x.fmt(f),
+        }
+    }
+}
+
+/// One of the two parts of a day's puzzle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Part {
+    A,
+    B,
+}
+
+impl Part {
+    pub const BOTH: [Part; 2] = [Part::A, Part::B];
+}
+
+impl FromStr for Part {
+    type Err = &'static str;
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "a" => Ok(Part::A),
+            "b" => Ok(Part::B),
+            _ => Err("Unknown part, expected a or b"),
+        }
+    }
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Part::A => f.pad("a"),
+            Part::B => f.pad("b"),
+        }
+    }
+}
+
+/// Solver for both parts of a day's puzzle
+///
+/// Input that can't be parsed is reported as an error rather than a panic.
+pub trait Solution: Sync {
+    fn part_a(&self, input: &str) -> Result<Answer>;
+    fn part_b(&self, input: &str) -> Result<Answer>;
+
+    fn solve(&self, part: Part, input: &str) -> Result<Answer> {
+        match part {
+            Part::A => self.part_a(input),
+            Part::B => self.part_b(input),
+        }
+    }
+}
+
+/// Every day's solution, day 1 first
+pub static SOLUTIONS: [&dyn Solution; 25] = [
+    &day_1::Day1,
+    &day_2::Day2,
+    &day_3::Day3,
+    &day_4::Day4,
+    &day_5::Day5,
+    &day_6::Day6,
+    &day_7::Day7,
+    &day_8::Day8,
+    &day_9::Day9,
+    &day_10::Day10,
+    &day_11::Day11,
+    &day_12::Day12,
+    &day_13::Day13,
+    &day_14::Day14,
+    &day_15::Day15,
+    &day_16::Day16,
+    &day_17::Day17,
+    &day_18::Day18,
+    &day_19::Day19,
+    &day_20::Day20,
+    &day_21::Day21,
+    &day_22::Day22,
+    &day_23::Day23,
+    &day_24::Day24,
+    &day_25::Day25,
+];
+
+/// Look up the solution for a day, counting from 1
+pub fn get(day: u8) -> Option<&'static dyn Solution> {
+    if day == 0 {
+        return None;
+    }
+    SOLUTIONS.get(day as usize - 1).copied()
+}
+
+/// Iterate over `(day, solution)` pairs in calendar order
+pub fn days() -> impl Iterator<Item = (u8, &'static dyn Solution)> {
+    SOLUTIONS
+        .iter()
+        .enumerate()
+        .map(|(i, solution)| (i as u8 + 1, *solution))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{days, get, Answer, Part};
+
+    #[test]
+    fn test_registry() {
+        assert!(get(0).is_none());
+        assert!(get(26).is_none());
+        assert_eq!(days().count(), 25);
+        let input = include_str!("day_9/example_data.txt");
+        let day_9 = get(9).unwrap();
+        assert_eq!(day_9.part_a(input), Ok(Answer::Signed(114)));
+        assert_eq!(day_9.part_b(input), Ok(Answer::Signed(2)));
+        assert_eq!(day_9.solve(Part::B, input), Ok(Answer::Signed(2)));
+        assert!(day_9.part_a("1 2 x").is_err());
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Answer::from(-3_i64).to_string(), "-3");
+        assert_eq!(Answer::from(42_usize).to_string(), "42");
+        assert_eq!("b".parse::<Part>(), Ok(Part::B));
+        assert!("c".parse::<Part>().is_err());
+    }
+}
@@ -0,0 +1,342 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{self, Write},
+    path::Path,
+    str::FromStr,
+};
+
+use crate::{
+    error::Error,
+    runner::{PartResult, PartRun},
+    solution::Part,
+};
+
+/// Expected answers read from an `answers.toml` file
+///
+/// Only the small part of TOML needed here is understood, one table per
+/// day holding the answer for each part:
+///
+/// ```toml
+/// [day_8]
+/// a = 16343
+/// b = "15299095336639"
+/// ```
+#[derive(Debug, Default)]
+pub struct Answers {
+    expected: BTreeMap<(u8, Part), String>,
+}
+
+impl Answers {
+    pub fn load(path: &Path) -> Result<Answers, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("could not read answers file {}: {}", path.display(), e))?;
+        text.parse::<Answers>()
+            .map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn get(&self, day: u8, part: Part) -> Option<&str> {
+        self.expected.get(&(day, part)).map(|s| s.as_str())
+    }
+}
+
+/// `line` without its comment, a `#` inside a string doesn't start one
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+/// Parse a TOML value that is either a bare integer or a basic string
+fn parse_value(s: &str) -> Option<String> {
+    if let Some(s) = s.strip_prefix('"') {
+        let s = s.strip_suffix('"')?;
+        if s.contains('"') {
+            return None;
+        }
+        Some(s.to_string())
+    } else {
+        let s = s.replace('_', "");
+        s.parse::<i128>().ok()?;
+        Some(s)
+    }
+}
+
+impl FromStr for Answers {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut expected = BTreeMap::new();
+        let mut day = None;
+        for (iline, line) in s.lines().enumerate() {
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+            let error = |message: &str| Error::new(message).at_line(iline + 1);
+            if let Some(table) = line.strip_prefix('[') {
+                let table = table
+                    .strip_suffix(']')
+                    .ok_or_else(|| error("unterminated table header"))?;
+                let num = table
+                    .trim()
+                    .strip_prefix("day_")
+                    .and_then(|num| num.parse::<u8>().ok())
+                    .ok_or_else(|| error("expected a table named like [day_8]"))?;
+                day = Some(num);
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| error("expected `a = ...` or `b = ...`"))?;
+            let part = match key.trim() {
+                "a" => Part::A,
+                "b" => Part::B,
+                _ => return Err(error("expected key a or b")),
+            };
+            let day = day.ok_or_else(|| error("answer outside of a [day_N] table"))?;
+            let value =
+                parse_value(value.trim()).ok_or_else(|| error("expected integer or string"))?;
+            if expected.insert((day, part), value).is_some() {
+                return Err(error("duplicate answer"));
+            }
+        }
+        Ok(Answers { expected })
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Fail {
+        expected: String,
+    },
+    /// The part failed to run, which fails it whether or not it has an
+    /// answer
+    Error {
+        message: String,
+    },
+    Missing,
+}
+
+impl Status {
+    fn failed(&self) -> bool {
+        matches!(self, Status::Fail { .. } | Status::Error { .. })
+    }
+}
+
+/// Outcome of comparing one part of a day with the expected answers
+#[derive(Debug)]
+pub struct Check {
+    pub day: u8,
+    pub part: Part,
+    /// None when the part wasn't run or failed to
+    pub result: Option<PartResult>,
+    pub status: Status,
+}
+
+/// Check both parts of each of `days` against the answers
+///
+/// Parts missing from `runs` weren't run, they pass only if there is no
+/// answer to compare with either.
+pub fn check(days: &[u8], runs: Vec<PartRun>, answers: &Answers) -> Vec<Check> {
+    let mut runs = runs.into_iter().peekable();
+    days.iter()
+        .flat_map(|day| Part::BOTH.map(|part| (*day, part)))
+        .map(|(day, part)| {
+            let result = runs
+                .next_if(|r| r.day == day && r.part == part)
+                .map(|run| run.result);
+            let status = match (answers.get(day, part), &result) {
+                (_, Some(Err(e))) => Status::Error {
+                    message: e.to_string(),
+                },
+                (None, _) => Status::Missing,
+                (Some(expected), Some(Ok(result))) if expected == result.answer.to_string() => {
+                    Status::Pass
+                }
+                (Some(expected), _) => Status::Fail {
+                    expected: expected.to_string(),
+                },
+            };
+            Check {
+                day,
+                part,
+                result: result.and_then(|result| result.ok()),
+                status,
+            }
+        })
+        .collect::<Vec<_>>()
+}
+
+/// True when no check failed, missing answers don't count as failures
+pub fn all_passed(checks: &[Check]) -> bool {
+    checks.iter().all(|check| !check.status.failed())
+}
+
+/// Write one line per check followed by a summary
+pub fn write_report(out: &mut impl Write, checks: &[Check]) -> io::Result<()> {
+    for check in checks {
+        let answer = match &check.result {
+            Some(result) => result.answer.to_string(),
+            None => "not run".to_string(),
+        };
+        match &check.status {
+            Status::Pass => writeln!(out, "day {} {} pass {}", check.day, check.part, answer)?,
+            Status::Fail { expected } => writeln!(
+                out,
+                "day {} {} FAIL {} expected {}",
+                check.day, check.part, answer, expected
+            )?,
+            Status::Error { message } => {
+                writeln!(out, "day {} {} FAIL {}", check.day, check.part, message)?
+            }
+            Status::Missing => {
+                writeln!(out, "day {} {} missing {}", check.day, check.part, answer)?
+            }
+        }
+    }
+    let count = |f: fn(&Status) -> bool| checks.iter().filter(|c| f(&c.status)).count();
+    writeln!(
+        out,
+        "{} passed, {} failed, {} missing",
+        count(|s| *s == Status::Pass),
+        count(Status::failed),
+        count(|s| *s == Status::Missing)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{all_passed, check, write_report, Answers, Status};
+    use crate::{
+        error::Error,
+        runner::{PartResult, PartRun},
+        solution::{Answer, Part},
+    };
+
+    fn result(day: u8, part: Part, answer: Answer) -> PartRun {
+        PartRun {
+            day,
+            part,
+            result: Ok(PartResult {
+                day,
+                part,
+                answer,
+                duration: Duration::ZERO,
+                input_hash: 0,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let input = r##"
+# answers for the checked in data
+[day_8]
+a = 16343
+b = "15299095336639" # too big to remember
+
+[day_10]
+a = "#1 # not a comment" # a comment
+
+[ day_9 ]
+b = -1_000
+"##;
+        let answers = input.parse::<Answers>().unwrap();
+        assert_eq!(answers.get(8, Part::A), Some("16343"));
+        assert_eq!(answers.get(8, Part::B), Some("15299095336639"));
+        assert_eq!(answers.get(9, Part::A), None);
+        assert_eq!(answers.get(9, Part::B), Some("-1000"));
+        assert_eq!(answers.get(10, Part::A), Some("#1 # not a comment"));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            "a = 1".parse::<Answers>().unwrap_err().to_string(),
+            "line 1: answer outside of a [day_N] table"
+        );
+        assert_eq!(
+            "[day_1]\nc = 1".parse::<Answers>().unwrap_err().to_string(),
+            "line 2: expected key a or b"
+        );
+        assert!("[day_1]\na = 1\na = 2".parse::<Answers>().is_err());
+        assert!("[day_1]\na = one".parse::<Answers>().is_err());
+        assert!("[week_1]".parse::<Answers>().is_err());
+    }
+
+    #[test]
+    fn test_check() {
+        let answers = "[day_1]\na = 3\nb = 4".parse::<Answers>().unwrap();
+        let checks = check(
+            &[1, 2],
+            vec![
+                result(1, Part::A, Answer::Unsigned(3)),
+                result(1, Part::B, Answer::Signed(5)),
+                result(2, Part::A, Answer::Unsigned(7)),
+            ],
+            &answers,
+        );
+        assert_eq!(checks[0].status, Status::Pass);
+        assert_eq!(
+            checks[1].status,
+            Status::Fail {
+                expected: "4".to_string()
+            }
+        );
+        assert_eq!(checks[2].status, Status::Missing);
+        assert_eq!(checks[3].status, Status::Missing);
+        assert!(checks[3].result.is_none());
+        assert!(!all_passed(&checks));
+        assert!(all_passed(&checks[0..1]));
+        assert!(all_passed(&checks[2..]));
+
+        let checks = check(
+            &[1],
+            vec![result(1, Part::B, Answer::Unsigned(4))],
+            &answers,
+        );
+        assert!(checks[0].result.is_none());
+        assert!(!all_passed(&checks));
+        assert_eq!(checks[1].status, Status::Pass);
+
+        // a part failing to run fails without stopping the others
+        let failed = PartRun {
+            day: 1,
+            part: Part::A,
+            result: Err(Error::new("no mirror").at_line(3).for_day(1)),
+        };
+        let checks = check(
+            &[1, 2],
+            vec![
+                failed,
+                result(1, Part::B, Answer::Unsigned(4)),
+                result(2, Part::A, Answer::Unsigned(7)),
+            ],
+            &answers,
+        );
+        assert!(checks[0].result.is_none());
+        assert_eq!(checks[1].status, Status::Pass);
+        assert!(!all_passed(&checks));
+        let mut out = Vec::new();
+        write_report(&mut out, &checks).unwrap();
+        let expected = "day 1 a FAIL day 1, line 3: no mirror
+day 1 b pass 4
+day 2 a missing 7
+day 2 b missing not run
+1 passed, 1 failed, 2 missing
+";
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+    }
+}